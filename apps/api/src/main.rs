@@ -1,8 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use gasguard_engine::{ContractScanner, ScanAnalyzer, TieredScanner, UserUsage, UsageTier};
+use colored::*;
+use gasguard_engine::{ContractScanner, FixEngine, SarifEmitter, ScanAnalyzer, TieredScanner, UserUsage, UsageTier};
 use std::path::PathBuf;
-use serde_json;
 
 #[derive(Parser)]
 #[command(name = "gasguard")]
@@ -19,7 +19,7 @@ enum Commands {
     Scan {
         /// Path to Rust file to scan
         file: PathBuf,
-        /// Output format (console, json)
+        /// Output format (console, json, sarif)
         #[arg(short, long, default_value = "console")]
         format: String,
     },
@@ -27,7 +27,7 @@ enum Commands {
     ScanDir {
         /// Path to directory to scan
         directory: PathBuf,
-        /// Output format (console, json)
+        /// Output format (console, json, sarif)
         #[arg(short, long, default_value = "console")]
         format: String,
     },
@@ -36,6 +36,43 @@ enum Commands {
         /// Path to Rust file or directory to analyze
         path: PathBuf,
     },
+    /// List the storage keys a Soroban contract reads and writes
+    Storage {
+        /// Path to Soroban contract file
+        file: PathBuf,
+        /// Output format (console, json)
+        #[arg(short, long, default_value = "console")]
+        format: String,
+    },
+    /// Estimate the ledger footprint, CPU weight and fee of each entry point of a Soroban contract
+    Cost {
+        /// Path to Soroban contract file
+        file: PathBuf,
+        /// Output format (console, json)
+        #[arg(short, long, default_value = "console")]
+        format: String,
+    },
+    /// Report which entry points of a Soroban contract its tests never call, or only call with mock_all_auths
+    Coverage {
+        /// Path to Soroban contract file
+        contract: PathBuf,
+        /// Path to a test file or a directory of test files
+        tests: PathBuf,
+        /// Output format (console, json)
+        #[arg(short, long, default_value = "console")]
+        format: String,
+    },
+    /// Apply automatic fixes to a file or every file in a directory
+    Fix {
+        /// Path to contract file or directory to fix
+        path: PathBuf,
+        /// Print a unified diff instead of modifying files
+        #[arg(long)]
+        diff: bool,
+        /// Also apply fixes that may change behavior and need review
+        #[arg(long = "unsafe")]
+        include_unsafe: bool,
+    },
     /// Scan with tiered pricing
     TieredScan {
         /// Path to Rust file to scan
@@ -64,35 +101,44 @@ enum Commands {
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
-    let scanner = ContractScanner::new();
 
     match cli.command {
         Commands::Scan { file, format } => {
-            println!("🔍 Scanning file: {:?}", file);
+            eprintln!("🔍 Scanning file: {:?}", file);
 
+            let scanner = ContractScanner::for_path(&file)?;
             let result = scanner.scan_file(&file)?;
 
             match format.as_str() {
                 "json" => {
                     println!("{}", result.to_json()?);
                 }
+                "sarif" => {
+                    let results = [result];
+                    println!("{}", SarifEmitter::to_sarif_string(&results, scanner.registry())?);
+                }
                 _ => {
                     println!("{}", ScanAnalyzer::format_violations(&result.violations));
                     println!("{}", ScanAnalyzer::generate_summary(&result.violations));
 
+                    if !result.suppressed.is_empty() {
+                        println!("🔇 {} findings suppressed by gasguard-ignore comments", result.suppressed.len());
+                    }
+
                     if !result.violations.is_empty() {
-                        let savings = ScanAnalyzer::calculate_storage_savings(&result.violations);
+                        let savings = scanner.storage_savings(std::slice::from_ref(&result));
                         println!("\n{}", savings);
                     }
                 }
             }
         }
         Commands::ScanDir { directory, format } => {
-            println!("🔍 Scanning directory: {:?}", directory);
+            eprintln!("🔍 Scanning directory: {:?}", directory);
 
+            let scanner = ContractScanner::for_path(&directory)?;
             let results = scanner.scan_directory(&directory)?;
 
-            if results.is_empty() {
+            if results.is_empty() && format == "console" {
                 println!("✅ No violations found in any files!");
                 return Ok(());
             }
@@ -103,6 +149,9 @@ async fn main() -> Result<()> {
                 "json" => {
                     println!("{}", serde_json::to_string_pretty(&results)?);
                 }
+                "sarif" => {
+                    println!("{}", SarifEmitter::to_sarif_string(&results, scanner.registry())?);
+                }
                 _ => {
                     for result in &results {
                         println!("\n📁 File: {}", result.source);
@@ -119,11 +168,103 @@ async fn main() -> Result<()> {
                         .bold()
                     );
 
-                    let all_violations: Vec<_> =
-                        results.iter().flat_map(|r| r.violations.clone()).collect();
-                    let savings = ScanAnalyzer::calculate_storage_savings(&all_violations);
-                    println!("\n{}", savings);
+                    let total_suppressed: usize = results.iter().map(|r| r.suppressed.len()).sum();
+                    if total_suppressed > 0 {
+                        println!("🔇 {} findings suppressed by gasguard-ignore comments", total_suppressed);
+                    }
+
+                    println!("\n{}", scanner.storage_savings(&results));
+                }
+            }
+        }
+        Commands::Analyze { path } => {
+            println!("🔍 Analyzing storage optimization potential: {:?}", path);
+
+            let scanner = ContractScanner::for_path(&path)?;
+            let results = if path.is_dir() {
+                scanner.scan_directory(&path)?
+            } else {
+                vec![scanner.scan_file(&path)?]
+            };
+
+            let all_violations: Vec<_> =
+                results.iter().flat_map(|r| r.violations.clone()).collect();
+
+            println!("{}", ScanAnalyzer::generate_summary(&all_violations));
+            println!("\n{}", scanner.storage_savings(&results));
+        }
+        Commands::Storage { file, format } => {
+            let inventory = ContractScanner::storage_inventory(&file)?;
+
+            match format.as_str() {
+                "json" => {
+                    println!("{}", serde_json::to_string_pretty(&inventory)?);
                 }
+                _ => {
+                    print!("{}", inventory);
+                }
+            }
+        }
+        Commands::Cost { file, format } => {
+            let scanner = ContractScanner::for_path(&file)?;
+            let cost = scanner.estimate_costs(&file)?;
+
+            match format.as_str() {
+                "json" => {
+                    println!("{}", serde_json::to_string_pretty(&cost)?);
+                }
+                _ => {
+                    print!("{}", cost);
+                }
+            }
+        }
+        Commands::Coverage { contract, tests, format } => {
+            let coverage = ContractScanner::test_coverage(&contract, &tests)?;
+
+            match format.as_str() {
+                "json" => {
+                    println!("{}", serde_json::to_string_pretty(&coverage)?);
+                }
+                _ => {
+                    print!("{}", coverage);
+                }
+            }
+        }
+        Commands::Fix { path, diff, include_unsafe } => {
+            let scanner = ContractScanner::for_path(&path)?;
+            let results = if path.is_dir() {
+                scanner.scan_directory(&path)?
+            } else {
+                vec![scanner.scan_file(&path)?]
+            };
+
+            let (mut applied, mut skipped, mut files) = (0, 0, 0);
+            for result in &results {
+                let original = std::fs::read_to_string(&result.source)?;
+                let outcome = FixEngine::apply(&original, &result.violations, include_unsafe);
+                skipped += outcome.skipped;
+                if outcome.applied == 0 {
+                    continue;
+                }
+
+                applied += outcome.applied;
+                files += 1;
+                if diff {
+                    print!("{}", FixEngine::unified_diff(&result.source, &original, &outcome.fixed));
+                } else {
+                    std::fs::write(&result.source, &outcome.fixed)?;
+                    println!("🔧 Fixed {} issue(s) in {}", outcome.applied, result.source);
+                }
+            }
+
+            if !diff {
+                println!("{}", format!("✅ Applied {} fixes across {} files", applied, files).bold());
+            }
+            if skipped > 0 {
+                eprintln!(
+                    "⚠️  {} fixes need review or conflict with another fix; rerun with --unsafe to apply the ones needing review",
+                    skipped
+                );
             }
         }
         Commands::TieredScan { file, tier, usage, format } => {
@@ -184,7 +325,8 @@ async fn main() -> Result<()> {
                     
                     if !result.base_result.violations.is_empty() {
                         println!("\n{}", ScanAnalyzer::format_violations(&result.base_result.violations));
-                        let savings = ScanAnalyzer::calculate_storage_savings(&result.base_result.violations);
+                        let scanner = ContractScanner::for_path(&file)?;
+                        let savings = scanner.storage_savings(std::slice::from_ref(&result.base_result));
                         println!("\n{}", savings);
                     }
                     
@@ -131,6 +131,7 @@ impl OptimizedContract {
             return Err(DemoError::Unauthorized); // Reuse error for pause state
         }
 
+        if env.ledger().timestamp() > deadline {
             return Err(DemoError::TransactionExpired);
         }
         
@@ -339,8 +340,6 @@ impl OptimizedContract {
         Ok(())
     }
 }
-}
-}
 
 /// Health status structure for comprehensive monitoring
 #[contracttype]
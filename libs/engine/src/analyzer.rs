@@ -1,4 +1,6 @@
+use crate::cost::CostModel;
 use colored::*;
+use gasguard_rules::soroban::SorobanContract;
 use gasguard_rules::{RuleViolation, ViolationSeverity};
 use std::fmt;
 
@@ -58,24 +60,39 @@ impl ScanAnalyzer {
         )
     }
 
+    /// Storage savings of unused-state findings, priced with the latest fee schedule
+    /// and assuming values of unknown size
     pub fn calculate_storage_savings(violations: &[RuleViolation]) -> StorageSavings {
-        let mut unused_vars = 0;
-        let mut estimated_savings_kb = 0.0;
+        Self::estimate_storage_savings(violations, &CostModel::default(), None)
+    }
+
+    /// Storage savings of unused-state findings priced with `model`, sized from the
+    /// types declared in `contract` when the findings come from a Soroban contract;
+    /// every other finding is counted as not priced
+    pub fn estimate_storage_savings(
+        violations: &[RuleViolation],
+        model: &CostModel,
+        contract: Option<&SorobanContract>,
+    ) -> StorageSavings {
+        let mut savings = StorageSavings {
+            unused_variables: 0,
+            unpriced_findings: 0,
+            estimated_savings_kb: 0.0,
+            monthly_ledger_rent_savings: 0.0,
+            fee_schedule: model.schedule().version.clone(),
+        };
 
         for violation in violations {
-            if violation.rule_name == "unused-state-variable" {
-                unused_vars += 1;
-                // Estimate average storage cost per unused variable
-                // This is a rough estimate - actual costs vary by type
-                estimated_savings_kb += 2.5; // Average variable size in KB
+            if let Some(saved) = model.violation_savings(contract, violation) {
+                savings.unused_variables += 1;
+                savings.estimated_savings_kb += saved.bytes as f64 / 1024.0;
+                savings.monthly_ledger_rent_savings += saved.monthly_rent;
+            } else {
+                savings.unpriced_findings += 1;
             }
         }
 
-        StorageSavings {
-            unused_variables: unused_vars,
-            estimated_savings_kb,
-            monthly_ledger_rent_savings: estimated_savings_kb * 0.001, // Rough estimate
-        }
+        savings
     }
 
     fn categorize_violations(
@@ -112,9 +129,10 @@ impl ScanAnalyzer {
         };
 
         format!(
-            "{}\n  📍 Line {}: {}\n  📝 {}\n  💡 {}\n\n",
+            "{}\n  📍 Line {}:{}: {}\n  📝 {}\n  💡 {}\n\n",
             format!("  [{}]", severity).color(severity_color).bold(),
             violation.line_number,
+            violation.column_number,
             violation.variable_name.bold(),
             violation.description,
             violation.suggestion.italic()
@@ -125,18 +143,42 @@ impl ScanAnalyzer {
 #[derive(Debug)]
 pub struct StorageSavings {
     pub unused_variables: usize,
+    /// Findings without a savings estimate: only unused state is priced, not
+    /// storage tier, repeated access or loop findings
+    pub unpriced_findings: usize,
     pub estimated_savings_kb: f64,
     pub monthly_ledger_rent_savings: f64,
+    /// Version of the fee schedule the rent was computed with
+    pub fee_schedule: String,
+}
+
+impl StorageSavings {
+    /// Add the savings of another set of findings
+    pub fn merge(&mut self, other: StorageSavings) {
+        self.unused_variables += other.unused_variables;
+        self.unpriced_findings += other.unpriced_findings;
+        self.estimated_savings_kb += other.estimated_savings_kb;
+        self.monthly_ledger_rent_savings += other.monthly_ledger_rent_savings;
+    }
 }
 
 impl fmt::Display for StorageSavings {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "💰 Storage Optimization Potential:\n   • {} unused state variables\n   • {:.1} KB storage savings\n   • {:.4} XLM/month ledger rent savings",
+            "💰 Storage Optimization Potential:\n   • {} unused state variables\n   • {:.2} KB storage savings\n   • {:.4} XLM/month ledger rent savings (fee schedule {})",
             self.unused_variables,
             self.estimated_savings_kb,
-            self.monthly_ledger_rent_savings
-        )
+            self.monthly_ledger_rent_savings,
+            self.fee_schedule
+        )?;
+        if self.unpriced_findings > 0 {
+            write!(
+                f,
+                "\n   • {} other findings not priced: only unused state has a storage estimate",
+                self.unpriced_findings
+            )?;
+        }
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,287 @@
+//! Project configuration
+//!
+//! Loads `gasguard.toml`, discovered from the scanned path upward, which lets a
+//! project choose its rules, severities, thresholds and scanned paths:
+//!
+//! ```toml
+//! [rules]
+//! enabled = []                          # optional allow-list, empty means all rules
+//! disabled = ["soroban-admin-pattern"]
+//!
+//! [rules.severity]
+//! soroban-unbounded-loop = "medium"
+//!
+//! [rules.thresholds.soroban-wasteful-ttl-extension]
+//! max_threshold_percent = 80
+//!
+//! [paths]
+//! include = ["contracts/**/*.rs"]
+//! exclude = ["**/test_*.rs"]
+//!
+//! [fees]
+//! schedule = "estimate-v1"              # optional, the latest schedule by default
+//! fee_write_1kb = 0.00012
+//! ```
+
+use crate::cost::FeeSchedule;
+use anyhow::{anyhow, bail, Context, Result};
+use gasguard_rules::ViolationSeverity;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+/// File name looked up by [`GasGuardConfig::discover`]
+pub const CONFIG_FILE_NAME: &str = "gasguard.toml";
+
+/// Settings read from `gasguard.toml`
+#[derive(Debug, Clone, Default)]
+pub struct GasGuardConfig {
+    /// Directory containing the config file; path globs are relative to it
+    pub root: Option<PathBuf>,
+    /// Rule IDs to run exclusively, all rules when empty
+    pub enabled_rules: Vec<String>,
+    /// Rule IDs never to run
+    pub disabled_rules: Vec<String>,
+    /// Severity reported for each rule ID instead of its default
+    pub severity_overrides: BTreeMap<String, ViolationSeverity>,
+    /// Named numeric thresholds for each rule ID
+    pub thresholds: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Globs of files to scan, all files when empty
+    pub include: Vec<glob::Pattern>,
+    /// Globs of files to skip
+    pub exclude: Vec<glob::Pattern>,
+    /// Version of the fee schedule costs are estimated with, the latest when unset
+    pub fee_schedule: Option<String>,
+    /// Fee schedule values replaced by name
+    pub fee_overrides: BTreeMap<String, f64>,
+}
+
+impl GasGuardConfig {
+    /// Find the nearest `gasguard.toml` in `start` or one of its ancestors
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        let start = if start.is_file() { start.parent().unwrap_or(start) } else { start };
+        let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+
+        for dir in start.ancestors() {
+            let candidate = dir.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Self::load(&candidate).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Load a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config: {:?}", path))?;
+        let mut config = Self::from_toml(&text).with_context(|| format!("Invalid config: {:?}", path))?;
+        config.root = path.parent().map(Path::to_path_buf);
+        Ok(config)
+    }
+
+    /// Parse config file contents
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let document: DocumentMut = text.parse()?;
+        let mut config = Self::default();
+
+        for (key, item) in document.as_table().iter() {
+            match key {
+                "rules" => config.read_rules(table(item, "rules")?)?,
+                "paths" => config.read_paths(table(item, "paths")?)?,
+                "fees" => config.read_fees(table(item, "fees")?)?,
+                _ => bail!("unknown section '{}'", key),
+            }
+        }
+
+        // Reject unknown schedules and values up front rather than at scan time
+        config.fee_schedule()?;
+        Ok(config)
+    }
+
+    fn read_rules(&mut self, rules: &dyn TableLike) -> Result<()> {
+        for (key, item) in rules.iter() {
+            match key {
+                "enabled" => self.enabled_rules = strings(item, "rules.enabled")?,
+                "disabled" => self.disabled_rules = strings(item, "rules.disabled")?,
+                "severity" => {
+                    for (rule_id, severity) in table(item, "rules.severity")?.iter() {
+                        let severity = severity
+                            .as_str()
+                            .ok_or_else(|| anyhow!("rules.severity.{} must be a string", rule_id))?
+                            .parse()
+                            .map_err(|e: String| anyhow!("rules.severity.{}: {}", rule_id, e))?;
+                        self.severity_overrides.insert(rule_id.to_string(), severity);
+                    }
+                }
+                "thresholds" => {
+                    for (rule_id, values) in table(item, "rules.thresholds")?.iter() {
+                        let context = format!("rules.thresholds.{}", rule_id);
+                        let mut rule_thresholds = BTreeMap::new();
+                        for (name, value) in table(values, &context)?.iter() {
+                            let value = value
+                                .as_integer()
+                                .and_then(|value| u64::try_from(value).ok())
+                                .ok_or_else(|| anyhow!("{}.{} must be a non-negative integer", context, name))?;
+                            rule_thresholds.insert(name.to_string(), value);
+                        }
+                        self.thresholds.insert(rule_id.to_string(), rule_thresholds);
+                    }
+                }
+                _ => bail!("unknown key 'rules.{}'", key),
+            }
+        }
+        Ok(())
+    }
+
+    fn read_paths(&mut self, paths: &dyn TableLike) -> Result<()> {
+        for (key, item) in paths.iter() {
+            let patterns = strings(item, &format!("paths.{}", key))?
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(pattern).with_context(|| format!("invalid glob '{}' in paths.{}", pattern, key))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            match key {
+                "include" => self.include = patterns,
+                "exclude" => self.exclude = patterns,
+                _ => bail!("unknown key 'paths.{}'", key),
+            }
+        }
+        Ok(())
+    }
+
+    fn read_fees(&mut self, fees: &dyn TableLike) -> Result<()> {
+        for (key, item) in fees.iter() {
+            if key == "schedule" {
+                let version = item.as_str().ok_or_else(|| anyhow!("fees.schedule must be a string"))?;
+                self.fee_schedule = Some(version.to_string());
+                continue;
+            }
+            let value = item
+                .as_float()
+                .or_else(|| item.as_integer().map(|value| value as f64))
+                .filter(|value| *value >= 0.0)
+                .ok_or_else(|| anyhow!("fees.{} must be a non-negative number", key))?;
+            self.fee_overrides.insert(key.to_string(), value);
+        }
+        Ok(())
+    }
+
+    /// The fee schedule selected by the `[fees]` table
+    pub fn fee_schedule(&self) -> Result<FeeSchedule> {
+        FeeSchedule::with_overrides(self.fee_schedule.as_deref().unwrap_or("latest"), &self.fee_overrides)
+    }
+
+    /// Whether findings of `rule_id` should be reported
+    pub fn is_rule_enabled(&self, rule_id: &str) -> bool {
+        (self.enabled_rules.is_empty() || self.enabled_rules.iter().any(|id| id == rule_id))
+            && !self.disabled_rules.iter().any(|id| id == rule_id)
+    }
+
+    /// Whether `path` is selected by the include and exclude globs
+    pub fn includes_path(&self, path: &Path) -> bool {
+        let relative = self
+            .root
+            .as_ref()
+            .and_then(|root| {
+                let path = path.canonicalize().ok()?;
+                path.strip_prefix(root.canonicalize().ok()?).ok().map(Path::to_path_buf)
+            })
+            .unwrap_or_else(|| path.to_path_buf());
+
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches_path(&relative)))
+            && !self.exclude.iter().any(|pattern| pattern.matches_path(&relative))
+    }
+}
+
+fn table<'a>(item: &'a Item, context: &str) -> Result<&'a dyn TableLike> {
+    item.as_table_like().ok_or_else(|| anyhow!("'{}' must be a table", context))
+}
+
+fn strings(item: &Item, context: &str) -> Result<Vec<String>> {
+    item.as_array()
+        .ok_or_else(|| anyhow!("'{}' must be an array of strings", context))?
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("'{}' must be an array of strings", context))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_config() {
+        let config = GasGuardConfig::from_toml(
+            r#"
+[rules]
+enabled = []
+disabled = ["soroban-admin-pattern"]
+
+[rules.severity]
+soroban-unbounded-loop = "medium"
+
+[rules.thresholds.soroban-wasteful-ttl-extension]
+max_threshold_percent = 80
+
+[paths]
+include = ["contracts/**/*.rs"]
+exclude = ["**/test_*.rs"]
+
+[fees]
+fee_write_1kb = 0.00012
+"#,
+        )
+        .unwrap();
+
+        assert!(!config.is_rule_enabled("soroban-admin-pattern"));
+        assert!(config.is_rule_enabled("soroban-unbounded-loop"));
+        assert!(matches!(
+            config.severity_overrides.get("soroban-unbounded-loop"),
+            Some(ViolationSeverity::Medium)
+        ));
+        assert_eq!(config.thresholds["soroban-wasteful-ttl-extension"]["max_threshold_percent"], 80);
+        assert!(config.includes_path(Path::new("contracts/token/lib.rs")));
+        assert!(!config.includes_path(Path::new("contracts/token/test_lib.rs")));
+        assert!(!config.includes_path(Path::new("scripts/deploy.rs")));
+        assert_eq!(config.fee_schedule().unwrap().fee_write_1kb, 0.00012);
+    }
+
+    #[test]
+    fn test_enabled_list_is_an_allow_list() {
+        let config = GasGuardConfig::from_toml("[rules]\nenabled = [\"soroban-unbounded-loop\"]\n").unwrap();
+        assert!(config.is_rule_enabled("soroban-unbounded-loop"));
+        assert!(!config.is_rule_enabled("soroban-expensive-strings"));
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        assert!(GasGuardConfig::from_toml("[rule]\ndisabled = []\n").is_err());
+        assert!(GasGuardConfig::from_toml("[rules.severity]\nx = \"critical\"\n").is_err());
+        assert!(GasGuardConfig::from_toml("[rules.thresholds.x]\nmax = -1\n").is_err());
+        assert!(GasGuardConfig::from_toml("[rules]\ndisabled = \"x\"\n").is_err());
+        assert!(GasGuardConfig::from_toml("[fees]\nschedule = \"testnet\"\n").is_err());
+        assert!(GasGuardConfig::from_toml("[fees]\nfee_gas = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_discover_walks_up_from_scanned_path() {
+        let root = std::env::temp_dir().join(format!("gasguard-config-{}", std::process::id()));
+        let nested = root.join("contracts").join("token");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join(CONFIG_FILE_NAME), "[rules]\ndisabled = [\"a\"]\n").unwrap();
+
+        let config = GasGuardConfig::discover(&nested).unwrap().unwrap();
+        assert!(!config.is_rule_enabled("a"));
+        assert_eq!(config.root.unwrap(), root.canonicalize().unwrap());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,579 @@
+//! Soroban resource cost model
+//!
+//! Estimates, for each entry point of a Soroban contract, the ledger entries it
+//! reads and writes, the bytes those entries hold and a relative CPU weight, and
+//! prices them with a [`FeeSchedule`]. The estimate is static: entry sizes come
+//! from the `#[contracttype]` types stored under each key and loops are assumed
+//! to run a fixed number of iterations, so the numbers are for comparing
+//! functions and findings, not for setting transaction fees.
+
+use anyhow::{bail, Result};
+use gasguard_rules::soroban::{
+    CallGraph, CallKind, ExternalCall, InventoryKey, KeyKind, SorobanContract, SorobanFunction, StorageInventory,
+    StorageKey, StorageTier,
+};
+use gasguard_rules::{RuleViolation, SourceSpan};
+use serde::Serialize;
+use std::fmt;
+
+/// Rules reporting state that takes up ledger space without being used
+const UNUSED_STATE_RULES: &[&str] = &["unused-state-variables", "soroban-unused-state-variables"];
+
+/// Deepest loop nesting the instruction estimate multiplies through
+const MAX_LOOP_DEPTH: u32 = 3;
+
+/// Deepest type nesting entry sizes are computed through
+const MAX_TYPE_DEPTH: usize = 8;
+
+/// Prices and weights the cost model charges with
+///
+/// Fees are in XLM. Built-in schedules are looked up by version with
+/// [`FeeSchedule::version`] and individual values can be overridden by name
+/// with [`FeeSchedule::set`], e.g. from the `[fees]` table of `gasguard.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeSchedule {
+    /// Name of the schedule, with `+overrides` appended once a value is changed
+    pub version: String,
+    /// Fee per 10,000 CPU instructions
+    pub fee_per_10k_instructions: f64,
+    /// Fee per ledger entry in the footprint
+    pub fee_read_entry: f64,
+    /// Fee per ledger entry written
+    pub fee_write_entry: f64,
+    /// Fee per KB read
+    pub fee_read_1kb: f64,
+    /// Fee per KB written, also the base of rent
+    pub fee_write_1kb: f64,
+    /// Rent for a persistent entry is `fee_write_1kb * KB * ledgers / persistent_rent_rate`
+    pub persistent_rent_rate: f64,
+    /// Rent divisor for temporary entries
+    pub temporary_rent_rate: f64,
+    /// Ledgers closed in 30 days
+    pub ledgers_per_month: f64,
+    /// Bytes of a ledger entry besides its key and value
+    pub entry_overhead_bytes: f64,
+    /// Bytes assumed for values of unknown or variable size
+    pub unknown_type_bytes: f64,
+    /// Instructions to set up an invocation, including instantiating the contract
+    pub invocation_instructions: f64,
+    /// Instructions per storage access
+    pub storage_access_instructions: f64,
+    /// Instructions per other host function call
+    pub host_call_instructions: f64,
+    /// Instructions per call into another contract
+    pub external_call_instructions: f64,
+    /// Iterations assumed for each loop
+    pub loop_iterations: f64,
+}
+
+impl FeeSchedule {
+    /// Versions of the built-in schedules, latest last
+    pub const VERSIONS: &'static [&'static str] = &["estimate-v1"];
+
+    /// A built-in schedule, `latest` naming the most recent one
+    pub fn version(version: &str) -> Option<Self> {
+        let version = match version {
+            "latest" => Self::VERSIONS[Self::VERSIONS.len() - 1],
+            version => version,
+        };
+        match version {
+            // Only the rent rates follow the network's protocol 20 settings; the
+            // fees and instruction counts are relative weights, not network values
+            "estimate-v1" => Some(Self {
+                version: version.to_string(),
+                fee_per_10k_instructions: 0.00001,
+                fee_read_entry: 0.0001,
+                fee_write_entry: 0.0002,
+                fee_read_1kb: 0.00005,
+                fee_write_1kb: 0.0001,
+                persistent_rent_rate: 2103.0,
+                temporary_rent_rate: 4206.0,
+                ledgers_per_month: 518_400.0,
+                entry_overhead_bytes: 48.0,
+                unknown_type_bytes: 64.0,
+                invocation_instructions: 1_000_000.0,
+                storage_access_instructions: 50_000.0,
+                host_call_instructions: 5_000.0,
+                external_call_instructions: 1_000_000.0,
+                loop_iterations: 10.0,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Override one value by field name; returns false if there is no such value
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        let field = match name {
+            "fee_per_10k_instructions" => &mut self.fee_per_10k_instructions,
+            "fee_read_entry" => &mut self.fee_read_entry,
+            "fee_write_entry" => &mut self.fee_write_entry,
+            "fee_read_1kb" => &mut self.fee_read_1kb,
+            "fee_write_1kb" => &mut self.fee_write_1kb,
+            "persistent_rent_rate" => &mut self.persistent_rent_rate,
+            "temporary_rent_rate" => &mut self.temporary_rent_rate,
+            "ledgers_per_month" => &mut self.ledgers_per_month,
+            "entry_overhead_bytes" => &mut self.entry_overhead_bytes,
+            "unknown_type_bytes" => &mut self.unknown_type_bytes,
+            "invocation_instructions" => &mut self.invocation_instructions,
+            "storage_access_instructions" => &mut self.storage_access_instructions,
+            "host_call_instructions" => &mut self.host_call_instructions,
+            "external_call_instructions" => &mut self.external_call_instructions,
+            "loop_iterations" => &mut self.loop_iterations,
+            _ => return false,
+        };
+        *field = value;
+        if !self.version.ends_with("+overrides") {
+            self.version.push_str("+overrides");
+        }
+        true
+    }
+
+    /// A built-in schedule with values overridden by name
+    pub fn with_overrides<'a>(version: &str, overrides: impl IntoIterator<Item = (&'a String, &'a f64)>) -> Result<Self> {
+        let Some(mut schedule) = Self::version(version) else {
+            bail!("unknown fee schedule '{}', expected one of: latest, {}", version, Self::VERSIONS.join(", "));
+        };
+        for (name, value) in overrides {
+            if !schedule.set(name, *value) {
+                bail!("fee schedule has no value '{}'", name);
+            }
+        }
+        Ok(schedule)
+    }
+
+    /// Rent for keeping `bytes` in `tier` for 30 days
+    pub fn monthly_rent(&self, tier: StorageTier, bytes: f64) -> f64 {
+        let rate = match tier {
+            StorageTier::Temporary => self.temporary_rent_rate,
+            StorageTier::Persistent | StorageTier::Instance => self.persistent_rent_rate,
+        };
+        self.fee_write_1kb * (bytes / 1024.0) * self.ledgers_per_month / rate
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self::version("latest").expect("latest fee schedule exists")
+    }
+}
+
+/// A ledger entry in an entry point's footprint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FootprintEntry {
+    /// Storage key, or `instance` for the contract instance holding all instance storage
+    pub key: String,
+    pub tier: StorageTier,
+    /// Estimated size of the entry
+    pub bytes: u64,
+    pub written: bool,
+}
+
+/// Estimated resources and fee of one entry point
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryPointCost {
+    pub function: String,
+    /// Ledger entries the entry point reads or writes, besides the contract code
+    pub footprint: Vec<FootprintEntry>,
+    /// Footprint entries only read
+    pub read_entries: u64,
+    /// Footprint entries written
+    pub write_entries: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// Relative CPU weight, in estimated instructions
+    pub instructions: u64,
+    /// Estimated resource fee in XLM
+    pub fee: f64,
+}
+
+/// Estimated costs of every entry point of a contract
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContractCost {
+    pub contract: String,
+    /// Version of the fee schedule the fees were computed with
+    pub fee_schedule: String,
+    pub entry_points: Vec<EntryPointCost>,
+}
+
+/// Ledger space a finding would free up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ViolationSavings {
+    pub bytes: u64,
+    /// Rent saved per month in XLM
+    pub monthly_rent: f64,
+}
+
+/// Static Soroban cost model
+#[derive(Debug, Clone, Default)]
+pub struct CostModel {
+    schedule: FeeSchedule,
+}
+
+impl CostModel {
+    pub fn new(schedule: FeeSchedule) -> Self {
+        Self { schedule }
+    }
+
+    /// The fee schedule the model prices with
+    pub fn schedule(&self) -> &FeeSchedule {
+        &self.schedule
+    }
+
+    /// Estimate the resources and fee of every entry point of `contract`
+    pub fn estimate(&self, contract: &SorobanContract) -> ContractCost {
+        let graph = CallGraph::build(contract);
+        let inventory = StorageInventory::build(contract);
+
+        ContractCost {
+            contract: contract.name.clone(),
+            fee_schedule: self.schedule.version.clone(),
+            entry_points: graph
+                .entry_points()
+                .map(|function| self.estimate_entry_point(contract, &graph, &inventory, function))
+                .collect(),
+        }
+    }
+
+    fn estimate_entry_point(
+        &self,
+        contract: &SorobanContract,
+        graph: &CallGraph<'_>,
+        inventory: &StorageInventory,
+        entry_point: &SorobanFunction,
+    ) -> EntryPointCost {
+        let schedule = &self.schedule;
+        // The contract instance is always loaded, with all of instance storage in it
+        let instance_bytes = schedule.entry_overhead_bytes
+            + inventory
+                .keys
+                .iter()
+                .filter(|key| key.tier == StorageTier::Instance)
+                .map(|key| self.key_bytes(contract, key) - schedule.entry_overhead_bytes)
+                .sum::<f64>();
+        let mut footprint = vec![FootprintEntry {
+            key: "instance".to_string(),
+            tier: StorageTier::Instance,
+            bytes: instance_bytes.round() as u64,
+            written: false,
+        }];
+        let mut instructions = schedule.invocation_instructions;
+
+        for function in graph.reachable_from(entry_point) {
+            for access in &function.body.storage_accesses {
+                instructions += schedule.storage_access_instructions * self.loop_factor(function, access.span);
+
+                let (name, bytes) = if access.tier == StorageTier::Instance {
+                    ("instance".to_string(), instance_bytes)
+                } else {
+                    let Some(key) = StorageKey::resolve(function, access) else { continue };
+                    let bytes = inventory
+                        .keys
+                        .iter()
+                        .find(|entry| entry.key == key.name && entry.tier == access.tier)
+                        .map_or(schedule.entry_overhead_bytes + schedule.unknown_type_bytes * 2.0, |entry| {
+                            self.key_bytes(contract, entry)
+                        });
+                    (key.name, bytes)
+                };
+                let written = access.operation.is_write();
+                match footprint.iter_mut().find(|entry| entry.key == name && entry.tier == access.tier) {
+                    Some(entry) => entry.written |= written,
+                    None => footprint.push(FootprintEntry {
+                        key: name,
+                        tier: access.tier,
+                        bytes: bytes.round() as u64,
+                        written,
+                    }),
+                }
+            }
+
+            let external = ExternalCall::find(function);
+            for call in &function.body.calls {
+                let weight = if external.iter().any(|external| std::ptr::eq(external.call, call)) {
+                    schedule.external_call_instructions
+                } else if is_host_call(call) {
+                    schedule.host_call_instructions
+                } else {
+                    continue;
+                };
+                instructions += weight * self.loop_factor(function, call.span);
+            }
+        }
+
+        let write_entries = footprint.iter().filter(|entry| entry.written).count() as u64;
+        let read_entries = footprint.len() as u64 - write_entries;
+        let read_bytes: u64 = footprint.iter().map(|entry| entry.bytes).sum();
+        let write_bytes: u64 = footprint.iter().filter(|entry| entry.written).map(|entry| entry.bytes).sum();
+
+        // Every footprint entry is read, including those also written
+        let fee = instructions / 10_000.0 * schedule.fee_per_10k_instructions
+            + footprint.len() as f64 * schedule.fee_read_entry
+            + write_entries as f64 * schedule.fee_write_entry
+            + read_bytes as f64 / 1024.0 * schedule.fee_read_1kb
+            + write_bytes as f64 / 1024.0 * schedule.fee_write_1kb;
+
+        EntryPointCost {
+            function: entry_point.name.clone(),
+            footprint,
+            read_entries,
+            write_entries,
+            read_bytes,
+            write_bytes,
+            instructions: instructions.round() as u64,
+            fee,
+        }
+    }
+
+    /// How many times code at `span` runs per call of `function`
+    fn loop_factor(&self, function: &SorobanFunction, span: SourceSpan) -> f64 {
+        let depth = function.body.loops.iter().filter(|lp| lp.repeats(&span)).count() as u32;
+        self.schedule.loop_iterations.powi(depth.min(MAX_LOOP_DEPTH) as i32)
+    }
+
+    /// Estimated size of the ledger entry under `key`
+    pub fn key_bytes(&self, contract: &SorobanContract, key: &InventoryKey) -> f64 {
+        let key_bytes = match key.kind {
+            // A vector of the variant name and its fields
+            KeyKind::Variant => {
+                8.0 + 16.0
+                    + key
+                        .key_types
+                        .iter()
+                        .map(|type_name| self.type_bytes(contract, type_name))
+                        .sum::<f64>()
+            }
+            KeyKind::Symbol => 16.0,
+            KeyKind::Other => self.schedule.unknown_type_bytes,
+        };
+        let value_bytes = key
+            .value_type
+            .as_deref()
+            .map_or(self.schedule.unknown_type_bytes, |type_name| self.type_bytes(contract, type_name));
+        self.schedule.entry_overhead_bytes + key_bytes + value_bytes
+    }
+
+    /// Estimated XDR size of a value of `type_name`
+    pub fn type_bytes(&self, contract: &SorobanContract, type_name: &str) -> f64 {
+        self.type_bytes_at(contract, type_name, 0)
+    }
+
+    fn type_bytes_at(&self, contract: &SorobanContract, type_name: &str, depth: usize) -> f64 {
+        let unknown = self.schedule.unknown_type_bytes;
+        if depth > MAX_TYPE_DEPTH {
+            return unknown;
+        }
+        let type_name = type_name.trim().trim_start_matches('&').trim();
+        let (base, generics) = match type_name.split_once('<') {
+            Some((base, rest)) => (base.trim(), rest.strip_suffix('>').unwrap_or(rest).trim()),
+            None => (type_name, ""),
+        };
+        let base = base.rsplit("::").next().unwrap_or(base);
+
+        match base {
+            "()" | "bool" | "u32" | "i32" => 8.0,
+            "u64" | "i64" | "Timepoint" | "Duration" => 12.0,
+            "u128" | "i128" => 20.0,
+            "U256" | "I256" | "u256" | "i256" => 36.0,
+            "Address" => 40.0,
+            "Symbol" => 16.0,
+            "BytesN" => 8.0 + generics.parse::<f64>().unwrap_or(unknown),
+            "Option" => self.type_bytes_at(contract, generics, depth + 1),
+            _ => {
+                if let Some(definition) = contract.contract_types.iter().find(|definition| definition.name == base) {
+                    // A map from field name symbols to values
+                    return 8.0
+                        + definition
+                            .fields
+                            .iter()
+                            .map(|field| 16.0 + self.type_bytes_at(contract, &field.type_name, depth + 1))
+                            .sum::<f64>();
+                }
+                if let Some(definition) = contract.enums.iter().find(|definition| definition.name == base) {
+                    let largest = definition
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            variant
+                                .fields
+                                .iter()
+                                .map(|field| self.type_bytes_at(contract, field, depth + 1))
+                                .sum::<f64>()
+                        })
+                        .fold(0.0, f64::max);
+                    return 8.0 + 16.0 + largest;
+                }
+                unknown
+            }
+        }
+    }
+
+    /// Ledger space and rent removing the state a finding reports would save
+    pub fn violation_savings(&self, contract: Option<&SorobanContract>, violation: &RuleViolation) -> Option<ViolationSavings> {
+        if !UNUSED_STATE_RULES.contains(&violation.rule_name.as_str()) {
+            return None;
+        }
+
+        // A storage key variant nothing uses never makes it onto the ledger
+        if violation.variable_name.contains("::") {
+            return None;
+        }
+
+        // An unused field is stored, name and value, in every entry holding its struct
+        let field = contract.and_then(|contract| {
+            contract.contract_types.iter().find_map(|definition| {
+                let field = definition.fields.iter().find(|field| field.name == violation.variable_name)?;
+                Some((contract, definition, field))
+            })
+        });
+        let (bytes, tier) = match field {
+            Some((contract, owner, field)) => {
+                let tier = StorageInventory::build(contract)
+                    .keys
+                    .iter()
+                    .find(|key| key.value_type.as_deref() == Some(owner.name.as_str()))
+                    .map_or(StorageTier::Persistent, |key| key.tier);
+                (16.0 + self.type_bytes(contract, &field.type_name), tier)
+            }
+            None => (16.0 + self.schedule.unknown_type_bytes, StorageTier::Persistent),
+        };
+
+        Some(ViolationSavings {
+            bytes: bytes.round() as u64,
+            monthly_rent: self.schedule.monthly_rent(tier, bytes),
+        })
+    }
+}
+
+/// Whether a call goes to the host through `env`, other than a storage access
+fn is_host_call(call: &gasguard_rules::soroban::Call) -> bool {
+    call.kind == CallKind::Method
+        && call.receiver_chain.first().is_some_and(|root| root == "env")
+        && call.receiver_chain.get(1).is_none_or(|accessor| accessor != "storage")
+        && call.name != "storage"
+}
+
+impl fmt::Display for ContractCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "💸 Estimated costs of {} (fee schedule {})", self.contract, self.fee_schedule)?;
+        for entry_point in &self.entry_points {
+            writeln!(f, "   • {}: ~{:.5} XLM", entry_point.function, entry_point.fee)?;
+            writeln!(
+                f,
+                "     entries: {} read-only, {} read-write; bytes: {} read, {} written; ~{} instructions",
+                entry_point.read_entries,
+                entry_point.write_entries,
+                entry_point.read_bytes,
+                entry_point.write_bytes,
+                entry_point.instructions
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gasguard_rules::soroban::SorobanParser;
+    use std::collections::BTreeMap;
+
+    const SOURCE: &str = r#"
+#[contracttype]
+pub struct Position {
+    pub owner: Address,
+    pub amount: i128,
+    pub opened: u64,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Position(Address),
+}
+
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn open(env: Env, owner: Address, amount: i128) {
+        let position = Position { owner: owner.clone(), amount, opened: env.ledger().timestamp() };
+        env.storage().persistent().set(&DataKey::Position(owner), &position);
+    }
+
+    pub fn position(env: Env, owner: Address) -> Position {
+        env.storage().persistent().get(&DataKey::Position(owner)).unwrap()
+    }
+
+    pub fn sweep(env: Env, owners: Vec<Address>) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        for owner in owners.iter() {
+            env.storage().persistent().remove(&DataKey::Position(owner));
+        }
+    }
+
+    pub fn owners(env: Env) -> u32 {
+        let mut count = 0;
+        for owner in env.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Admin).unwrap().iter() {
+            count += 1;
+        }
+        count
+    }
+}
+"#;
+
+    #[test]
+    fn test_estimate_entry_points() {
+        let contract = SorobanParser::parse_contract(SOURCE, "vault.rs").unwrap();
+        let model = CostModel::default();
+        let cost = model.estimate(&contract);
+        assert_eq!(cost.fee_schedule, "estimate-v1");
+
+        let open = &cost.entry_points[0];
+        assert_eq!((open.read_entries, open.write_entries), (1, 1));
+        // Position: map header + three (symbol, value) pairs; key: vec header + symbol + Address
+        let position_bytes = 48 + (8 + 16 + 40) + (8 + 16 * 3 + 40 + 20 + 12);
+        assert_eq!(open.footprint[1].bytes, position_bytes);
+        assert_eq!(open.write_bytes, position_bytes);
+
+        let read = &cost.entry_points[1];
+        assert_eq!((read.read_entries, read.write_entries), (2, 0));
+        assert!(read.fee < open.fee);
+
+        // The loop multiplies the storage access it runs
+        let sweep = &cost.entry_points[2];
+        assert_eq!(sweep.instructions, 1_000_000 + 50_000 + 50_000 * 10);
+        // A for header is evaluated once
+        assert_eq!(cost.entry_points[3].instructions, 1_000_000 + 50_000);
+        assert!(cost.to_string().contains("open: ~"));
+    }
+
+    #[test]
+    fn test_fee_schedule_overrides_and_savings() {
+        let mut overrides = BTreeMap::from([("fee_write_1kb".to_string(), 0.0002)]);
+        let schedule = FeeSchedule::with_overrides("latest", &overrides).unwrap();
+        assert_eq!(schedule.version, "estimate-v1+overrides");
+        assert_eq!(schedule.fee_write_1kb, 0.0002);
+        assert!(FeeSchedule::with_overrides("estimate-v0", &overrides).is_err());
+        overrides.insert("fee_gas".to_string(), 1.0);
+        assert!(FeeSchedule::with_overrides("latest", &overrides).is_err());
+
+        let contract = SorobanParser::parse_contract(SOURCE, "vault.rs").unwrap();
+        let violation = RuleViolation {
+            rule_name: "soroban-unused-state-variables".to_string(),
+            description: String::new(),
+            suggestion: String::new(),
+            line_number: 6,
+            column_number: 5,
+            span: SourceSpan::default(),
+            variable_name: "opened".to_string(),
+            severity: gasguard_rules::ViolationSeverity::Warning,
+            fix: None,
+        };
+        let model = CostModel::new(schedule);
+        let savings = model.violation_savings(Some(&contract), &violation).unwrap();
+        assert_eq!(savings.bytes, 16 + 12);
+        let expected = 0.0002 * (28.0 / 1024.0) * 518_400.0 / 2103.0;
+        assert!((savings.monthly_rent - expected).abs() < 1e-12);
+    }
+}
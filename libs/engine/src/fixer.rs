@@ -0,0 +1,259 @@
+//! Applies the structured fixes attached to violations and renders the result
+//! as a unified diff.
+
+use gasguard_rules::{apply_edits, Applicability, RuleViolation, TextEdit};
+
+/// Lines of unchanged context around each diff hunk
+const DIFF_CONTEXT: usize = 3;
+
+/// Result of applying fixes to one source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixOutcome {
+    /// Source text with the accepted fixes applied
+    pub fixed: String,
+    /// Number of fixes applied
+    pub applied: usize,
+    /// Number of fixes left out because they need review or conflict with another fix
+    pub skipped: usize,
+}
+
+pub struct FixEngine;
+
+impl FixEngine {
+    /// Apply every machine-applicable fix in `violations` to `source`, plus the
+    /// ones that may be incorrect when `include_unsafe` is set. Fixes whose edits
+    /// overlap an already accepted fix are skipped.
+    pub fn apply(source: &str, violations: &[RuleViolation], include_unsafe: bool) -> FixOutcome {
+        let mut accepted: Vec<TextEdit> = Vec::new();
+        let mut applied = 0;
+        let mut skipped = 0;
+
+        for fix in violations.iter().filter_map(|v| v.fix.as_ref()) {
+            if fix.applicability == Applicability::MaybeIncorrect && !include_unsafe {
+                skipped += 1;
+                continue;
+            }
+
+            let candidate: Vec<TextEdit> = accepted.iter().chain(&fix.edits).cloned().collect();
+            if apply_edits(source, &candidate).is_ok() {
+                accepted = candidate;
+                applied += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+
+        FixOutcome {
+            fixed: apply_edits(source, &accepted).unwrap_or_else(|_| source.to_string()),
+            applied,
+            skipped,
+        }
+    }
+
+    /// Render the change from `original` to `fixed` as a unified diff
+    pub fn unified_diff(path: &str, original: &str, fixed: &str) -> String {
+        let old: Vec<&str> = original.split_inclusive('\n').collect();
+        let new: Vec<&str> = fixed.split_inclusive('\n').collect();
+        let ops = diff_lines(&old, &new);
+
+        // Line positions before each operation
+        let mut positions = Vec::with_capacity(ops.len() + 1);
+        let (mut old_line, mut new_line) = (0, 0);
+        for op in &ops {
+            positions.push((old_line, new_line));
+            match op {
+                DiffOp::Equal(..) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                DiffOp::Delete(_) => old_line += 1,
+                DiffOp::Insert(_) => new_line += 1,
+            }
+        }
+        positions.push((old_line, new_line));
+
+        let changes: Vec<usize> = (0..ops.len())
+            .filter(|&index| !matches!(ops[index], DiffOp::Equal(..)))
+            .collect();
+        if changes.is_empty() {
+            return String::new();
+        }
+
+        // Group changes whose context would overlap into one hunk
+        let mut hunks: Vec<(usize, usize)> = Vec::new();
+        for &index in &changes {
+            let start = index.saturating_sub(DIFF_CONTEXT);
+            let end = (index + DIFF_CONTEXT + 1).min(ops.len());
+            match hunks.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => hunks.push((start, end)),
+            }
+        }
+
+        let path = path.trim_start_matches('/');
+        let mut output = format!("--- a/{}\n+++ b/{}\n", path, path);
+        for (start, end) in hunks {
+            let (old_start, new_start) = positions[start];
+            let (old_end, new_end) = positions[end];
+            let range = |start: usize, count: usize| {
+                format!("{},{}", if count == 0 { start } else { start + 1 }, count)
+            };
+            output.push_str(&format!(
+                "@@ -{} +{} @@\n",
+                range(old_start, old_end - old_start),
+                range(new_start, new_end - new_start)
+            ));
+
+            for op in &ops[start..end] {
+                let (prefix, line) = match *op {
+                    DiffOp::Equal(index, _) => (' ', old[index]),
+                    DiffOp::Delete(index) => ('-', old[index]),
+                    DiffOp::Insert(index) => ('+', new[index]),
+                };
+                output.push(prefix);
+                output.push_str(line);
+                if !line.ends_with('\n') {
+                    output.push_str("\n\\ No newline at end of file\n");
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// One step of a line edit script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Shortest edit script between two line sequences (Myers' algorithm)
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut frontier = vec![0isize; 2 * max + 3];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=max as isize {
+        trace.push(frontier.clone());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && frontier[index - 1] < frontier[index + 1]) {
+                frontier[index + 1]
+            } else {
+                frontier[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            frontier[index] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, frontier) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let index = (k + offset) as usize;
+        let prev_k = if k == -d || (k != d && frontier[index - 1] < frontier[index + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = frontier[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            ops.push(DiffOp::Equal((x - 1) as usize, (y - 1) as usize));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(DiffOp::Insert((y - 1) as usize));
+            } else {
+                ops.push(DiffOp::Delete((x - 1) as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gasguard_rules::{Fix, SourceSpan, ViolationSeverity};
+
+    fn violation_with_fix(source: &str, text: &str, replacement: &str, applicability: Applicability) -> RuleViolation {
+        let start = source.find(text).unwrap();
+        let span = SourceSpan::from_byte_range(source, start, start + text.len());
+        RuleViolation {
+            rule_name: "test-rule".to_string(),
+            description: String::new(),
+            severity: ViolationSeverity::Medium,
+            line_number: span.start_line,
+            column_number: span.start_column,
+            span,
+            variable_name: String::new(),
+            suggestion: String::new(),
+            fix: Some(Fix::new("fix", applicability, vec![TextEdit::replace(span, replacement)])),
+        }
+    }
+
+    #[test]
+    fn test_apply_skips_unsafe_and_conflicting_fixes() {
+        let source = "let a = x.clone();\nlet b = y.clone();\n";
+        let violations = vec![
+            violation_with_fix(source, "x.clone()", "x", Applicability::MachineApplicable),
+            violation_with_fix(source, ".clone();\nlet b", "", Applicability::MachineApplicable),
+            violation_with_fix(source, "y.clone()", "y", Applicability::MaybeIncorrect),
+        ];
+
+        let outcome = FixEngine::apply(source, &violations, false);
+        assert_eq!(outcome.fixed, "let a = x;\nlet b = y.clone();\n");
+        assert_eq!((outcome.applied, outcome.skipped), (1, 2));
+
+        let outcome = FixEngine::apply(source, &violations, true);
+        assert_eq!(outcome.fixed, "let a = x;\nlet b = y;\n");
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let original = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let fixed = "a\nb\nC\nd\ne\nf\ng\nh\nj\n";
+        let diff = FixEngine::unified_diff("lib.rs", original, fixed);
+
+        assert_eq!(
+            diff,
+            "--- a/lib.rs\n+++ b/lib.rs\n@@ -1,10 +1,9 @@\n a\n b\n-c\n+C\n d\n e\n f\n g\n h\n-i\n j\n"
+        );
+        assert!(FixEngine::unified_diff("lib.rs", original, original).is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_separates_distant_hunks() {
+        let original: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        let fixed: String = (1..=20)
+            .filter(|&n| n != 18)
+            .map(|n| if n == 2 { "two\n".to_string() } else { format!("{}\n", n) })
+            .collect();
+        let diff = FixEngine::unified_diff("lib.rs", &original, &fixed);
+
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n"));
+        assert!(diff.contains("@@ -15,6 +15,5 @@\n 15\n 16\n 17\n-18\n 19\n 20\n"));
+    }
+}
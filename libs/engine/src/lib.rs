@@ -1,7 +1,15 @@
+pub mod config;
+pub mod cost;
+pub mod fixer;
 pub mod scanner;
 pub mod analyzer;
+pub mod sarif;
 pub mod tiered_scanner;
 
+pub use config::*;
+pub use cost::*;
+pub use fixer::*;
 pub use scanner::*;
 pub use analyzer::*;
+pub use sarif::*;
 pub use tiered_scanner::*;
@@ -0,0 +1,296 @@
+//! SARIF 2.1.0 output
+//!
+//! Renders scan results in the Static Analysis Results Interchange Format so
+//! they can be uploaded to code scanning dashboards and read by editors.
+
+use crate::scanner::ScanResult;
+use gasguard_rules::{Fix, RuleRegistry, RuleViolation, SourceSpan, ViolationSeverity};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+pub struct SarifEmitter;
+
+impl SarifEmitter {
+    /// Build a SARIF log with a single run covering `results`. Rule descriptors
+    /// come from the metadata of the rules in `registry`.
+    pub fn to_sarif(results: &[ScanResult], registry: &RuleRegistry) -> Value {
+        let mut rule_index: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut rules = Vec::new();
+        let mut sarif_results = Vec::new();
+
+        for result in results {
+            let findings = result
+                .violations
+                .iter()
+                .map(|v| (v, false))
+                .chain(result.suppressed.iter().map(|v| (v, true)));
+
+            for (violation, suppressed) in findings {
+                let index = *rule_index.entry(&violation.rule_name).or_insert_with(|| {
+                    rules.push(Self::rule_descriptor(violation, registry));
+                    rules.len() - 1
+                });
+
+                let mut sarif_result = Self::result(&result.source, violation, index);
+                if suppressed {
+                    sarif_result["suppressions"] = json!([{ "kind": "inSource" }]);
+                }
+                sarif_results.push(sarif_result);
+            }
+        }
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "GasGuard",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "results": sarif_results,
+            }],
+        })
+    }
+
+    /// Pretty-printed SARIF log for `results`
+    pub fn to_sarif_string(
+        results: &[ScanResult],
+        registry: &RuleRegistry,
+    ) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&Self::to_sarif(results, registry))
+    }
+
+    /// SARIF level for a violation severity
+    pub fn level(severity: &ViolationSeverity) -> &'static str {
+        match severity {
+            ViolationSeverity::Error | ViolationSeverity::High => "error",
+            ViolationSeverity::Medium | ViolationSeverity::Warning => "warning",
+            ViolationSeverity::Info => "note",
+        }
+    }
+
+    /// Descriptor of the rule that reported `violation`; findings of checks that
+    /// are not registered rules get a bare descriptor named after the finding
+    fn rule_descriptor(violation: &RuleViolation, registry: &RuleRegistry) -> Value {
+        match registry.get(&violation.rule_name) {
+            Some(rule) => {
+                let metadata = rule.metadata();
+                json!({
+                    "id": metadata.id,
+                    "name": metadata.name,
+                    "shortDescription": { "text": metadata.description },
+                    "helpUri": metadata.docs_url(),
+                    "defaultConfiguration": { "level": Self::level(&metadata.default_severity) },
+                    "properties": {
+                        "category": metadata.category,
+                        "tags": metadata.tags,
+                    },
+                })
+            }
+            None => json!({
+                "id": violation.rule_name,
+                "shortDescription": { "text": violation.rule_name },
+                "defaultConfiguration": { "level": Self::level(&violation.severity) },
+            }),
+        }
+    }
+
+    fn result(source: &str, violation: &RuleViolation, rule_index: usize) -> Value {
+        let mut physical_location = Map::new();
+        physical_location.insert("artifactLocation".to_string(), artifact_location(source));
+        if let Some(region) = violation_region(violation) {
+            physical_location.insert("region".to_string(), region);
+        }
+
+        let mut properties = Map::new();
+        properties.insert("severity".to_string(), json!(violation.severity));
+        if !violation.suggestion.is_empty() {
+            properties.insert("suggestion".to_string(), json!(violation.suggestion));
+        }
+        if !violation.variable_name.is_empty() {
+            properties.insert("variableName".to_string(), json!(violation.variable_name));
+        }
+
+        let mut result = json!({
+            "ruleId": violation.rule_name,
+            "ruleIndex": rule_index,
+            "level": Self::level(&violation.severity),
+            "message": { "text": violation.description },
+            "locations": [{ "physicalLocation": physical_location }],
+            "properties": properties,
+        });
+        if let Some(fix) = &violation.fix {
+            result["fixes"] = json!([sarif_fix(source, fix)]);
+        }
+
+        result
+    }
+}
+
+fn sarif_fix(source: &str, fix: &Fix) -> Value {
+    let replacements: Vec<Value> = fix
+        .edits
+        .iter()
+        .map(|edit| {
+            json!({
+                "deletedRegion": span_region(&edit.span),
+                "insertedContent": { "text": edit.replacement },
+            })
+        })
+        .collect();
+
+    json!({
+        "description": { "text": fix.description },
+        "artifactChanges": [{
+            "artifactLocation": artifact_location(source),
+            "replacements": replacements,
+        }],
+        "properties": { "applicability": fix.applicability },
+    })
+}
+
+/// Region of a violation, falling back to its line and column when it has no span
+fn violation_region(violation: &RuleViolation) -> Option<Value> {
+    if violation.span.start_line > 0 {
+        Some(span_region(&violation.span))
+    } else if violation.line_number > 0 {
+        let mut region = json!({ "startLine": violation.line_number });
+        if violation.column_number > 0 {
+            region["startColumn"] = json!(violation.column_number);
+        }
+        Some(region)
+    } else {
+        None
+    }
+}
+
+fn span_region(span: &SourceSpan) -> Value {
+    json!({
+        "startLine": span.start_line,
+        "startColumn": span.start_column,
+        "endLine": span.end_line,
+        "endColumn": span.end_column,
+        "byteOffset": span.start_byte,
+        "byteLength": span.end_byte - span.start_byte,
+    })
+}
+
+/// Artifact location for a scanned path, as a relative reference or a `file` URI
+fn artifact_location(source: &str) -> Value {
+    let path = source.replace('\\', "/").replace('%', "%25").replace(' ', "%20");
+    let uri = if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        path.trim_start_matches("./").to_string()
+    };
+    json!({ "uri": uri })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gasguard_rules::{Applicability, TextEdit};
+
+    fn violation(rule_name: &str, severity: ViolationSeverity, span: SourceSpan) -> RuleViolation {
+        RuleViolation {
+            rule_name: rule_name.to_string(),
+            description: format!("{} finding", rule_name),
+            severity,
+            line_number: span.start_line,
+            column_number: span.start_column,
+            span,
+            variable_name: String::new(),
+            suggestion: "Do better".to_string(),
+            fix: None,
+        }
+    }
+
+    fn scan_result(source: &str, violations: Vec<RuleViolation>, suppressed: Vec<RuleViolation>) -> ScanResult {
+        ScanResult {
+            source: source.to_string(),
+            violations,
+            suppressed,
+            scan_time: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_rules_results_and_locations() {
+        let code = "let y = x.clone();\n";
+        let span = SourceSpan::from_byte_range(code, 9, 17);
+        let mut with_fix = violation("soroban-redundant-clone", ViolationSeverity::Medium, span);
+        with_fix.fix = Some(Fix::new(
+            "Remove the clone",
+            Applicability::MachineApplicable,
+            vec![TextEdit::delete(span)],
+        ));
+
+        let results = vec![
+            scan_result("src/lib.rs", vec![with_fix], vec![]),
+            scan_result(
+                "/work/my contract.rs",
+                vec![violation("unbounded-loop", ViolationSeverity::High, SourceSpan::default())],
+                vec![violation("soroban-redundant-clone", ViolationSeverity::Info, span)],
+            ),
+        ];
+        let sarif = SarifEmitter::to_sarif(&results, &RuleRegistry::with_default_rules());
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["name"], "Redundant Clone");
+        assert!(rules[0]["helpUri"].as_str().unwrap().ends_with("#soroban-redundant-clone"));
+        assert_eq!(rules[0]["properties"]["category"], "gas");
+        assert_eq!(rules[1]["shortDescription"]["text"], "unbounded-loop");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(results[0]["properties"]["suggestion"], "Do better");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(location["region"]["startColumn"], 10);
+        assert_eq!(location["region"]["endColumn"], 18);
+
+        assert_eq!(results[1]["level"], "error");
+        assert_eq!(results[1]["ruleIndex"], 1);
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "file:///work/my%20contract.rs");
+        assert!(location.get("region").is_none());
+
+        assert_eq!(results[2]["level"], "note");
+        assert_eq!(results[2]["ruleIndex"], 0);
+        assert_eq!(results[2]["suppressions"][0]["kind"], "inSource");
+    }
+
+    #[test]
+    fn test_fixes_become_artifact_changes() {
+        let code = "let y = x.clone();\n";
+        let span = SourceSpan::from_byte_range(code, 9, 17);
+        let mut finding = violation("soroban-redundant-clone", ViolationSeverity::Medium, span);
+        finding.fix = Some(Fix::new(
+            "Remove the clone",
+            Applicability::MaybeIncorrect,
+            vec![TextEdit::replace(span, "")],
+        ));
+
+        let sarif = SarifEmitter::to_sarif(&[scan_result("lib.rs", vec![finding], vec![])], &RuleRegistry::new());
+        let fix = &sarif["runs"][0]["results"][0]["fixes"][0];
+
+        assert_eq!(fix["description"]["text"], "Remove the clone");
+        assert_eq!(fix["properties"]["applicability"], "MaybeIncorrect");
+        let change = &fix["artifactChanges"][0];
+        assert_eq!(change["artifactLocation"]["uri"], "lib.rs");
+        let replacement = &change["replacements"][0];
+        assert_eq!(replacement["deletedRegion"]["byteOffset"], 9);
+        assert_eq!(replacement["deletedRegion"]["byteLength"], 8);
+        assert_eq!(replacement["insertedContent"]["text"], "");
+    }
+}
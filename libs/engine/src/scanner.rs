@@ -1,66 +1,81 @@
-use anyhow::{Context, Result};
-use gasguard_rules::{RuleEngine, UnusedStateVariablesRule, VyperRuleEngine, SorobanRuleEngine};
+use crate::analyzer::{ScanAnalyzer, StorageSavings};
+use crate::config::GasGuardConfig;
+use crate::cost::{ContractCost, CostModel};
+use anyhow::{bail, Context, Result};
+use gasguard_rules::soroban::{SorobanParser, StorageInventory, TestCoverage};
+use gasguard_rules::{RuleRegistry, SuppressionReport};
 use std::path::Path;
 
-/// Supported languages for scanning
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Language {
-    Rust,
-    Vyper,
-    Soroban, // Added Soroban support
+pub use gasguard_rules::Language;
+
+pub struct ContractScanner {
+    registry: RuleRegistry,
+    config: GasGuardConfig,
+    cost_model: CostModel,
 }
 
-impl Language {
-    /// Detect language from file extension
-    pub fn from_extension(ext: &str) -> Option<Self> {
-        match ext.to_lowercase().as_str() {
-            "rs" => Some(Language::Rust),
-            "vy" => Some(Language::Vyper),
-            _ => None,
-        }
+impl ContractScanner {
+    pub fn new() -> Self {
+        Self::with_config(GasGuardConfig::default()).expect("default configuration is valid")
     }
-    
-    /// Detect language from file content heuristics
-    pub fn from_content(content: &str) -> Option<Self> {
-        // Check for Soroban-specific patterns
-        if content.contains("soroban_sdk") && 
-           (content.contains("#[contract]") || 
-            content.contains("#[contractimpl]") || 
-            content.contains("#[contracttype]")) {
-            return Some(Language::Soroban);
-        }
-        
-        // Check for Vyper patterns
-        if content.contains("# @version") || content.contains("interface ") {
-            return Some(Language::Vyper);
+
+    /// Build a scanner from the `gasguard.toml` governing `path`, or the defaults if there is none
+    pub fn for_path(path: &Path) -> Result<Self> {
+        Self::with_config(GasGuardConfig::discover(path)?.unwrap_or_default())
+    }
+
+    /// Build a scanner whose registry runs the rules selected by `config`
+    pub fn with_config(config: GasGuardConfig) -> Result<Self> {
+        let mut registry = RuleRegistry::with_default_rules();
+        let rule_ids: Vec<String> = registry.rules().map(|r| r.id().to_string()).collect();
+        for rule_id in rule_ids {
+            registry.set_enabled(&rule_id, config.is_rule_enabled(&rule_id));
         }
-        
-        // Default to Rust for .rs files or general Rust code
-        if content.contains("fn main(") || content.contains("#[derive(") {
-            return Some(Language::Rust);
+        for (rule_id, thresholds) in &config.thresholds {
+            for (name, value) in thresholds {
+                if !registry.set_threshold(rule_id, name, *value) {
+                    bail!("Rule '{}' has no threshold '{}'", rule_id, name);
+                }
+            }
         }
-        
-        None
+
+        let cost_model = CostModel::new(config.fee_schedule()?);
+        Ok(Self { registry, config, cost_model })
     }
-}
 
-pub struct ContractScanner {
-    rule_engine: RuleEngine,
-    vyper_rule_engine: VyperRuleEngine,
-    soroban_rule_engine: SorobanRuleEngine, // Added Soroban rule engine
-}
+    /// The configuration the scanner was built from
+    pub fn config(&self) -> &GasGuardConfig {
+        &self.config
+    }
 
-impl ContractScanner {
-    pub fn new() -> Self {
-        let rule_engine = RuleEngine::new().add_rule(Box::new(UnusedStateVariablesRule));
-        let vyper_rule_engine = VyperRuleEngine::with_default_rules();
-        let soroban_rule_engine = SorobanRuleEngine::with_default_rules(); // Initialize Soroban engine
-
-        Self {
-            rule_engine,
-            vyper_rule_engine,
-            soroban_rule_engine,
+    /// The cost model, priced with the configured fee schedule
+    pub fn cost_model(&self) -> &CostModel {
+        &self.cost_model
+    }
+
+    /// The rules the scanner runs
+    pub fn registry(&self) -> &RuleRegistry {
+        &self.registry
+    }
+
+    /// Build the scan result, dropping findings of disabled rules and applying severity overrides
+    fn finish(&self, source: String, report: SuppressionReport) -> ScanResult {
+        let mut result = ScanResult {
+            source,
+            violations: report.violations,
+            suppressed: report.suppressed,
+            scan_time: chrono::Utc::now(),
+        };
+
+        result.violations.retain(|v| self.config.is_rule_enabled(&v.rule_name));
+        result.suppressed.retain(|v| self.config.is_rule_enabled(&v.rule_name));
+        for violation in result.violations.iter_mut().chain(result.suppressed.iter_mut()) {
+            if let Some(severity) = self.config.severity_overrides.get(&violation.rule_name) {
+                violation.severity = severity.clone();
+            }
         }
+
+        result
     }
 
     pub fn scan_file(&self, file_path: &Path) -> Result<ScanResult> {
@@ -69,7 +84,10 @@ impl ContractScanner {
 
         let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
 
-        let language = Language::from_extension(extension);
+        // Soroban contracts share the .rs extension, so recognise them by content first
+        let language = Language::from_content(&content)
+            .filter(|language| *language == Language::Soroban)
+            .or_else(|| Language::from_extension(extension));
 
         self.scan_content_with_language(&content, file_path.to_string_lossy().to_string(), language)
     }
@@ -85,41 +103,21 @@ impl ContractScanner {
         source: String,
         language: Option<Language>,
     ) -> Result<ScanResult> {
-        let detected_language = language.or_else(|| Language::from_content(content));
-        
-        let violations = match detected_language {
-            Some(Language::Rust) => self
-                .rule_engine
-                .analyze(content)
-                .map_err(|e| anyhow::anyhow!(e))?,
-            Some(Language::Vyper) => self
-                .vyper_rule_engine
-                .analyze(content)
-                .map_err(|e| anyhow::anyhow!(e))?,
-            Some(Language::Soroban) => self
-                .soroban_rule_engine
-                .analyze(content, &source)
-                .map_err(|e| anyhow::anyhow!(format!("Soroban analysis failed: {:?}", e)))?,
-            None => {
-                // Unknown language, try to detect and analyze
-                if content.contains("soroban_sdk") {
-                    self.soroban_rule_engine
-                        .analyze(content, &source)
-                        .map_err(|e| anyhow::anyhow!(format!("Soroban analysis failed: {:?}", e)))?
-                } else {
-                    // Default to general Rust analysis
-                    self.rule_engine
-                        .analyze(content)
-                        .map_err(|e| anyhow::anyhow!(e))?
-                }
+        // Unknown language: Soroban if it uses the SDK, general Rust analysis otherwise
+        let detected_language = language.or_else(|| Language::from_content(content)).unwrap_or_else(|| {
+            if content.contains("soroban_sdk") {
+                Language::Soroban
+            } else {
+                Language::Rust
             }
-        };
+        });
 
-        Ok(ScanResult {
-            source,
-            violations,
-            scan_time: chrono::Utc::now(),
-        })
+        let report = self
+            .registry
+            .analyze(detected_language, content, &source)
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(self.finish(source, report))
     }
 
     /// Scan a Vyper file specifically
@@ -132,16 +130,7 @@ impl ContractScanner {
 
     /// Scan Vyper content directly
     pub fn scan_vyper_content(&self, content: &str, source: String) -> Result<ScanResult> {
-        let violations = self
-            .vyper_rule_engine
-            .analyze(content)
-            .map_err(|e| anyhow::anyhow!(e))?;
-
-        Ok(ScanResult {
-            source,
-            violations,
-            scan_time: chrono::Utc::now(),
-        })
+        self.scan_content_with_language(content, source, Some(Language::Vyper))
     }
     
     /// Scan a Soroban contract file specifically
@@ -154,16 +143,66 @@ impl ContractScanner {
 
     /// Scan Soroban contract content directly
     pub fn scan_soroban_content(&self, content: &str, source: String) -> Result<ScanResult> {
-        let violations = self
-            .soroban_rule_engine
-            .analyze(content, &source)
-            .map_err(|e| anyhow::anyhow!(format!("Soroban analysis failed: {:?}", e)))?;
+        self.scan_content_with_language(content, source, Some(Language::Soroban))
+    }
 
-        Ok(ScanResult {
-            source,
-            violations,
-            scan_time: chrono::Utc::now(),
-        })
+    /// List the storage keys a Soroban contract file touches
+    pub fn storage_inventory(file_path: &Path) -> Result<StorageInventory> {
+        let content = std::fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
+
+        let contract = SorobanParser::parse_contract(&content, &file_path.to_string_lossy())?;
+        Ok(StorageInventory::build(&contract))
+    }
+
+    /// Match the entry points of a Soroban contract file against the `#[test]`
+    /// functions in `tests_path`, a test file or a directory of them
+    pub fn test_coverage(contract_path: &Path, tests_path: &Path) -> Result<TestCoverage> {
+        let content = std::fs::read_to_string(contract_path)
+            .with_context(|| format!("Failed to read file: {:?}", contract_path))?;
+        let contract = SorobanParser::parse_contract(&content, &contract_path.to_string_lossy())?;
+
+        let mut files = Vec::new();
+        for entry in walkdir::WalkDir::new(tests_path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "rs"))
+        {
+            let source = std::fs::read_to_string(entry.path())
+                .with_context(|| format!("Failed to read file: {:?}", entry.path()))?;
+            files.push(SorobanParser::parse_test_file(&source, &entry.path().to_string_lossy())?);
+        }
+        if files.is_empty() {
+            bail!("No Rust test files found in {:?}", tests_path);
+        }
+        Ok(TestCoverage::build(&contract, &files))
+    }
+
+    /// Estimate the resources and fee of each entry point of a Soroban contract file
+    pub fn estimate_costs(&self, file_path: &Path) -> Result<ContractCost> {
+        let content = std::fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
+
+        let contract = SorobanParser::parse_contract(&content, &file_path.to_string_lossy())?;
+        Ok(self.cost_model.estimate(&contract))
+    }
+
+    /// Storage savings of the findings in `results`, sized from the types of each
+    /// Soroban contract scanned
+    pub fn storage_savings(&self, results: &[ScanResult]) -> StorageSavings {
+        let mut savings = ScanAnalyzer::estimate_storage_savings(&[], &self.cost_model, None);
+        for result in results {
+            let contract = std::fs::read_to_string(&result.source)
+                .ok()
+                .and_then(|content| SorobanParser::parse_contract(&content, &result.source).ok());
+            savings.merge(ScanAnalyzer::estimate_storage_savings(
+                &result.violations,
+                &self.cost_model,
+                contract.as_ref(),
+            ));
+        }
+        savings
     }
 
     pub fn scan_directory(&self, dir_path: &Path) -> Result<Vec<ScanResult>> {
@@ -172,21 +211,25 @@ impl ContractScanner {
         for entry in walkdir::WalkDir::new(dir_path)
             .into_iter()
             .filter_map(|e| e.ok())
+            .filter(|e| self.config.includes_path(e.path()))
             .filter(|e| {
-                e.path().extension().map_or(false, |ext| {
+                e.path().extension().is_some_and(|ext| {
                     let ext_str = ext.to_str().unwrap_or("");
-                    ext_str == "rs" || ext_str == "vy" // Both Rust and Vyper files
+                    ext_str == "rs" || ext_str == "vy" || ext_str == "sol"
                 })
             })
         {
             let content = std::fs::read_to_string(entry.path())
                 .with_context(|| format!("Failed to read file: {:?}", entry.path()))?;
             
-            // Detect language from content for better accuracy
-            let language = Language::from_content(&content).or_else(|| {
-                entry.path().extension()
-                    .and_then(|ext| Language::from_extension(ext.to_str().unwrap_or("")))
-            });
+            // Solidity files are recognised by extension since their interfaces
+            // look like Vyper; otherwise detect from content for better accuracy
+            let extension_language = entry.path().extension()
+                .and_then(|ext| Language::from_extension(ext.to_str().unwrap_or("")));
+            let language = extension_language
+                .filter(|language| *language == Language::Solidity)
+                .or_else(|| Language::from_content(&content))
+                .or(extension_language);
             
             let result = match language {
                 Some(Language::Soroban) => {
@@ -201,7 +244,7 @@ impl ContractScanner {
                 }
             };
             
-            if !result.violations.is_empty() {
+            if result.has_violations() || !result.suppressed.is_empty() {
                 results.push(result);
             }
         }
@@ -216,10 +259,13 @@ impl Default for ContractScanner {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ScanResult {
     pub source: String,
     pub violations: Vec<gasguard_rules::RuleViolation>,
+    /// Findings silenced by `gasguard-ignore` comments
+    #[serde(default)]
+    pub suppressed: Vec<gasguard_rules::RuleViolation>,
     pub scan_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -242,3 +288,118 @@ impl ScanResult {
         serde_json::to_string_pretty(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = r#"use soroban_sdk::{contract, contractimpl, Env, Symbol};
+
+#[contract]
+pub struct Counter;
+
+#[contractimpl]
+impl Counter {
+    pub fn bump(env: Env, key: Symbol) {
+        let a: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        let b: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &(a + b));
+        loop {
+            env.storage().instance().remove(&key);
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_scanner_applies_config() {
+        let config = GasGuardConfig::from_toml(
+            r#"
+[rules]
+disabled = ["soroban-unbounded-loop"]
+
+[rules.severity]
+soroban-inefficient-storage = "info"
+
+[rules.thresholds.soroban-wasteful-ttl-extension]
+max_threshold_percent = 80
+"#,
+        )
+        .unwrap();
+        let scanner = ContractScanner::with_config(config).unwrap();
+        let result = scanner.scan_soroban_content(CONTRACT, "counter.rs".to_string()).unwrap();
+
+        assert!(!result.violations.iter().any(|v| v.rule_name.ends_with("unbounded-loop")));
+        let storage = result
+            .violations
+            .iter()
+            .find(|v| v.rule_name == "soroban-inefficient-storage")
+            .unwrap();
+        assert!(matches!(storage.severity, gasguard_rules::ViolationSeverity::Info));
+    }
+
+    #[test]
+    fn test_scan_directory_includes_solidity() {
+        let dir = std::env::temp_dir().join(format!("gasguard-scan-sol-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("Vault.sol"),
+            "interface IVault {\n    function level() external view returns (uint8);\n}\n",
+        )
+        .unwrap();
+
+        let results = ContractScanner::new().scan_directory(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].violations[0].rule_name, "solidity-uint8-vs-uint256");
+        assert_eq!(results[0].violations[0].line_number, 2);
+    }
+
+    #[test]
+    fn test_unused_field_is_reported_and_priced_once() {
+        let dir = std::env::temp_dir().join(format!("gasguard-savings-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("registry.rs");
+        std::fs::write(
+            &path,
+            r#"use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+#[contracttype]
+pub struct Config {
+    pub admin: Address,
+    pub stale: u64,
+}
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn admin(env: Env) -> Address {
+        let config: Config = env.storage().instance().get(&1u32).unwrap();
+        config.admin
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let scanner = ContractScanner::new();
+        let result = scanner.scan_file(&path).unwrap();
+        let savings = scanner.storage_savings(std::slice::from_ref(&result));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let reports = result.violations.iter().filter(|v| v.variable_name == "stale").count();
+        assert_eq!(reports, 1);
+        assert_eq!(savings.unused_variables, 1);
+        assert_eq!(savings.unpriced_findings, result.violations.len() - 1);
+        assert!((savings.estimated_savings_kb - (16.0 + 12.0) / 1024.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unknown_threshold_is_rejected() {
+        let config = GasGuardConfig::from_toml("[rules.thresholds.soroban-unbounded-loop]\nmax_depth = 1\n").unwrap();
+        assert!(ContractScanner::with_config(config).is_err());
+    }
+}
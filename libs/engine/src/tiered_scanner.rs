@@ -2,7 +2,7 @@ use crate::scanner::{ScanResult, ContractScanner};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UsageTier {
     Starter,
     Developer,
@@ -353,6 +353,12 @@ impl TieredScanner {
     }
 }
 
+impl Default for TieredScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TierValidationResult {
     pub is_valid: bool,
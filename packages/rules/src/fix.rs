@@ -0,0 +1,146 @@
+//! Structured fixes
+//!
+//! Rules can attach a [`Fix`] to a violation: text edits on the analyzed source
+//! together with how safe it is to apply them without review.
+
+use crate::SourceSpan;
+use serde::{Deserialize, Serialize};
+
+/// How confident a rule is that its fix is correct
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Applicability {
+    /// The fix preserves behaviour and can be applied automatically
+    MachineApplicable,
+    /// The fix is likely right but may change behaviour or break the build
+    MaybeIncorrect,
+}
+
+/// Replacement of a region of the source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub span: SourceSpan,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn replace(span: SourceSpan, replacement: impl Into<String>) -> Self {
+        Self {
+            span,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Remove the spanned text; if that leaves its line blank, the line is removed too
+    pub fn delete(span: SourceSpan) -> Self {
+        Self::replace(span, "")
+    }
+}
+
+/// Edits resolving a violation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fix {
+    pub description: String,
+    pub applicability: Applicability,
+    pub edits: Vec<TextEdit>,
+}
+
+impl Fix {
+    pub fn new(description: impl Into<String>, applicability: Applicability, edits: Vec<TextEdit>) -> Self {
+        Self {
+            description: description.into(),
+            applicability,
+            edits,
+        }
+    }
+
+    pub fn is_machine_applicable(&self) -> bool {
+        self.applicability == Applicability::MachineApplicable
+    }
+}
+
+/// Apply `edits` to `source`, failing if any two edits overlap
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut ranges: Vec<(usize, usize, &str)> = edits
+        .iter()
+        .map(|edit| {
+            let (start, end) = (edit.span.start_byte, edit.span.end_byte);
+            if start > end || end > source.len() || !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+                return Err(format!("Edit at line {} is outside the source", edit.span.start_line));
+            }
+            Ok(if edit.replacement.is_empty() {
+                let (start, end) = blank_line_range(source, start, end);
+                (start, end, "")
+            } else {
+                (start, end, edit.replacement.as_str())
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    ranges.sort_by_key(|(start, end, _)| (*start, *end));
+
+    let mut fixed = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for (start, end, replacement) in ranges {
+        if start < cursor {
+            return Err(format!(
+                "Overlapping edits at line {}",
+                SourceSpan::from_byte_range(source, start, end).start_line
+            ));
+        }
+        fixed.push_str(&source[cursor..start]);
+        fixed.push_str(replacement);
+        cursor = end;
+    }
+    fixed.push_str(&source[cursor..]);
+
+    Ok(fixed)
+}
+
+/// Widen a deletion to its whole line when nothing else is left on it
+fn blank_line_range(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+    let line_end = source[end..].find('\n').map_or(source.len(), |newline| end + newline + 1);
+    if source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty() {
+        (line_start, line_end)
+    } else {
+        (start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span_of(source: &str, text: &str) -> SourceSpan {
+        let start = source.find(text).unwrap();
+        SourceSpan::from_byte_range(source, start, start + text.len())
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let source = "struct S {\n    used: u32,\n    unused: u64,\n}\nlet y = x.clone();\n";
+        let fixed = apply_edits(
+            source,
+            &[
+                TextEdit::delete(span_of(source, ".clone()")),
+                TextEdit::delete(span_of(source, "unused: u64,")),
+                TextEdit::replace(span_of(source, "used: u32"), "used: u64"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(fixed, "struct S {\n    used: u64,\n}\nlet y = x;\n");
+    }
+
+    #[test]
+    fn test_overlapping_edits_are_rejected() {
+        let source = "let y = x.clone();\n";
+        let result = apply_edits(
+            source,
+            &[
+                TextEdit::delete(span_of(source, "x.clone()")),
+                TextEdit::replace(span_of(source, ".clone()"), ".to_owned()"),
+            ],
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Source languages understood by the rules
+//!
+//! Each language has its own frontend producing the typed input its rules check.
+
+use serde::{Deserialize, Serialize};
+
+/// Supported languages for scanning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Rust,
+    Vyper,
+    Soroban,
+    Solidity,
+}
+
+impl Language {
+    /// Detect language from file extension
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "rs" => Some(Language::Rust),
+            "vy" => Some(Language::Vyper),
+            "sol" => Some(Language::Solidity),
+            _ => None,
+        }
+    }
+
+    /// Detect language from file content heuristics
+    pub fn from_content(content: &str) -> Option<Self> {
+        // Check for Soroban-specific patterns
+        if content.contains("soroban_sdk")
+            && (content.contains("#[contract]")
+                || content.contains("#[contractimpl]")
+                || content.contains("#[contracttype]"))
+        {
+            return Some(Language::Soroban);
+        }
+
+        if content.contains("pragma solidity") {
+            return Some(Language::Solidity);
+        }
+
+        // Check for Vyper patterns
+        if content.contains("# @version") || content.contains("interface ") {
+            return Some(Language::Vyper);
+        }
+
+        // Default to Rust for .rs files or general Rust code
+        if content.contains("fn main(") || content.contains("#[derive(") {
+            return Some(Language::Rust);
+        }
+
+        None
+    }
+
+    /// Prefix of a line comment, used to find `gasguard-ignore` directives
+    pub fn comment_prefix(&self) -> &'static str {
+        match self {
+            Language::Rust | Language::Soroban | Language::Solidity => "//",
+            Language::Vyper => "#",
+        }
+    }
+}
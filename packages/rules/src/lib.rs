@@ -1,15 +1,30 @@
+pub mod fix;
+pub mod language;
+pub mod registry;
 pub mod rule_engine;
+pub mod solidity;
+pub mod span;
+pub mod suppression;
+pub mod unchecked_arithmetic;
 pub mod unused_state_variables;
 pub mod vyper;
 pub mod soroban;
 
 // Explicitly export core types to avoid ambiguity
-pub use rule_engine::{Rule, RuleEngine, RuleViolation, ViolationSeverity, extract_struct_fields, find_variable_usage};
+pub use rule_engine::{
+    Rule, RuleCategory, RuleEngine, RuleInput, RuleMetadata, RuleViolation, ViolationSeverity, RULE_DOCS_URL,
+    extract_struct_fields, find_variable_usage,
+};
+pub use fix::{apply_edits, Applicability, Fix, TextEdit};
+pub use language::Language;
+pub use registry::RuleRegistry;
+pub use span::SourceSpan;
+pub use suppression::{Suppression, SuppressionReport, SuppressionScope, Suppressions, UNUSED_SUPPRESSION_RULE};
+pub use unchecked_arithmetic::UncheckedArithmeticRule;
 pub use unused_state_variables::UnusedStateVariablesRule;
 
 // Export Soroban types specifically
 pub use soroban::{
-    SorobanAnalyzer, 
     SorobanContract, 
     SorobanParser, 
     SorobanResult, 
@@ -21,5 +36,7 @@ pub use soroban::{
     SorobanParam
 };
 
+pub use solidity::{SolidityASTNode, SolidityParser, SolidityRuleEngine, Uint8VsUint256Rule};
+
 // Export Vyper types (keeping glob here is fine if Vyper module is clean, but let's be safe)
 pub use vyper::*;
\ No newline at end of file
@@ -0,0 +1,216 @@
+//! Rule registry
+//!
+//! Holds every rule regardless of language, tracks which are enabled and runs
+//! the ones that apply to a parsed source.
+
+use crate::solidity::{SolidityParser, SolidityRuleEngine};
+use crate::soroban::{SorobanParser, SorobanRuleEngine};
+use crate::vyper::{VyperContract, VyperRuleEngine};
+use crate::{
+    Language, Rule, RuleInput, RuleMetadata, RuleViolation, SuppressionReport, Suppressions,
+    UncheckedArithmeticRule, UnusedStateVariablesRule,
+};
+
+struct RegisteredRule {
+    rule: Box<dyn Rule>,
+    enabled: bool,
+}
+
+/// Registered rules in registration order
+#[derive(Default)]
+pub struct RuleRegistry {
+    rules: Vec<RegisteredRule>,
+}
+
+impl RuleRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry holding the built-in rules of every language
+    pub fn with_default_rules() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(UnusedStateVariablesRule));
+        registry.register(Box::new(UncheckedArithmeticRule));
+        for rule in VyperRuleEngine::default_rules() {
+            registry.register(rule);
+        }
+        for rule in SorobanRuleEngine::default_rules() {
+            registry.register(rule);
+        }
+        for rule in SolidityRuleEngine::default_rules() {
+            registry.register(rule);
+        }
+        registry
+    }
+
+    /// Add an enabled rule, replacing any rule registered under the same ID
+    pub fn register(&mut self, rule: Box<dyn Rule>) -> &mut Self {
+        let registered = RegisteredRule { rule, enabled: true };
+        match self.position(registered.rule.id()) {
+            Some(index) => self.rules[index] = registered,
+            None => self.rules.push(registered),
+        }
+        self
+    }
+
+    fn position(&self, rule_id: &str) -> Option<usize> {
+        self.rules.iter().position(|r| r.rule.id() == rule_id)
+    }
+
+    /// All registered rules, enabled or not
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> {
+        self.rules.iter().map(|r| r.rule.as_ref())
+    }
+
+    /// Metadata of every registered rule
+    pub fn metadata(&self) -> impl Iterator<Item = &RuleMetadata> {
+        self.rules().map(|rule| rule.metadata())
+    }
+
+    /// Registered rules checking `language`
+    pub fn rules_for(&self, language: Language) -> impl Iterator<Item = &dyn Rule> {
+        self.rules().filter(move |rule| rule.metadata().supports(language))
+    }
+
+    pub fn get(&self, rule_id: &str) -> Option<&dyn Rule> {
+        self.position(rule_id).map(|index| self.rules[index].rule.as_ref())
+    }
+
+    pub fn is_enabled(&self, rule_id: &str) -> bool {
+        self.position(rule_id).is_some_and(|index| self.rules[index].enabled)
+    }
+
+    /// Enable or disable a rule, returning whether it is registered
+    pub fn set_enabled(&mut self, rule_id: &str, enabled: bool) -> bool {
+        match self.position(rule_id) {
+            Some(index) => {
+                self.rules[index].enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set a named threshold of a rule, returning whether the rule accepted it
+    pub fn set_threshold(&mut self, rule_id: &str, name: &str, value: u64) -> bool {
+        match self.position(rule_id) {
+            Some(index) => self.rules[index].rule.set_threshold(name, value),
+            None => false,
+        }
+    }
+
+    /// Run every enabled rule that supports the language of `input`
+    pub fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let language = input.language();
+        self.rules
+            .iter()
+            .filter(|r| r.enabled && r.rule.metadata().supports(language))
+            .flat_map(|r| r.rule.check(input))
+            .collect()
+    }
+
+    /// Parse `source` as `language`, run the applicable rules and apply
+    /// `gasguard-ignore` comments
+    pub fn analyze(&self, language: Language, source: &str, file_path: &str) -> Result<SuppressionReport, String> {
+        let violations = match language {
+            Language::Rust => {
+                let ast = syn::parse_file(source).map_err(|e| format!("Failed to parse Rust code: {}", e))?;
+                self.check(&RuleInput::Rust(&ast.items))
+            }
+            Language::Soroban => {
+                let contract = SorobanParser::parse_contract(source, file_path)
+                    .map_err(|e| format!("Soroban analysis failed: {}", e))?;
+                self.check(&RuleInput::Soroban(&contract))
+            }
+            Language::Vyper => {
+                let contract = VyperContract::parse(source)?;
+                self.check(&RuleInput::Vyper(&contract))
+            }
+            Language::Solidity => {
+                let ast = SolidityParser::parse(source).map_err(|e| format!("Failed to parse Solidity code: {}", e))?;
+                self.check(&RuleInput::Solidity(&ast))
+            }
+        };
+
+        Ok(Suppressions::parse(source, language.comment_prefix()).apply(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RuleCategory, SourceSpan, ViolationSeverity};
+
+    /// Flags every file it sees, in any language
+    struct EveryFileRule;
+
+    impl Rule for EveryFileRule {
+        fn metadata(&self) -> &RuleMetadata {
+            static METADATA: RuleMetadata = RuleMetadata {
+                id: "every-file",
+                name: "Every File",
+                description: "Reports one finding per file",
+                default_severity: ViolationSeverity::Info,
+                category: RuleCategory::BestPractice,
+                languages: &[Language::Rust, Language::Vyper],
+                tags: &[],
+            };
+            &METADATA
+        }
+
+        fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+            let subject = match input {
+                RuleInput::Rust(items) => format!("{} items", items.len()),
+                RuleInput::Vyper(contract) => format!("{} functions", contract.functions.len()),
+                RuleInput::Soroban(_) | RuleInput::Solidity(_) => unreachable!("not registered for this language"),
+            };
+            vec![RuleViolation {
+                rule_name: self.id().to_string(),
+                description: subject,
+                severity: self.severity(),
+                line_number: 1,
+                column_number: 1,
+                span: SourceSpan::default(),
+                variable_name: String::new(),
+                suggestion: String::new(),
+                fix: None,
+            }]
+        }
+    }
+
+    #[test]
+    fn test_multi_language_rule() {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(EveryFileRule));
+
+        let rust = registry.analyze(Language::Rust, "fn a() {}\nfn b() {}\n", "a.rs").unwrap();
+        assert_eq!(rust.violations[0].description, "2 items");
+
+        let vyper = registry.analyze(Language::Vyper, "@external\ndef f():\n    pass\n", "a.vy").unwrap();
+        assert_eq!(vyper.violations[0].description, "1 functions");
+
+        assert_eq!(registry.rules_for(Language::Soroban).count(), 0);
+    }
+
+    #[test]
+    fn test_enable_and_replace_rules() {
+        let mut registry = RuleRegistry::with_default_rules();
+        let count = registry.rules().count();
+        assert!(registry.get("soroban-unbounded-loop").is_some());
+        assert!(registry.get("vyper-redundant-external").is_some());
+        assert!(registry.get("unused-state-variables").is_some());
+        assert!(registry.get("solidity-uint8-vs-uint256").is_some());
+
+        assert!(registry.set_enabled("soroban-unbounded-loop", false));
+        assert!(!registry.is_enabled("soroban-unbounded-loop"));
+        assert!(!registry.set_enabled("no-such-rule", false));
+        assert!(registry.set_threshold("soroban-wasteful-ttl-extension", "max_threshold_percent", 80));
+        assert!(!registry.set_threshold("soroban-inefficient-storage", "max_operations", 5));
+
+        registry.register(Box::new(UnusedStateVariablesRule));
+        assert_eq!(registry.rules().count(), count);
+        assert!(registry.metadata().all(|m| !m.languages.is_empty() && m.docs_url().ends_with(m.id)));
+    }
+}
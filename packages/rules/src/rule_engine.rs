@@ -2,6 +2,10 @@
 //!
 //! Provides the fundamental traits and AST traversal logic for the rules engine.
 
+use crate::solidity::SolidityASTNode;
+use crate::soroban::SorobanContract;
+use crate::vyper::VyperContract;
+use crate::{Fix, Language, RuleRegistry, SourceSpan, SuppressionReport};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use syn::{Expr, Item, ItemImpl, ItemStruct, Member, Pat};
@@ -13,8 +17,14 @@ pub struct RuleViolation {
     pub severity: ViolationSeverity,
     pub line_number: usize,
     pub column_number: usize,
+    /// Exact location of the code that triggered the violation
+    #[serde(default)]
+    pub span: SourceSpan,
     pub variable_name: String,
     pub suggestion: String,
+    /// Machine-readable edits resolving the violation, when the rule can provide them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,35 +36,167 @@ pub enum ViolationSeverity {
     Info,
 }
 
-pub trait Rule {
-    fn name(&self) -> &str;
-    fn description(&self) -> &str;
-    fn check(&self, ast: &[Item]) -> Vec<RuleViolation>;
+impl std::str::FromStr for ViolationSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "high" => Ok(Self::High),
+            "medium" => Ok(Self::Medium),
+            "warning" => Ok(Self::Warning),
+            "info" => Ok(Self::Info),
+            _ => Err(format!("unknown severity '{}' (expected error, high, medium, warning or info)", s)),
+        }
+    }
+}
+
+/// Broad kind of problem a rule looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleCategory {
+    /// Wasted gas, storage or ledger rent
+    Gas,
+    /// Exploitable or unsafe behaviour
+    Security,
+    /// Maintainability and conventions
+    BestPractice,
+}
+
+/// Base URL of the rule reference; each rule has an anchor named after its ID
+pub const RULE_DOCS_URL: &str = "https://github.com/MDTechLabs/GasGuard/blob/main/docs/RULES.md";
+
+/// Static description of a rule, shared by every language
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleMetadata {
+    /// Stable identifier used in findings, config files and suppression comments
+    pub id: &'static str,
+    /// Human-readable name
+    pub name: &'static str,
+    /// What the rule checks for
+    pub description: &'static str,
+    /// Severity of findings unless the project config overrides it
+    pub default_severity: ViolationSeverity,
+    pub category: RuleCategory,
+    /// Languages whose input the rule checks
+    pub languages: &'static [Language],
+    pub tags: &'static [&'static str],
+}
+
+impl RuleMetadata {
+    pub fn docs_url(&self) -> String {
+        format!("{}#{}", RULE_DOCS_URL, self.id)
+    }
+
+    pub fn supports(&self, language: Language) -> bool {
+        self.languages.contains(&language)
+    }
+}
+
+/// Parsed source handed to rules, one variant per language frontend
+#[derive(Clone, Copy)]
+pub enum RuleInput<'a> {
+    Rust(&'a [Item]),
+    Soroban(&'a SorobanContract),
+    Vyper(&'a VyperContract),
+    Solidity(&'a SolidityASTNode),
+}
+
+impl<'a> RuleInput<'a> {
+    pub fn language(&self) -> Language {
+        match self {
+            RuleInput::Rust(_) => Language::Rust,
+            RuleInput::Soroban(_) => Language::Soroban,
+            RuleInput::Vyper(_) => Language::Vyper,
+            RuleInput::Solidity(_) => Language::Solidity,
+        }
+    }
+
+    pub fn rust(&self) -> Option<&'a [Item]> {
+        match *self {
+            RuleInput::Rust(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn soroban(&self) -> Option<&'a SorobanContract> {
+        match *self {
+            RuleInput::Soroban(contract) => Some(contract),
+            _ => None,
+        }
+    }
+
+    pub fn vyper(&self) -> Option<&'a VyperContract> {
+        match *self {
+            RuleInput::Vyper(contract) => Some(contract),
+            _ => None,
+        }
+    }
+
+    /// Source unit of a Solidity file
+    pub fn solidity(&self) -> Option<&'a SolidityASTNode> {
+        match *self {
+            RuleInput::Solidity(ast) => Some(ast),
+            _ => None,
+        }
+    }
 }
 
+/// A check run against parsed source of one or more languages
+///
+/// Rules are only handed input of the languages listed in their metadata.
+pub trait Rule: Send + Sync {
+    fn metadata(&self) -> &RuleMetadata;
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation>;
+
+    /// Set a named numeric threshold, returning whether the rule has such a threshold
+    fn set_threshold(&mut self, _name: &str, _value: u64) -> bool {
+        false
+    }
+
+    fn id(&self) -> &str {
+        self.metadata().id
+    }
+
+    fn severity(&self) -> ViolationSeverity {
+        self.metadata().default_severity.clone()
+    }
+}
+
+/// Runs rules against plain Rust sources
 pub struct RuleEngine {
-    rules: Vec<Box<dyn Rule>>,
+    registry: RuleRegistry,
 }
 
 impl RuleEngine {
     pub fn new() -> Self {
-        Self { rules: Vec::new() }
+        Self { registry: RuleRegistry::new() }
     }
 
     pub fn add_rule(mut self, rule: Box<dyn Rule>) -> Self {
-        self.rules.push(rule);
+        self.registry.register(rule);
         self
     }
 
+    pub fn get_rules(&self) -> Vec<&dyn Rule> {
+        self.registry.rules().collect()
+    }
+
     pub fn analyze(&self, code: &str) -> Result<Vec<RuleViolation>, String> {
-        let ast = syn::parse_file(code).map_err(|e| format!("Failed to parse Rust code: {}", e))?;
+        self.analyze_with_suppressions(code).map(|report| report.violations)
+    }
 
-        let mut violations = Vec::new();
-        for rule in &self.rules {
-            violations.extend(rule.check(&ast.items));
-        }
+    /// Analyze `code`, honouring `// gasguard-ignore` comments and keeping the
+    /// suppressed findings separately
+    pub fn analyze_with_suppressions(&self, code: &str) -> Result<SuppressionReport, String> {
+        self.registry.analyze(Language::Rust, code, "")
+    }
+}
 
-        Ok(violations)
+impl Default for RuleEngine {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
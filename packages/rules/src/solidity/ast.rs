@@ -0,0 +1,452 @@
+//! Solidity syntax tree
+//!
+//! Every node carries the span of the source it was parsed from. Inline
+//! assembly is kept as an opaque node.
+
+use crate::SourceSpan;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractKind {
+    Contract,
+    AbstractContract,
+    Interface,
+    Library,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    Function,
+    Constructor,
+    Modifier,
+    Fallback,
+    Receive,
+}
+
+/// Where a variable is declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableKind {
+    /// Contract storage, or a file-level constant
+    State,
+    Local,
+    Parameter,
+    Return,
+    StructMember,
+    EventParameter,
+    ErrorParameter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralKind {
+    Number,
+    String,
+    Bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolidityASTNode {
+    SourceUnit {
+        children: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    PragmaDirective {
+        value: String,
+        span: SourceSpan,
+    },
+    ImportDirective {
+        path: String,
+        span: SourceSpan,
+    },
+    UsingDirective {
+        value: String,
+        span: SourceSpan,
+    },
+    ContractDefinition {
+        kind: ContractKind,
+        name: String,
+        bases: Vec<String>,
+        children: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    StructDefinition {
+        name: String,
+        members: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    EnumDefinition {
+        name: String,
+        values: Vec<String>,
+        span: SourceSpan,
+    },
+    EventDefinition {
+        name: String,
+        parameters: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    ErrorDefinition {
+        name: String,
+        parameters: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    /// `type Name is underlying;`
+    TypeDefinition {
+        name: String,
+        underlying: String,
+        span: SourceSpan,
+    },
+    VariableDeclaration {
+        type_name: String,
+        /// Location of the type name alone
+        type_span: SourceSpan,
+        name: Option<String>,
+        kind: VariableKind,
+        /// Visibility, mutability, data location, `indexed` and `override`
+        attributes: Vec<String>,
+        value: Option<Box<SolidityASTNode>>,
+        span: SourceSpan,
+    },
+    FunctionDefinition {
+        kind: FunctionKind,
+        name: Option<String>,
+        parameters: Vec<SolidityASTNode>,
+        returns: Vec<SolidityASTNode>,
+        visibility: Option<String>,
+        mutability: Option<String>,
+        modifiers: Vec<SolidityASTNode>,
+        /// `virtual` and `override`
+        attributes: Vec<String>,
+        body: Option<Box<SolidityASTNode>>,
+        span: SourceSpan,
+    },
+    ModifierInvocation {
+        name: String,
+        arguments: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+
+    // Statements
+    Block {
+        statements: Vec<SolidityASTNode>,
+        unchecked: bool,
+        span: SourceSpan,
+    },
+    VariableDeclarationStatement {
+        /// Declared variables; tuple declarations may skip components
+        declarations: Vec<Option<SolidityASTNode>>,
+        value: Option<Box<SolidityASTNode>>,
+        span: SourceSpan,
+    },
+    ExpressionStatement {
+        expression: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    IfStatement {
+        condition: Box<SolidityASTNode>,
+        true_body: Box<SolidityASTNode>,
+        false_body: Option<Box<SolidityASTNode>>,
+        span: SourceSpan,
+    },
+    ForStatement {
+        init: Option<Box<SolidityASTNode>>,
+        condition: Option<Box<SolidityASTNode>>,
+        update: Option<Box<SolidityASTNode>>,
+        body: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    WhileStatement {
+        condition: Box<SolidityASTNode>,
+        body: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    DoWhileStatement {
+        body: Box<SolidityASTNode>,
+        condition: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    ReturnStatement {
+        expression: Option<Box<SolidityASTNode>>,
+        span: SourceSpan,
+    },
+    EmitStatement {
+        event: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    /// `revert CustomError(...)`; `revert("reason")` is a plain call
+    RevertStatement {
+        error: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    TryStatement {
+        expression: Box<SolidityASTNode>,
+        returns: Vec<SolidityASTNode>,
+        body: Box<SolidityASTNode>,
+        catch_clauses: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    CatchClause {
+        name: Option<String>,
+        parameters: Vec<SolidityASTNode>,
+        body: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    InlineAssembly {
+        span: SourceSpan,
+    },
+    Break {
+        span: SourceSpan,
+    },
+    Continue {
+        span: SourceSpan,
+    },
+    /// `_;` in a modifier body
+    Placeholder {
+        span: SourceSpan,
+    },
+
+    // Expressions
+    Identifier {
+        name: String,
+        span: SourceSpan,
+    },
+    Literal {
+        kind: LiteralKind,
+        value: String,
+        span: SourceSpan,
+    },
+    MemberAccess {
+        expression: Box<SolidityASTNode>,
+        member: String,
+        span: SourceSpan,
+    },
+    IndexAccess {
+        base: Box<SolidityASTNode>,
+        index: Option<Box<SolidityASTNode>>,
+        span: SourceSpan,
+    },
+    FunctionCall {
+        expression: Box<SolidityASTNode>,
+        arguments: Vec<SolidityASTNode>,
+        /// Argument names of a `f({a: 1, b: 2})` call, empty for positional calls
+        names: Vec<String>,
+        span: SourceSpan,
+    },
+    /// `target.call{value: v, gas: g}`
+    FunctionCallOptions {
+        expression: Box<SolidityASTNode>,
+        names: Vec<String>,
+        options: Vec<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    UnaryOperation {
+        operator: String,
+        prefix: bool,
+        operand: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    BinaryOperation {
+        operator: String,
+        left: Box<SolidityASTNode>,
+        right: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    Assignment {
+        operator: String,
+        left: Box<SolidityASTNode>,
+        right: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    Conditional {
+        condition: Box<SolidityASTNode>,
+        true_expression: Box<SolidityASTNode>,
+        false_expression: Box<SolidityASTNode>,
+        span: SourceSpan,
+    },
+    /// `(a, , b)` or, with `is_array`, `[a, b]`
+    TupleExpression {
+        components: Vec<Option<SolidityASTNode>>,
+        is_array: bool,
+        span: SourceSpan,
+    },
+    NewExpression {
+        type_name: String,
+        span: SourceSpan,
+    },
+}
+
+impl SolidityASTNode {
+    pub fn span(&self) -> SourceSpan {
+        use SolidityASTNode::*;
+        match self {
+            SourceUnit { span, .. }
+            | PragmaDirective { span, .. }
+            | ImportDirective { span, .. }
+            | UsingDirective { span, .. }
+            | ContractDefinition { span, .. }
+            | StructDefinition { span, .. }
+            | EnumDefinition { span, .. }
+            | EventDefinition { span, .. }
+            | ErrorDefinition { span, .. }
+            | TypeDefinition { span, .. }
+            | VariableDeclaration { span, .. }
+            | FunctionDefinition { span, .. }
+            | ModifierInvocation { span, .. }
+            | Block { span, .. }
+            | VariableDeclarationStatement { span, .. }
+            | ExpressionStatement { span, .. }
+            | IfStatement { span, .. }
+            | ForStatement { span, .. }
+            | WhileStatement { span, .. }
+            | DoWhileStatement { span, .. }
+            | ReturnStatement { span, .. }
+            | EmitStatement { span, .. }
+            | RevertStatement { span, .. }
+            | TryStatement { span, .. }
+            | CatchClause { span, .. }
+            | InlineAssembly { span }
+            | Break { span }
+            | Continue { span }
+            | Placeholder { span }
+            | Identifier { span, .. }
+            | Literal { span, .. }
+            | MemberAccess { span, .. }
+            | IndexAccess { span, .. }
+            | FunctionCall { span, .. }
+            | FunctionCallOptions { span, .. }
+            | UnaryOperation { span, .. }
+            | BinaryOperation { span, .. }
+            | Assignment { span, .. }
+            | Conditional { span, .. }
+            | TupleExpression { span, .. }
+            | NewExpression { span, .. } => *span,
+        }
+    }
+
+    /// Direct children in source order
+    pub fn children(&self) -> Vec<&SolidityASTNode> {
+        use SolidityASTNode::*;
+        let mut children: Vec<&SolidityASTNode> = Vec::new();
+        match self {
+            SourceUnit { children: nodes, .. } | ContractDefinition { children: nodes, .. } => {
+                children.extend(nodes)
+            }
+            StructDefinition { members: nodes, .. }
+            | EventDefinition { parameters: nodes, .. }
+            | ErrorDefinition { parameters: nodes, .. }
+            | ModifierInvocation { arguments: nodes, .. }
+            | Block { statements: nodes, .. } => children.extend(nodes),
+            VariableDeclaration { value, .. } => children.extend(value.as_deref()),
+            FunctionDefinition { parameters, returns, modifiers, body, .. } => {
+                children.extend(parameters);
+                children.extend(returns);
+                children.extend(modifiers);
+                children.extend(body.as_deref());
+            }
+            VariableDeclarationStatement { declarations, value, .. } => {
+                children.extend(declarations.iter().flatten());
+                children.extend(value.as_deref());
+            }
+            ExpressionStatement { expression, .. } => children.push(expression),
+            IfStatement { condition, true_body, false_body, .. } => {
+                children.push(condition);
+                children.push(true_body);
+                children.extend(false_body.as_deref());
+            }
+            ForStatement { init, condition, update, body, .. } => {
+                children.extend(init.as_deref());
+                children.extend(condition.as_deref());
+                children.extend(update.as_deref());
+                children.push(body);
+            }
+            WhileStatement { condition, body, .. } => {
+                children.push(condition);
+                children.push(body);
+            }
+            DoWhileStatement { body, condition, .. } => {
+                children.push(body);
+                children.push(condition);
+            }
+            ReturnStatement { expression, .. } => children.extend(expression.as_deref()),
+            EmitStatement { event: node, .. } | RevertStatement { error: node, .. } => children.push(node),
+            TryStatement { expression, returns, body, catch_clauses, .. } => {
+                children.push(expression);
+                children.extend(returns);
+                children.push(body);
+                children.extend(catch_clauses);
+            }
+            CatchClause { parameters, body, .. } => {
+                children.extend(parameters);
+                children.push(body);
+            }
+            MemberAccess { expression, .. } => children.push(expression),
+            IndexAccess { base, index, .. } => {
+                children.push(base);
+                children.extend(index.as_deref());
+            }
+            FunctionCall { expression, arguments: nodes, .. }
+            | FunctionCallOptions { expression, options: nodes, .. } => {
+                children.push(expression);
+                children.extend(nodes);
+            }
+            UnaryOperation { operand, .. } => children.push(operand),
+            BinaryOperation { left, right, .. } | Assignment { left, right, .. } => {
+                children.push(left);
+                children.push(right);
+            }
+            Conditional { condition, true_expression, false_expression, .. } => {
+                children.push(condition);
+                children.push(true_expression);
+                children.push(false_expression);
+            }
+            TupleExpression { components, .. } => children.extend(components.iter().flatten()),
+            PragmaDirective { .. }
+            | ImportDirective { .. }
+            | UsingDirective { .. }
+            | EnumDefinition { .. }
+            | TypeDefinition { .. }
+            | InlineAssembly { .. }
+            | Break { .. }
+            | Continue { .. }
+            | Placeholder { .. }
+            | Identifier { .. }
+            | Literal { .. }
+            | NewExpression { .. } => {}
+        }
+        children
+    }
+
+    /// Visit this node and all its descendants depth-first, passing each node's parent
+    pub fn walk<'a, F>(&'a self, mut visit: F)
+    where
+        F: FnMut(&'a SolidityASTNode, Option<&'a SolidityASTNode>),
+    {
+        self.walk_with_parent(None, &mut visit);
+    }
+
+    fn walk_with_parent<'a, F>(&'a self, parent: Option<&'a SolidityASTNode>, visit: &mut F)
+    where
+        F: FnMut(&'a SolidityASTNode, Option<&'a SolidityASTNode>),
+    {
+        visit(self, parent);
+        for child in self.children() {
+            child.walk_with_parent(Some(self), visit);
+        }
+    }
+
+    /// Name of a definition, declared variable or identifier
+    pub fn name(&self) -> Option<&str> {
+        use SolidityASTNode::*;
+        match self {
+            ContractDefinition { name, .. }
+            | StructDefinition { name, .. }
+            | EnumDefinition { name, .. }
+            | EventDefinition { name, .. }
+            | ErrorDefinition { name, .. }
+            | TypeDefinition { name, .. }
+            | ModifierInvocation { name, .. }
+            | Identifier { name, .. } => Some(name),
+            VariableDeclaration { name, .. } | FunctionDefinition { name, .. } | CatchClause { name, .. } => {
+                name.as_deref()
+            }
+            _ => None,
+        }
+    }
+}
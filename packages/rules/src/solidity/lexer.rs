@@ -0,0 +1,160 @@
+//! Solidity tokenizer
+
+use crate::SourceSpan;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Identifiers and keywords
+    Ident,
+    Number,
+    /// String literal, including `hex"..."` and `unicode"..."`
+    Str,
+    Punct,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn is(&self, text: &str) -> bool {
+        self.text == text && self.kind != TokenKind::Str
+    }
+}
+
+/// Operators and punctuation, longest first so the first match wins
+const PUNCTUATION: &[&str] = &[
+    ">>>=", "<<=", ">>=", ">>>", "**", "=>", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=",
+    "/=", "%=", "|=", "&=", "^=", "<<", ">>", "->", ":=", "+", "-", "*", "/", "%", "=", "<", ">", "!", "~", "&",
+    "|", "^", "?", ":", ";", ",", ".", "(", ")", "[", "]", "{", "}",
+];
+
+/// Split `source` into tokens, dropping whitespace and comments
+pub fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let rest = &source[pos..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+        } else if rest.starts_with("//") {
+            pos += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let close = comment
+                .find("*/")
+                .ok_or_else(|| error(source, pos, "unterminated block comment"))?;
+            pos += close + 4;
+        } else if c == '"' || c == '\'' {
+            let end = string_end(source, pos)?;
+            tokens.push(token(TokenKind::Str, source, pos, end));
+            pos = end;
+        } else if c.is_ascii_digit() || (c == '.' && rest[1..].starts_with(|d: char| d.is_ascii_digit())) {
+            let end = number_end(source, pos);
+            tokens.push(token(TokenKind::Number, source, pos, end));
+            pos = end;
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            // hex"..." and unicode"..." literals
+            if (word == "hex" || word == "unicode") && rest[len..].starts_with(['"', '\'']) {
+                let end = string_end(source, pos + len)?;
+                tokens.push(token(TokenKind::Str, source, pos, end));
+                pos = end;
+            } else {
+                tokens.push(token(TokenKind::Ident, source, pos, pos + len));
+                pos += len;
+            }
+        } else if let Some(punct) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) {
+            tokens.push(token(TokenKind::Punct, source, pos, pos + punct.len()));
+            pos += punct.len();
+        } else {
+            return Err(error(source, pos, &format!("unexpected character '{}'", c)));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn token(kind: TokenKind, source: &str, start: usize, end: usize) -> Token {
+    Token {
+        kind,
+        text: source[start..end].to_string(),
+        start,
+        end,
+    }
+}
+
+/// End of the string literal whose opening quote is at `start`
+fn string_end(source: &str, start: usize) -> Result<usize, String> {
+    let bytes = source.as_bytes();
+    let quote = bytes[start];
+    let mut pos = start + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            b'\n' => break,
+            b if b == quote => return Ok(pos + 1),
+            _ => pos += 1,
+        }
+    }
+    Err(error(source, start, "unterminated string literal"))
+}
+
+/// End of the number literal starting at `start`: decimal, hex or scientific
+fn number_end(source: &str, start: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut pos = start;
+    if source[start..].starts_with("0x") || source[start..].starts_with("0X") {
+        pos += 2;
+        while pos < bytes.len() && (bytes[pos].is_ascii_hexdigit() || bytes[pos] == b'_') {
+            pos += 1;
+        }
+        return pos;
+    }
+
+    while pos < bytes.len() {
+        let b = bytes[pos];
+        if b.is_ascii_digit() || b == b'_' || (b == b'.' && bytes.get(pos + 1).is_some_and(u8::is_ascii_digit)) {
+            pos += 1;
+        } else if (b == b'e' || b == b'E')
+            && (bytes.get(pos + 1).is_some_and(u8::is_ascii_digit)
+                || (bytes.get(pos + 1) == Some(&b'-') && bytes.get(pos + 2).is_some_and(u8::is_ascii_digit)))
+        {
+            pos += 2;
+        } else {
+            break;
+        }
+    }
+    pos
+}
+
+fn error(source: &str, pos: usize, message: &str) -> String {
+    let span = SourceSpan::from_byte_range(source, pos, pos);
+    format!("Line {}, column {}: {}", span.start_line, span.start_column, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let source = "x >>= 0x1F; // note\n/* block */ s = hex\"ab\" + 'c\\'d' + 1.5e3 ether;";
+        let texts: Vec<_> = tokenize(source).unwrap().into_iter().map(|t| t.text).collect();
+        assert_eq!(
+            texts,
+            vec!["x", ">>=", "0x1F", ";", "s", "=", "hex\"ab\"", "+", "'c\\'d'", "+", "1.5e3", "ether", ";"]
+        );
+        assert!(tokenize("string s = \"open").is_err());
+    }
+}
@@ -1,2 +1,10 @@
+pub mod ast;
+pub mod lexer;
 pub mod parser;
+pub mod rule_engine;
 pub mod uint8_vs_uint256;
+
+pub use ast::*;
+pub use parser::SolidityParser;
+pub use rule_engine::SolidityRuleEngine;
+pub use uint8_vs_uint256::Uint8VsUint256Rule;
@@ -0,0 +1,1248 @@
+//! Solidity parser
+//!
+//! A recursive descent parser over the tokens of [`super::lexer`], covering the
+//! declarations, statements and expressions of Solidity 0.4 through 0.8.
+
+use crate::solidity::ast::{ContractKind, FunctionKind, LiteralKind, SolidityASTNode, VariableKind};
+use crate::solidity::lexer::{tokenize, Token, TokenKind};
+use crate::SourceSpan;
+
+type Node = SolidityASTNode;
+type ParseResult<T> = Result<T, String>;
+
+const VISIBILITIES: &[&str] = &["public", "external", "internal", "private"];
+const MUTABILITIES: &[&str] = &["pure", "view", "payable", "constant"];
+const DATA_LOCATIONS: &[&str] = &["memory", "storage", "calldata"];
+const ASSIGNMENT_OPERATORS: &[&str] =
+    &["=", "+=", "-=", "*=", "/=", "%=", "|=", "&=", "^=", "<<=", ">>=", ">>>="];
+const UNITS: &[&str] = &[
+    "wei", "gwei", "szabo", "finney", "ether", "seconds", "minutes", "hours", "days", "weeks", "years",
+];
+/// Words that start an expression and so can never be a type name
+const EXPRESSION_KEYWORDS: &[&str] = &["delete", "new", "true", "false", "emit", "return"];
+
+pub struct SolidityParser;
+
+impl SolidityParser {
+    /// Parse a Solidity source file into its source unit node
+    pub fn parse(source: &str) -> Result<SolidityASTNode, String> {
+        let mut parser = Parser {
+            source,
+            tokens: tokenize(source)?,
+            pos: 0,
+            previous_end: 0,
+            line_starts: std::iter::once(0)
+                .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+                .collect(),
+        };
+        parser.source_unit()
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    /// End offset of the last consumed token
+    previous_end: usize,
+    /// Byte offset at which each line starts
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Parser<'a> {
+    // --- Token helpers ---
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn at(&self, text: &str) -> bool {
+        self.peek().is_some_and(|token| token.is(text))
+    }
+
+    fn at_any(&self, texts: &[&str]) -> bool {
+        texts.iter().any(|text| self.at(text))
+    }
+
+    fn at_kind(&self, kind: TokenKind) -> bool {
+        self.peek().is_some_and(|token| token.kind == kind)
+    }
+
+    fn next(&mut self) -> ParseResult<Token> {
+        let token = self.peek().cloned().ok_or_else(|| self.error("unexpected end of file"))?;
+        self.pos += 1;
+        self.previous_end = token.end;
+        Ok(token)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        if self.at(text) {
+            self.pos += 1;
+            self.previous_end = self.tokens[self.pos - 1].end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, text: &str) -> ParseResult<Token> {
+        if self.at(text) {
+            self.next()
+        } else {
+            Err(self.error(&format!("expected '{}'", text)))
+        }
+    }
+
+    fn ident(&mut self) -> ParseResult<String> {
+        if self.at_kind(TokenKind::Ident) {
+            Ok(self.next()?.text)
+        } else {
+            Err(self.error("expected identifier"))
+        }
+    }
+
+    /// Start offset of the next token
+    fn start(&self) -> usize {
+        self.peek().map_or(self.source.len(), |token| token.start)
+    }
+
+    /// Span from `start` to the end of the last consumed token
+    fn span_from(&self, start: usize) -> SourceSpan {
+        self.span(start, self.previous_end.max(start))
+    }
+
+    fn span(&self, start: usize, end: usize) -> SourceSpan {
+        let (start_line, start_column) = self.line_column(start);
+        let (end_line, end_column) = self.line_column(end);
+        SourceSpan {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            start_byte: start,
+            end_byte: end,
+        }
+    }
+
+    fn line_column(&self, byte: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&line_start| line_start <= byte) - 1;
+        let column = self.source[self.line_starts[line]..byte].chars().count() + 1;
+        (line + 1, column)
+    }
+
+    fn error(&self, message: &str) -> String {
+        let (line, column) = self.line_column(self.start());
+        match self.peek() {
+            Some(token) => format!("Line {}, column {}: {}, found '{}'", line, column, message, token.text),
+            None => format!("Line {}, column {}: {}", line, column, message),
+        }
+    }
+
+    /// Consume tokens up to and including the next `;`, returning the text before it
+    fn until_semicolon(&mut self) -> ParseResult<String> {
+        let start = self.start();
+        while !self.at(";") {
+            self.next()?;
+        }
+        let text = self.source[start..self.previous_end.max(start)].trim().to_string();
+        self.expect(";")?;
+        Ok(text)
+    }
+
+    /// Consume a balanced `(...)`, `[...]` or `{...}` group starting at the next token
+    fn skip_group(&mut self) -> ParseResult<()> {
+        let mut depth = 0usize;
+        loop {
+            let token = self.next()?;
+            match token.text.as_str() {
+                "(" | "[" | "{" if token.kind == TokenKind::Punct => depth += 1,
+                ")" | "]" | "}" if token.kind == TokenKind::Punct => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    // --- Declarations ---
+
+    fn source_unit(&mut self) -> ParseResult<Node> {
+        let mut children = Vec::new();
+        while self.peek().is_some() {
+            children.push(self.source_unit_item()?);
+        }
+        Ok(Node::SourceUnit {
+            children,
+            span: self.span(0, self.source.len()),
+        })
+    }
+
+    fn source_unit_item(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        match self.peek().map(|token| token.text.as_str()) {
+            Some("pragma") => {
+                self.next()?;
+                let value = self.until_semicolon()?;
+                Ok(Node::PragmaDirective {
+                    value,
+                    span: self.span_from(start),
+                })
+            }
+            Some("import") => {
+                self.next()?;
+                let mut path = String::new();
+                while !self.at(";") {
+                    let token = self.next()?;
+                    if token.kind == TokenKind::Str && path.is_empty() {
+                        path = token.text[1..token.text.len() - 1].to_string();
+                    }
+                }
+                self.expect(";")?;
+                Ok(Node::ImportDirective {
+                    path,
+                    span: self.span_from(start),
+                })
+            }
+            Some("abstract" | "contract" | "interface" | "library") => self.contract_definition(),
+            _ => self.definition(),
+        }
+    }
+
+    fn contract_definition(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let kind = match self.next()?.text.as_str() {
+            "abstract" => {
+                self.expect("contract")?;
+                ContractKind::AbstractContract
+            }
+            "interface" => ContractKind::Interface,
+            "library" => ContractKind::Library,
+            _ => ContractKind::Contract,
+        };
+        let name = self.ident()?;
+
+        let mut bases = Vec::new();
+        if self.eat("is") {
+            loop {
+                bases.push(self.path()?);
+                if self.at("(") {
+                    self.skip_group()?;
+                }
+                if !self.eat(",") {
+                    break;
+                }
+            }
+        }
+
+        self.expect("{")?;
+        let mut children = Vec::new();
+        while !self.at("}") {
+            children.push(self.definition()?);
+        }
+        self.expect("}")?;
+
+        Ok(Node::ContractDefinition {
+            kind,
+            name,
+            bases,
+            children,
+            span: self.span_from(start),
+        })
+    }
+
+    /// A definition allowed in a contract body or at file level
+    fn definition(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let next_is_paren = self.peek_at(1).is_some_and(|token| token.is("("));
+        match self.peek().map(|token| token.text.as_str()) {
+            Some("using") => {
+                self.next()?;
+                let value = self.until_semicolon()?;
+                Ok(Node::UsingDirective {
+                    value,
+                    span: self.span_from(start),
+                })
+            }
+            Some("struct") => self.struct_definition(),
+            Some("enum") => {
+                self.next()?;
+                let name = self.ident()?;
+                self.expect("{")?;
+                let mut values = Vec::new();
+                while !self.at("}") {
+                    values.push(self.ident()?);
+                    if !self.eat(",") {
+                        break;
+                    }
+                }
+                self.expect("}")?;
+                Ok(Node::EnumDefinition {
+                    name,
+                    values,
+                    span: self.span_from(start),
+                })
+            }
+            Some("event") => {
+                self.next()?;
+                let name = self.ident()?;
+                let parameters = self.parameter_list(VariableKind::EventParameter)?;
+                self.eat("anonymous");
+                self.expect(";")?;
+                Ok(Node::EventDefinition {
+                    name,
+                    parameters,
+                    span: self.span_from(start),
+                })
+            }
+            Some("error") if !next_is_paren => {
+                self.next()?;
+                let name = self.ident()?;
+                let parameters = self.parameter_list(VariableKind::ErrorParameter)?;
+                self.expect(";")?;
+                Ok(Node::ErrorDefinition {
+                    name,
+                    parameters,
+                    span: self.span_from(start),
+                })
+            }
+            Some("type") if self.peek_at(2).is_some_and(|token| token.is("is")) => {
+                self.next()?;
+                let name = self.ident()?;
+                self.expect("is")?;
+                let underlying = self.type_name()?;
+                self.expect(";")?;
+                Ok(Node::TypeDefinition {
+                    name,
+                    underlying,
+                    span: self.span_from(start),
+                })
+            }
+            Some("function") => self.function_definition(FunctionKind::Function),
+            Some("modifier") => self.function_definition(FunctionKind::Modifier),
+            Some("constructor") => self.function_definition(FunctionKind::Constructor),
+            Some("fallback") if next_is_paren => self.function_definition(FunctionKind::Fallback),
+            Some("receive") if next_is_paren => self.function_definition(FunctionKind::Receive),
+            _ => self.state_variable(),
+        }
+    }
+
+    fn struct_definition(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        self.expect("struct")?;
+        let name = self.ident()?;
+        self.expect("{")?;
+        let mut members = Vec::new();
+        while !self.at("}") {
+            let member_start = self.start();
+            let (type_name, type_span) = self.spanned_type_name()?;
+            let member_name = self.ident()?;
+            members.push(Node::VariableDeclaration {
+                type_name,
+                type_span,
+                name: Some(member_name),
+                kind: VariableKind::StructMember,
+                attributes: Vec::new(),
+                value: None,
+                span: self.span_from(member_start),
+            });
+            self.expect(";")?;
+        }
+        self.expect("}")?;
+        Ok(Node::StructDefinition {
+            name,
+            members,
+            span: self.span_from(start),
+        })
+    }
+
+    fn state_variable(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let (type_name, type_span) = self.spanned_type_name()?;
+        let mut attributes = Vec::new();
+        while self.at_any(VISIBILITIES) || self.at_any(&["constant", "immutable", "transient", "override"]) {
+            let attribute = self.next()?.text;
+            if attribute == "override" && self.at("(") {
+                self.skip_group()?;
+            }
+            attributes.push(attribute);
+        }
+        let name = self.ident()?;
+        let value = if self.eat("=") { Some(Box::new(self.expression()?)) } else { None };
+        let span = self.span_from(start);
+        self.expect(";")?;
+
+        Ok(Node::VariableDeclaration {
+            type_name,
+            type_span,
+            name: Some(name),
+            kind: VariableKind::State,
+            attributes,
+            value,
+            span,
+        })
+    }
+
+    fn function_definition(&mut self, kind: FunctionKind) -> ParseResult<Node> {
+        let start = self.start();
+        self.next()?;
+        let name = match kind {
+            FunctionKind::Function | FunctionKind::Modifier if self.at_kind(TokenKind::Ident) => Some(self.ident()?),
+            _ => None,
+        };
+        let parameters = if self.at("(") {
+            self.parameter_list(VariableKind::Parameter)?
+        } else {
+            Vec::new()
+        };
+
+        let mut returns = Vec::new();
+        let mut visibility = None;
+        let mut mutability = None;
+        let mut modifiers = Vec::new();
+        let mut attributes = Vec::new();
+        loop {
+            if self.eat("returns") {
+                returns = self.parameter_list(VariableKind::Return)?;
+            } else if self.at_any(VISIBILITIES) {
+                visibility = Some(self.next()?.text);
+            } else if self.at_any(MUTABILITIES) {
+                mutability = Some(self.next()?.text);
+            } else if self.at("virtual") || self.at("override") {
+                let attribute = self.next()?.text;
+                if attribute == "override" && self.at("(") {
+                    self.skip_group()?;
+                }
+                attributes.push(attribute);
+            } else if self.at_kind(TokenKind::Ident) {
+                let modifier_start = self.start();
+                let name = self.path()?;
+                let arguments = if self.at("(") { self.call_arguments()?.0 } else { Vec::new() };
+                modifiers.push(Node::ModifierInvocation {
+                    name,
+                    arguments,
+                    span: self.span_from(modifier_start),
+                });
+            } else {
+                break;
+            }
+        }
+
+        let body = if self.eat(";") { None } else { Some(Box::new(self.block()?)) };
+
+        Ok(Node::FunctionDefinition {
+            kind,
+            name,
+            parameters,
+            returns,
+            visibility,
+            mutability,
+            modifiers,
+            attributes,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    /// `(type [location] [name], ...)`
+    fn parameter_list(&mut self, kind: VariableKind) -> ParseResult<Vec<Node>> {
+        self.expect("(")?;
+        let mut parameters = Vec::new();
+        while !self.at(")") {
+            let start = self.start();
+            let (type_name, type_span) = self.spanned_type_name()?;
+            let mut attributes = Vec::new();
+            while self.at_any(DATA_LOCATIONS) || self.at("indexed") {
+                attributes.push(self.next()?.text);
+            }
+            let name = if self.at_kind(TokenKind::Ident) { Some(self.ident()?) } else { None };
+            parameters.push(Node::VariableDeclaration {
+                type_name,
+                type_span,
+                name,
+                kind,
+                attributes,
+                value: None,
+                span: self.span_from(start),
+            });
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(")")?;
+        Ok(parameters)
+    }
+
+    /// Dotted name such as `IERC20.Transfer`
+    fn path(&mut self) -> ParseResult<String> {
+        let mut path = self.ident()?;
+        while self.at(".") && self.peek_at(1).is_some_and(|token| token.kind == TokenKind::Ident) {
+            self.next()?;
+            path.push('.');
+            path.push_str(&self.ident()?);
+        }
+        Ok(path)
+    }
+
+    fn spanned_type_name(&mut self) -> ParseResult<(String, SourceSpan)> {
+        let start = self.start();
+        let type_name = self.type_name()?;
+        Ok((type_name, self.span_from(start)))
+    }
+
+    /// A type name, normalized to single spaces
+    fn type_name(&mut self) -> ParseResult<String> {
+        let mut name = if self.eat("mapping") {
+            self.expect("(")?;
+            let key = self.type_name()?;
+            if self.at_kind(TokenKind::Ident) {
+                self.next()?;
+            }
+            self.expect("=>")?;
+            let value = self.type_name()?;
+            if self.at_kind(TokenKind::Ident) {
+                self.next()?;
+            }
+            self.expect(")")?;
+            format!("mapping({} => {})", key, value)
+        } else if self.at("function") {
+            let start = self.start();
+            self.next()?;
+            self.parameter_list(VariableKind::Parameter)?;
+            while self.at_any(VISIBILITIES) || self.at_any(MUTABILITIES) {
+                self.next()?;
+            }
+            if self.eat("returns") {
+                self.parameter_list(VariableKind::Return)?;
+            }
+            self.source[start..self.previous_end]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            if self.peek().is_some_and(|token| EXPRESSION_KEYWORDS.contains(&token.text.as_str())) {
+                return Err(self.error("expected type name"));
+            }
+            let path = self.path()?;
+            if path == "address" && self.eat("payable") {
+                "address payable".to_string()
+            } else {
+                path
+            }
+        };
+
+        while self.eat("[") {
+            if self.eat("]") {
+                name.push_str("[]");
+            } else {
+                let length = self.expression()?;
+                self.expect("]")?;
+                name.push_str(&format!("[{}]", length.span().snippet(self.source)));
+            }
+        }
+        Ok(name)
+    }
+
+    // --- Statements ---
+
+    fn block(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let unchecked = self.eat("unchecked");
+        self.expect("{")?;
+        let mut statements = Vec::new();
+        while !self.at("}") {
+            statements.push(self.statement()?);
+        }
+        self.expect("}")?;
+        Ok(Node::Block {
+            statements,
+            unchecked,
+            span: self.span_from(start),
+        })
+    }
+
+    fn statement(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let second = self.peek_at(1).map(|token| (token.kind, token.text.clone()));
+        match self.peek().map(|token| token.text.as_str()) {
+            Some("{") => self.block(),
+            Some("unchecked") if second.as_ref().is_some_and(|(_, text)| text == "{") => self.block(),
+            Some("if") => {
+                self.next()?;
+                self.expect("(")?;
+                let condition = Box::new(self.expression()?);
+                self.expect(")")?;
+                let true_body = Box::new(self.statement()?);
+                let false_body = if self.eat("else") { Some(Box::new(self.statement()?)) } else { None };
+                Ok(Node::IfStatement {
+                    condition,
+                    true_body,
+                    false_body,
+                    span: self.span_from(start),
+                })
+            }
+            Some("for") => {
+                self.next()?;
+                self.expect("(")?;
+                let init = if self.eat(";") { None } else { Some(Box::new(self.simple_statement()?)) };
+                let condition = if self.at(";") { None } else { Some(Box::new(self.expression()?)) };
+                self.expect(";")?;
+                let update = if self.at(")") { None } else { Some(Box::new(self.expression()?)) };
+                self.expect(")")?;
+                let body = Box::new(self.statement()?);
+                Ok(Node::ForStatement {
+                    init,
+                    condition,
+                    update,
+                    body,
+                    span: self.span_from(start),
+                })
+            }
+            Some("while") => {
+                self.next()?;
+                self.expect("(")?;
+                let condition = Box::new(self.expression()?);
+                self.expect(")")?;
+                let body = Box::new(self.statement()?);
+                Ok(Node::WhileStatement {
+                    condition,
+                    body,
+                    span: self.span_from(start),
+                })
+            }
+            Some("do") => {
+                self.next()?;
+                let body = Box::new(self.statement()?);
+                self.expect("while")?;
+                self.expect("(")?;
+                let condition = Box::new(self.expression()?);
+                self.expect(")")?;
+                self.expect(";")?;
+                Ok(Node::DoWhileStatement {
+                    body,
+                    condition,
+                    span: self.span_from(start),
+                })
+            }
+            Some("return") => {
+                self.next()?;
+                let expression = if self.at(";") { None } else { Some(Box::new(self.expression()?)) };
+                self.expect(";")?;
+                Ok(Node::ReturnStatement {
+                    expression,
+                    span: self.span_from(start),
+                })
+            }
+            Some("emit") => {
+                self.next()?;
+                let event = Box::new(self.expression()?);
+                self.expect(";")?;
+                Ok(Node::EmitStatement {
+                    event,
+                    span: self.span_from(start),
+                })
+            }
+            Some("revert") if second.as_ref().is_some_and(|(kind, _)| *kind == TokenKind::Ident) => {
+                self.next()?;
+                let error = Box::new(self.expression()?);
+                self.expect(";")?;
+                Ok(Node::RevertStatement {
+                    error,
+                    span: self.span_from(start),
+                })
+            }
+            Some("try") => self.try_statement(),
+            Some("assembly") => {
+                self.next()?;
+                if self.at_kind(TokenKind::Str) {
+                    self.next()?;
+                }
+                if self.at("(") {
+                    self.skip_group()?;
+                }
+                if !self.at("{") {
+                    return Err(self.error("expected '{'"));
+                }
+                self.skip_group()?;
+                Ok(Node::InlineAssembly {
+                    span: self.span_from(start),
+                })
+            }
+            Some("break") => {
+                self.next()?;
+                self.expect(";")?;
+                Ok(Node::Break {
+                    span: self.span_from(start),
+                })
+            }
+            Some("continue") => {
+                self.next()?;
+                self.expect(";")?;
+                Ok(Node::Continue {
+                    span: self.span_from(start),
+                })
+            }
+            Some("_") if second.as_ref().is_some_and(|(_, text)| text == ";") => {
+                self.next()?;
+                self.next()?;
+                Ok(Node::Placeholder {
+                    span: self.span_from(start),
+                })
+            }
+            _ => self.simple_statement(),
+        }
+    }
+
+    fn try_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        self.expect("try")?;
+        let expression = Box::new(self.expression()?);
+        let returns = if self.eat("returns") {
+            self.parameter_list(VariableKind::Return)?
+        } else {
+            Vec::new()
+        };
+        let body = Box::new(self.block()?);
+
+        let mut catch_clauses = Vec::new();
+        while self.at("catch") {
+            let catch_start = self.start();
+            self.next()?;
+            let name = if self.at_kind(TokenKind::Ident) { Some(self.ident()?) } else { None };
+            let parameters = if self.at("(") {
+                self.parameter_list(VariableKind::Parameter)?
+            } else {
+                Vec::new()
+            };
+            let body = Box::new(self.block()?);
+            catch_clauses.push(Node::CatchClause {
+                name,
+                parameters,
+                body,
+                span: self.span_from(catch_start),
+            });
+        }
+
+        Ok(Node::TryStatement {
+            expression,
+            returns,
+            body,
+            catch_clauses,
+            span: self.span_from(start),
+        })
+    }
+
+    /// A variable declaration or expression statement, including its `;`
+    fn simple_statement(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        if let Some(declarations) = self.declaration_head() {
+            let value = if self.eat("=") { Some(Box::new(self.expression()?)) } else { None };
+            self.expect(";")?;
+            return Ok(Node::VariableDeclarationStatement {
+                declarations,
+                value,
+                span: self.span_from(start),
+            });
+        }
+
+        let expression = Box::new(self.expression()?);
+        self.expect(";")?;
+        Ok(Node::ExpressionStatement {
+            expression,
+            span: self.span_from(start),
+        })
+    }
+
+    /// The declared variables of a local declaration, or `None` with nothing
+    /// consumed when the statement is an expression
+    fn declaration_head(&mut self) -> Option<Vec<Option<Node>>> {
+        let (pos, previous_end) = (self.pos, self.previous_end);
+        let head = if self.eat("(") {
+            let mut declarations = Vec::new();
+            loop {
+                if self.at(",") || self.at(")") {
+                    declarations.push(None);
+                } else {
+                    match self.local_declaration() {
+                        Ok(declaration) => declarations.push(Some(declaration)),
+                        Err(_) => break None,
+                    }
+                }
+                if !self.eat(",") {
+                    break (self.eat(")") && self.at("=") && declarations.iter().any(Option::is_some))
+                        .then_some(declarations);
+                }
+            }
+        } else {
+            self.local_declaration()
+                .ok()
+                .filter(|_| self.at("=") || self.at(";"))
+                .map(|declaration| vec![Some(declaration)])
+        };
+
+        if head.is_none() {
+            self.pos = pos;
+            self.previous_end = previous_end;
+        }
+        head
+    }
+
+    fn local_declaration(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let (type_name, type_span) = self.spanned_type_name()?;
+        let mut attributes = Vec::new();
+        while self.at_any(DATA_LOCATIONS) {
+            attributes.push(self.next()?.text);
+        }
+        let name = self.ident()?;
+        Ok(Node::VariableDeclaration {
+            type_name,
+            type_span,
+            name: Some(name),
+            kind: VariableKind::Local,
+            attributes,
+            value: None,
+            span: self.span_from(start),
+        })
+    }
+
+    // --- Expressions ---
+
+    fn expression(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let left = self.conditional()?;
+        if self.at_any(ASSIGNMENT_OPERATORS) {
+            let operator = self.next()?.text;
+            let right = self.expression()?;
+            return Ok(Node::Assignment {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+                span: self.span_from(start),
+            });
+        }
+        Ok(left)
+    }
+
+    fn conditional(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let condition = self.binary(1)?;
+        if self.eat("?") {
+            let true_expression = self.expression()?;
+            self.expect(":")?;
+            let false_expression = self.expression()?;
+            return Ok(Node::Conditional {
+                condition: Box::new(condition),
+                true_expression: Box::new(true_expression),
+                false_expression: Box::new(false_expression),
+                span: self.span_from(start),
+            });
+        }
+        Ok(condition)
+    }
+
+    /// Binding power of the binary operator at the current token
+    fn binary_precedence(&self) -> Option<u8> {
+        let token = self.peek().filter(|token| token.kind == TokenKind::Punct)?;
+        Some(match token.text.as_str() {
+            "||" => 1,
+            "&&" => 2,
+            "==" | "!=" => 3,
+            "<" | ">" | "<=" | ">=" => 4,
+            "|" => 5,
+            "^" => 6,
+            "&" => 7,
+            "<<" | ">>" | ">>>" => 8,
+            "+" | "-" => 9,
+            "*" | "/" | "%" => 10,
+            "**" => 11,
+            _ => return None,
+        })
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> ParseResult<Node> {
+        let start = self.start();
+        let mut left = self.unary()?;
+        while let Some(precedence) = self.binary_precedence().filter(|p| *p >= min_precedence) {
+            let operator = self.next()?.text;
+            // `**` is right-associative
+            let next_precedence = if operator == "**" { precedence } else { precedence + 1 };
+            let right = self.binary(next_precedence)?;
+            left = Node::BinaryOperation {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+                span: self.span_from(start),
+            };
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        if self.at_any(&["!", "~", "-", "+", "++", "--", "delete"]) {
+            let operator = self.next()?.text;
+            let operand = self.unary()?;
+            return Ok(Node::UnaryOperation {
+                operator,
+                prefix: true,
+                operand: Box::new(operand),
+                span: self.span_from(start),
+            });
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let mut expression = self.primary()?;
+        loop {
+            if self.eat(".") {
+                let member = self.ident()?;
+                expression = Node::MemberAccess {
+                    expression: Box::new(expression),
+                    member,
+                    span: self.span_from(start),
+                };
+            } else if self.eat("[") {
+                let index = if self.at("]") || self.at(":") { None } else { Some(Box::new(self.expression()?)) };
+                // Slices keep only their start
+                if self.eat(":") && !self.at("]") {
+                    self.expression()?;
+                }
+                self.expect("]")?;
+                expression = Node::IndexAccess {
+                    base: Box::new(expression),
+                    index,
+                    span: self.span_from(start),
+                };
+            } else if self.at("(") {
+                let (arguments, names) = self.call_arguments()?;
+                expression = Node::FunctionCall {
+                    expression: Box::new(expression),
+                    arguments,
+                    names,
+                    span: self.span_from(start),
+                };
+            } else if self.at("{")
+                && self.peek_at(1).is_some_and(|token| token.kind == TokenKind::Ident)
+                && self.peek_at(2).is_some_and(|token| token.is(":"))
+            {
+                self.next()?;
+                let (options, names) = self.named_arguments("}")?;
+                expression = Node::FunctionCallOptions {
+                    expression: Box::new(expression),
+                    names,
+                    options,
+                    span: self.span_from(start),
+                };
+            } else if self.at("++") || self.at("--") {
+                let operator = self.next()?.text;
+                expression = Node::UnaryOperation {
+                    operator,
+                    prefix: false,
+                    operand: Box::new(expression),
+                    span: self.span_from(start),
+                };
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    /// `(a, b)` or `({name: a, other: b})`, returning the arguments and their names
+    fn call_arguments(&mut self) -> ParseResult<(Vec<Node>, Vec<String>)> {
+        self.expect("(")?;
+        if self.eat("{") {
+            let named = self.named_arguments("}")?;
+            self.expect(")")?;
+            return Ok(named);
+        }
+
+        let mut arguments = Vec::new();
+        while !self.at(")") {
+            arguments.push(self.expression()?);
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(")")?;
+        Ok((arguments, Vec::new()))
+    }
+
+    /// `name: value, ...` up to and including `close`
+    fn named_arguments(&mut self, close: &str) -> ParseResult<(Vec<Node>, Vec<String>)> {
+        let (mut values, mut names) = (Vec::new(), Vec::new());
+        while !self.at(close) {
+            names.push(self.ident()?);
+            self.expect(":")?;
+            values.push(self.expression()?);
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(close)?;
+        Ok((values, names))
+    }
+
+    fn primary(&mut self) -> ParseResult<Node> {
+        let start = self.start();
+        let token = self.peek().cloned().ok_or_else(|| self.error("expected expression"))?;
+        match token.kind {
+            TokenKind::Number => {
+                self.next()?;
+                if self.peek().is_some_and(|unit| unit.kind == TokenKind::Ident && UNITS.contains(&unit.text.as_str())) {
+                    self.next()?;
+                }
+                Ok(Node::Literal {
+                    kind: LiteralKind::Number,
+                    value: self.source[start..self.previous_end].to_string(),
+                    span: self.span_from(start),
+                })
+            }
+            TokenKind::Str => {
+                // Adjacent string literals are concatenated
+                while self.at_kind(TokenKind::Str) {
+                    self.next()?;
+                }
+                Ok(Node::Literal {
+                    kind: LiteralKind::String,
+                    value: self.source[start..self.previous_end].to_string(),
+                    span: self.span_from(start),
+                })
+            }
+            TokenKind::Ident if token.text == "true" || token.text == "false" => {
+                self.next()?;
+                Ok(Node::Literal {
+                    kind: LiteralKind::Bool,
+                    value: token.text,
+                    span: self.span_from(start),
+                })
+            }
+            TokenKind::Ident if token.text == "new" => {
+                self.next()?;
+                let type_name = self.type_name()?;
+                Ok(Node::NewExpression {
+                    type_name,
+                    span: self.span_from(start),
+                })
+            }
+            TokenKind::Ident => {
+                self.next()?;
+                let name = if token.text == "address" && self.at("payable") && !self.peek_at(1).is_some_and(|t| t.is("(")) {
+                    self.next()?;
+                    "address payable".to_string()
+                } else {
+                    token.text
+                };
+                Ok(Node::Identifier {
+                    name,
+                    span: self.span_from(start),
+                })
+            }
+            TokenKind::Punct if token.text == "(" || token.text == "[" => {
+                let is_array = token.text == "[";
+                let close = if is_array { "]" } else { ")" };
+                self.next()?;
+                let mut components = Vec::new();
+                loop {
+                    if self.at(",") || self.at(close) {
+                        components.push(None);
+                    } else {
+                        components.push(Some(self.expression()?));
+                    }
+                    if !self.eat(",") {
+                        break;
+                    }
+                }
+                self.expect(close)?;
+
+                // A parenthesized expression is just the expression
+                if !is_array && components.len() == 1 && components[0].is_some() {
+                    return Ok(components.remove(0).unwrap_or_else(|| unreachable!()));
+                }
+                if components.len() == 1 && components[0].is_none() {
+                    components.clear();
+                }
+                Ok(Node::TupleExpression {
+                    components,
+                    is_array,
+                    span: self.span_from(start),
+                })
+            }
+            _ => Err(self.error("expected expression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {IERC20} from "./IERC20.sol";
+
+/// A small token
+contract Token is IERC20, Ownable(msg.sender) {
+    struct Checkpoint {
+        uint32 fromBlock;
+        uint224 votes;
+    }
+
+    enum Status { Active, Paused }
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    error Insufficient(uint256 available);
+
+    mapping(address owner => uint256) public balances;
+    uint8 public constant DECIMALS = 18;
+    address payable immutable treasury;
+
+    modifier onlyTreasury() {
+        require(msg.sender == treasury, "not treasury");
+        _;
+    }
+
+    constructor(address payable treasury_) {
+        treasury = treasury_;
+    }
+
+    function transfer(address to, uint256 amount) external override returns (bool ok) {
+        uint256 balance = balances[msg.sender];
+        if (balance < amount) revert Insufficient({available: balance});
+        unchecked {
+            balances[msg.sender] = balance - amount;
+        }
+        balances[to] += amount;
+        emit Transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function sweep(address[] calldata targets) external onlyTreasury {
+        for (uint8 i = 0; i < targets.length; i++) {
+            (bool sent, ) = targets[i].call{value: 1 ether}("");
+            require(sent);
+        }
+        try IERC20(targets[0]).totalSupply() returns (uint256 supply) {
+            delete supply;
+        } catch Error(string memory) {
+        } catch {
+        }
+        assembly ("memory-safe") { let x := add(1, 2) }
+    }
+
+    receive() external payable {}
+}
+"#;
+
+    fn find(root: &SolidityASTNode, predicate: impl Fn(&SolidityASTNode) -> bool) -> Vec<&SolidityASTNode> {
+        let mut found = Vec::new();
+        root.walk(|node, _| {
+            if predicate(node) {
+                found.push(node);
+            }
+        });
+        found
+    }
+
+    #[test]
+    fn test_parse_contract_definitions() {
+        let ast = SolidityParser::parse(TOKEN).unwrap();
+        let SolidityASTNode::SourceUnit { children, .. } = &ast else { panic!("expected source unit") };
+        assert!(matches!(&children[0], SolidityASTNode::PragmaDirective { value, .. } if value == "solidity ^0.8.20"));
+        assert!(matches!(&children[1], SolidityASTNode::ImportDirective { path, .. } if path == "./IERC20.sol"));
+
+        let SolidityASTNode::ContractDefinition { kind, name, bases, children, span } = &children[2] else {
+            panic!("expected contract");
+        };
+        assert_eq!((kind, name.as_str()), (&ContractKind::Contract, "Token"));
+        assert_eq!(bases, &vec!["IERC20".to_string(), "Ownable".to_string()]);
+        assert_eq!(span.start_line, 7);
+
+        let functions: Vec<_> = children
+            .iter()
+            .filter_map(|child| match child {
+                SolidityASTNode::FunctionDefinition { kind, name, .. } => Some((*kind, name.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            functions,
+            vec![
+                (FunctionKind::Modifier, Some("onlyTreasury".to_string())),
+                (FunctionKind::Constructor, None),
+                (FunctionKind::Function, Some("transfer".to_string())),
+                (FunctionKind::Function, Some("sweep".to_string())),
+                (FunctionKind::Receive, None),
+            ]
+        );
+
+        let state: Vec<_> = find(&ast, |node| {
+            matches!(node, SolidityASTNode::VariableDeclaration { kind: VariableKind::State, .. })
+        });
+        let types: Vec<_> = state
+            .iter()
+            .map(|node| match node {
+                SolidityASTNode::VariableDeclaration { type_name, .. } => type_name.as_str(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(types, vec!["mapping(address => uint256)", "uint8", "address payable"]);
+    }
+
+    #[test]
+    fn test_parse_statements_and_expressions() {
+        let ast = SolidityParser::parse(TOKEN).unwrap();
+
+        let locals = find(&ast, |node| {
+            matches!(node, SolidityASTNode::VariableDeclaration { kind: VariableKind::Local, .. })
+        });
+        let names: Vec<_> = locals.iter().filter_map(|node| node.name()).collect();
+        assert_eq!(names, vec!["balance", "i", "sent"]);
+
+        let loop_span = find(&ast, |node| matches!(node, SolidityASTNode::ForStatement { .. }))[0].span();
+        assert_eq!(loop_span.snippet(TOKEN).lines().next().unwrap(), "for (uint8 i = 0; i < targets.length; i++) {");
+
+        assert_eq!(find(&ast, |node| matches!(node, SolidityASTNode::RevertStatement { .. })).len(), 1);
+        assert_eq!(find(&ast, |node| matches!(node, SolidityASTNode::CatchClause { .. })).len(), 2);
+        assert_eq!(find(&ast, |node| matches!(node, SolidityASTNode::InlineAssembly { .. })).len(), 1);
+        assert_eq!(find(&ast, |node| matches!(node, SolidityASTNode::Placeholder { .. })).len(), 1);
+        assert!(find(&ast, |node| matches!(node, SolidityASTNode::Block { unchecked: true, .. })).len() == 1);
+
+        let options = find(&ast, |node| matches!(node, SolidityASTNode::FunctionCallOptions { .. }));
+        assert_eq!(options[0].span().snippet(TOKEN), "targets[i].call{value: 1 ether}");
+
+        let compound = find(&ast, |node| {
+            matches!(node, SolidityASTNode::Assignment { operator, .. } if operator == "+=")
+        });
+        assert_eq!(compound[0].span().snippet(TOKEN), "balances[to] += amount");
+    }
+
+    #[test]
+    fn test_operator_precedence() {
+        let source = "contract C { function f() { x = a + b * c ** d ** e || f; } }";
+        let ast = SolidityParser::parse(source).unwrap();
+        let rhs = find(&ast, |node| matches!(node, SolidityASTNode::Assignment { .. }))[0];
+        let SolidityASTNode::Assignment { right, .. } = rhs else { unreachable!() };
+        let SolidityASTNode::BinaryOperation { operator, left, .. } = right.as_ref() else { panic!() };
+        assert_eq!(operator, "||");
+        assert_eq!(left.span().snippet(source), "a + b * c ** d ** e");
+        let SolidityASTNode::BinaryOperation { operator, right, .. } = left.as_ref() else { panic!() };
+        assert_eq!(operator, "+");
+        let SolidityASTNode::BinaryOperation { right: power, .. } = right.as_ref() else { panic!() };
+        let SolidityASTNode::BinaryOperation { operator, right, .. } = power.as_ref() else { panic!() };
+        assert_eq!((operator.as_str(), right.span().snippet(source)), ("**", "d ** e"));
+    }
+
+    #[test]
+    fn test_syntax_errors_report_location() {
+        let error = SolidityParser::parse("contract C {\n    function f( {\n}").unwrap_err();
+        assert!(error.starts_with("Line 2, column 17"), "{}", error);
+    }
+}
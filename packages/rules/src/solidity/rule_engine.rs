@@ -0,0 +1,52 @@
+//! Solidity rule engine
+
+use crate::solidity::Uint8VsUint256Rule;
+use crate::{Language, Rule, RuleRegistry, RuleViolation, SuppressionReport};
+
+/// Solidity rule engine for running Solidity-specific rules
+pub struct SolidityRuleEngine {
+    registry: RuleRegistry,
+}
+
+impl SolidityRuleEngine {
+    pub fn new() -> Self {
+        Self { registry: RuleRegistry::new() }
+    }
+
+    pub fn with_default_rules() -> Self {
+        let mut engine = Self::new();
+        for rule in Self::default_rules() {
+            engine.add_rule(rule);
+        }
+        engine
+    }
+
+    /// The built-in Solidity rules
+    pub fn default_rules() -> Vec<Box<dyn Rule>> {
+        vec![Box::new(Uint8VsUint256Rule)]
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+        self.registry.register(rule);
+    }
+
+    pub fn get_rules(&self) -> Vec<&dyn Rule> {
+        self.registry.rules().collect()
+    }
+
+    pub fn analyze(&self, source: &str) -> Result<Vec<RuleViolation>, String> {
+        self.analyze_with_suppressions(source).map(|report| report.violations)
+    }
+
+    /// Analyze `source`, honouring `// gasguard-ignore` comments and keeping the
+    /// suppressed findings separately
+    pub fn analyze_with_suppressions(&self, source: &str) -> Result<SuppressionReport, String> {
+        self.registry.analyze(Language::Solidity, source, "")
+    }
+}
+
+impl Default for SolidityRuleEngine {
+    fn default() -> Self {
+        Self::with_default_rules()
+    }
+}
@@ -1,40 +1,104 @@
-use crate::rule_engine::{Rule, RuleResult};
-use crate::solidity::parser::SolidityASTNode;
+use crate::rule_engine::{Rule, RuleCategory, RuleInput, RuleMetadata, RuleViolation, ViolationSeverity};
+use crate::solidity::ast::SolidityASTNode;
+use crate::{Applicability, Fix, Language, TextEdit};
 
+/// Rule for detecting `uint8` variables outside structs
+///
+/// The EVM works on 32-byte words, so a lone `uint8` costs extra masking
+/// operations without saving any storage. Only struct members, which can be
+/// packed into a shared slot, benefit from the narrower type.
 pub struct Uint8VsUint256Rule;
 
 impl Rule for Uint8VsUint256Rule {
-    fn id(&self) -> &'static str {
-        "uint8-vs-uint256"
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "solidity-uint8-vs-uint256",
+            name: "uint8 Instead of uint256",
+            description: "Using uint8 outside structs is often more gas-expensive than uint256 on EVM chains.",
+            default_severity: ViolationSeverity::Info,
+            category: RuleCategory::Gas,
+            languages: &[Language::Solidity],
+            tags: &["types"],
+        };
+        &METADATA
     }
 
-    fn description(&self) -> &'static str {
-        "Using uint8 outside structs is often more gas-expensive than uint256 on EVM chains."
-    }
-
-    fn analyze(&self, ast: &SolidityASTNode) -> Vec<RuleResult> {
-        let mut results = Vec::new();
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(ast) = input.solidity() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
 
         ast.walk(|node, parent| {
-            // Match variable declarations
-            if let SolidityASTNode::VariableDeclaration { type_name, location } = node {
-                // Only uint8
-                if type_name == "uint8" {
-                    // Ignore struct members
-                    if matches!(parent, Some(SolidityASTNode::StructDefinition { .. })) {
-                        return;
-                    }
-
-                    results.push(RuleResult {
-                        rule_id: self.id(),
-                        message: "uint8 used outside a struct. Consider using uint256 for better gas efficiency.".to_string(),
-                        location: location.clone(),
-                        severity: "LOW",
-                    });
-                }
+            let SolidityASTNode::VariableDeclaration { type_name, type_span, name, span, .. } = node else {
+                return;
+            };
+            // Struct members can share a storage slot
+            if type_name != "uint8" || matches!(parent, Some(SolidityASTNode::StructDefinition { .. })) {
+                return;
             }
+
+            let variable_name = name.clone().unwrap_or_default();
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "uint8 used outside a struct{}. Consider using uint256 for better gas efficiency.",
+                    name.as_ref().map(|n| format!(" for '{}'", n)).unwrap_or_default()
+                ),
+                severity: self.severity(),
+                line_number: span.start_line,
+                column_number: span.start_column,
+                span: *span,
+                variable_name,
+                suggestion: "Use uint256 unless the value is packed with other fields in a struct".to_string(),
+                fix: Some(Fix::new(
+                    "Widen to uint256",
+                    Applicability::MaybeIncorrect,
+                    vec![TextEdit::replace(*type_span, "uint256")],
+                )),
+            });
         });
 
-        results
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solidity::SolidityRuleEngine;
+
+    #[test]
+    fn test_flags_uint8_outside_structs() {
+        let source = r#"pragma solidity ^0.8.0;
+
+contract Counter {
+    struct Packed {
+        uint8 small;
+        uint248 rest;
+    }
+
+    uint8 public count;
+
+    function bump(uint8 step) external {
+        for (uint256 i = 0; i < step; i++) {
+            count += 1;
+        }
+    }
+}
+"#;
+        let violations = SolidityRuleEngine::with_default_rules().analyze(source).unwrap();
+        let names: Vec<_> = violations.iter().map(|v| v.variable_name.as_str()).collect();
+        assert_eq!(names, vec!["count", "step"]);
+        assert_eq!(violations[0].line_number, 9);
+        assert_eq!(violations[0].span.snippet(source), "uint8 public count");
+    }
+
+    #[test]
+    fn test_fix_widens_type() {
+        let source = "contract C {\n    uint8 level;\n}\n";
+        let violations = SolidityRuleEngine::with_default_rules().analyze(source).unwrap();
+        let fix = violations[0].fix.as_ref().unwrap();
+        let fixed = crate::apply_edits(source, &fix.edits).unwrap();
+        assert_eq!(fixed, "contract C {\n    uint256 level;\n}\n");
     }
 }
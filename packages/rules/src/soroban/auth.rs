@@ -0,0 +1,357 @@
+//! Authorization flow
+//!
+//! Works out, for each contract entry point, which addresses are authorized
+//! with `require_auth()` and in what order relative to the storage writes and
+//! token transfers the function makes. Private helpers such as
+//! `Self::check_admin_auth(&env)` are followed, with their parameters mapped
+//! back to the arguments the entry point passes.
+
+use super::{
+    client_type, CallGraph, CallKind, SorobanContract, SorobanFunction, StatementKind, StorageKey, StorageOperation, StorageTier,
+};
+use crate::SourceSpan;
+use std::collections::HashMap;
+
+/// Calls authorizing their receiver
+const AUTH_METHODS: &[&str] = &["require_auth", "require_auth_for_args"];
+
+/// Token client methods moving funds out of their first argument's balance
+const TRANSFER_METHODS: &[&str] = &["transfer", "transfer_from", "burn", "burn_from"];
+
+/// The address an authorization check or a transfer applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthSubject {
+    /// An `Address` parameter of the entry point
+    Parameter(String),
+    /// An address loaded from storage, e.g. the admin, named by its key
+    Stored(String),
+    /// Any other expression, such as `env.current_contract_address()`
+    Other(String),
+}
+
+/// An authorization check reached from an entry point
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthCheck {
+    pub subject: AuthSubject,
+    /// Location in the entry point: the check itself, or the helper call leading to it
+    pub span: SourceSpan,
+    /// Helpers called on the way to the check, outermost first
+    pub via: Vec<String>,
+}
+
+/// State-changing operations guarded by authorization
+#[derive(Debug, Clone, PartialEq)]
+pub enum EffectKind {
+    /// A storage `set`, `update` or `remove`
+    StorageWrite { tier: StorageTier, key: Option<String> },
+    /// A token transfer or burn out of `from`
+    TokenTransfer { method: String, from: AuthSubject },
+}
+
+/// A state-changing operation reached from an entry point
+#[derive(Debug, Clone, PartialEq)]
+pub struct Effect {
+    pub kind: EffectKind,
+    /// Location in the entry point: the operation itself, or the helper call leading to it
+    pub span: SourceSpan,
+    /// Helpers called on the way to the operation, outermost first
+    pub via: Vec<String>,
+}
+
+impl Effect {
+    /// Short description such as `writes persistent key 'DataKey::Balance'`
+    pub fn describe(&self) -> String {
+        let what = match &self.kind {
+            EffectKind::StorageWrite { tier, key: Some(key) } => format!("writes {} key '{}'", tier.method(), key),
+            EffectKind::StorageWrite { tier, key: None } => format!("writes {} storage", tier.method()),
+            EffectKind::TokenTransfer { method, from } => format!("calls token '{}' from {}", method, from.describe()),
+        };
+        match self.via.first() {
+            Some(helper) => format!("{} through '{}'", what, helper),
+            None => what,
+        }
+    }
+}
+
+impl AuthSubject {
+    /// Whether the subject is the contract's own address
+    pub fn is_current_contract(&self) -> bool {
+        matches!(self, Self::Other(expression) if expression.contains("current_contract_address()"))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Parameter(name) => format!("parameter '{}'", name),
+            Self::Stored(key) => format!("the address stored under '{}'", key),
+            Self::Other(expression) => format!("'{}'", expression),
+        }
+    }
+}
+
+/// Authorization checks and effects of one entry point, in execution order
+#[derive(Debug, Clone)]
+pub struct AuthFlow<'a> {
+    pub function: &'a SorobanFunction,
+    pub checks: Vec<AuthCheck>,
+    pub effects: Vec<Effect>,
+}
+
+/// A check or effect, ordered by where it ends in the entry point
+enum Event {
+    Check(AuthCheck),
+    Effect(Effect),
+}
+
+impl<'a> AuthFlow<'a> {
+    /// Flows of every public contract function
+    pub fn analyze_contract(contract: &'a SorobanContract) -> Vec<Self> {
+        let graph = CallGraph::build(contract);
+        graph
+            .entry_points()
+            .map(|function| Self::analyze_in(&graph, function))
+            .collect()
+    }
+
+    pub fn analyze(contract: &'a SorobanContract, function: &'a SorobanFunction) -> Self {
+        Self::analyze_in(&CallGraph::build(contract), function)
+    }
+
+    /// Flow of `function`, following calls through `graph`
+    pub fn analyze_in(graph: &CallGraph<'a>, function: &'a SorobanFunction) -> Self {
+        let mut events = Vec::new();
+        let mut stack = vec![function];
+        collect(graph, function, &HashMap::new(), &mut stack, &mut events);
+
+        let mut flow = Self {
+            function,
+            checks: Vec::new(),
+            effects: Vec::new(),
+        };
+        for (_, event) in events {
+            match event {
+                Event::Check(check) => flow.checks.push(check),
+                Event::Effect(effect) => flow.effects.push(effect),
+            }
+        }
+        flow
+    }
+
+    /// Whether any address is authorized
+    pub fn is_authorized(&self) -> bool {
+        !self.checks.is_empty()
+    }
+
+    /// Whether `subject` is authorized
+    pub fn authorizes(&self, subject: &AuthSubject) -> bool {
+        self.checks.iter().any(|check| &check.subject == subject)
+    }
+
+    /// Whether an address loaded from storage, such as an admin, is authorized
+    pub fn authorizes_stored(&self) -> bool {
+        self.checks.iter().any(|check| matches!(check.subject, AuthSubject::Stored(_)))
+    }
+
+    /// Effects that run before the first authorization check, when there is one
+    pub fn effects_before_auth(&self) -> impl Iterator<Item = &Effect> {
+        let first = self.checks.first().map(|check| check.span.end_byte);
+        self.effects
+            .iter()
+            .filter(move |effect| first.is_some_and(|first| effect.span.end_byte < first))
+    }
+}
+
+/// Collect the events of `function`, called with `arguments` bound to its parameters,
+/// into `events` keyed by their position in the entry point
+fn collect<'a>(
+    graph: &CallGraph<'a>,
+    function: &'a SorobanFunction,
+    arguments: &HashMap<String, AuthSubject>,
+    stack: &mut Vec<&'a SorobanFunction>,
+    events: &mut Vec<(usize, Event)>,
+) {
+    // Parameters only name entry point inputs in the entry point itself
+    let entry = stack.len() == 1;
+
+    let mut local: Vec<(usize, Event)> = Vec::new();
+    for call in &function.body.calls {
+        let position = call.call_span.end_byte;
+        if call.kind == CallKind::Method && AUTH_METHODS.contains(&call.name.as_str()) {
+            let Some(receiver) = call.receiver.as_deref() else { continue };
+            local.push((
+                position,
+                Event::Check(AuthCheck {
+                    subject: subject(function, receiver, call.span, arguments, entry),
+                    span: call.span,
+                    via: Vec::new(),
+                }),
+            ));
+        } else if call.kind == CallKind::Method
+            && TRANSFER_METHODS.contains(&call.name.as_str())
+            && call.receiver.as_deref().is_some_and(|receiver| client_type(function, receiver, call.span).is_some())
+        {
+            let Some(from) = call.args.first() else { continue };
+            local.push((
+                position,
+                Event::Effect(Effect {
+                    kind: EffectKind::TokenTransfer {
+                        method: call.name.clone(),
+                        from: subject(function, from, call.span, arguments, entry),
+                    },
+                    span: call.span,
+                    via: Vec::new(),
+                }),
+            ));
+        } else if let Some(helper) = graph.resolve(function, call) {
+            if stack.iter().any(|seen| std::ptr::eq(*seen, helper)) {
+                continue;
+            }
+
+            // Bind the helper's parameters to what this call passes
+            let bound = helper
+                .params
+                .iter()
+                .zip(&call.args)
+                .map(|(param, arg)| (param.name.clone(), subject(function, arg, call.span, arguments, entry)))
+                .collect();
+            stack.push(helper);
+            let mut nested = Vec::new();
+            collect(graph, helper, &bound, stack, &mut nested);
+            stack.pop();
+
+            for (_, event) in nested {
+                let event = match event {
+                    Event::Check(mut check) => {
+                        check.span = call.span;
+                        check.via.insert(0, helper.name.clone());
+                        Event::Check(check)
+                    }
+                    Event::Effect(mut effect) => {
+                        effect.span = call.span;
+                        effect.via.insert(0, helper.name.clone());
+                        Event::Effect(effect)
+                    }
+                };
+                local.push((position, event));
+            }
+        }
+    }
+
+    for access in &function.body.storage_accesses {
+        if !access.operation.is_write() {
+            continue;
+        }
+        let key = StorageKey::resolve(function, access).map(|key| key.name);
+        local.push((
+            access.span.end_byte,
+            Event::Effect(Effect {
+                kind: EffectKind::StorageWrite { tier: access.tier, key },
+                span: access.span,
+                via: Vec::new(),
+            }),
+        ));
+    }
+
+    // The sort is stable, so a helper's events keep their order
+    local.sort_by_key(|(position, _)| *position);
+    events.extend(local);
+}
+
+/// What a receiver or argument expression refers to, seen from `function`
+fn subject(
+    function: &SorobanFunction,
+    expression: &str,
+    at: SourceSpan,
+    arguments: &HashMap<String, AuthSubject>,
+    entry: bool,
+) -> AuthSubject {
+    let expression = expression.trim_start_matches('&').trim().trim_end_matches(".clone()");
+    if let Some(bound) = arguments.get(expression) {
+        return bound.clone();
+    }
+    if entry && function.params.iter().any(|param| param.name == expression) {
+        return AuthSubject::Parameter(expression.to_string());
+    }
+
+    // `let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();`
+    let binding = function
+        .body
+        .statements
+        .iter()
+        .rev()
+        .filter(|statement| statement.span.end_byte <= at.start_byte)
+        .find(|statement| {
+            matches!(&statement.kind, StatementKind::Let { pattern, .. }
+                if pattern.trim_start_matches("mut ") == expression)
+        });
+    if let Some(statement) = binding {
+        let stored = function
+            .body
+            .storage_accesses_within(statement.span)
+            .find(|access| access.operation == StorageOperation::Get)
+            .and_then(|access| StorageKey::resolve(function, access));
+        if let Some(key) = stored {
+            return AuthSubject::Stored(key.name);
+        }
+        if let StatementKind::Let { init: Some(init), .. } = &statement.kind {
+            return AuthSubject::Other(init.clone());
+        }
+    }
+    AuthSubject::Other(expression.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_auth_flow_follows_helpers() {
+        let source = r#"
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        Self::check_admin(&env);
+        let token = token::Client::new(&env, &Self::token(&env));
+        token.transfer(&env.current_contract_address(), &to, &amount);
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        env.storage().persistent().set(&DataKey::Balance(from.clone()), &amount);
+        Self::authorize(&from);
+    }
+
+    fn check_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+    }
+
+    fn authorize(user: &Address) {
+        user.require_auth();
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "vault.rs").unwrap();
+        let flows = AuthFlow::analyze_contract(&contract);
+
+        let withdraw = &flows[0];
+        assert_eq!(withdraw.checks.len(), 1);
+        assert_eq!(withdraw.checks[0].subject, AuthSubject::Stored("DataKey::Admin".to_string()));
+        assert_eq!(withdraw.checks[0].via, vec!["check_admin"]);
+        assert_eq!(withdraw.checks[0].span.start_line, 8);
+        assert!(withdraw.authorizes_stored());
+        assert!(matches!(
+            &withdraw.effects[0].kind,
+            EffectKind::TokenTransfer { from: AuthSubject::Other(from), .. } if from == "env.current_contract_address()"
+        ));
+        assert_eq!(withdraw.effects_before_auth().count(), 0);
+
+        let deposit = &flows[1];
+        assert!(deposit.authorizes(&AuthSubject::Parameter("from".to_string())));
+        let early: Vec<_> = deposit.effects_before_auth().collect();
+        assert_eq!(early.len(), 1);
+        assert_eq!(early[0].describe(), "writes persistent key 'DataKey::Balance'");
+    }
+}
@@ -0,0 +1,724 @@
+//! Statement-level model of Soroban function bodies
+//!
+//! Lowers the syn block of a contract function into source-ordered lists of the
+//! statements, calls, loops and storage accesses it contains, so rules can query
+//! the code itself instead of searching raw text (which also matches comments,
+//! string literals and unrelated identifiers). Nesting is recovered through span
+//! containment, e.g. the calls made inside a loop are the calls whose span lies
+//! within the loop's span.
+
+use super::SorobanParser;
+use crate::SourceSpan;
+use std::collections::BTreeSet;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, Member, Pat, Stmt, Token};
+
+/// Parsed body of a Soroban function
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FunctionBody {
+    /// Every statement in the body, including those in nested blocks, in source order
+    pub statements: Vec<Statement>,
+    /// Every function call, method call and macro invocation, in evaluation order
+    pub calls: Vec<Call>,
+    /// Every `for`, `while` and `loop` expression, in source order
+    pub loops: Vec<Loop>,
+    /// Storage accessor chains such as `env.storage().instance().get(..)`, in evaluation order
+    pub storage_accesses: Vec<StorageAccess>,
+    /// Uses of single-identifier paths such as locals and parameters, in source order
+    pub local_references: Vec<LocalReference>,
+    /// Assignments, plain and compound, in source order
+    pub assignments: Vec<Assignment>,
+    /// Additions, subtractions and multiplications, including their compound
+    /// assignment forms, in source order
+    pub arithmetic: Vec<Arithmetic>,
+    /// Conditions that end the function early, in source order
+    pub guards: Vec<Guard>,
+    /// Every block nested in the function body, in source order
+    pub blocks: Vec<SourceSpan>,
+    /// Identifiers referenced by the signature and body, excluding comments and literals
+    pub identifiers: BTreeSet<String>,
+}
+
+/// A use of a local variable or parameter by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalReference {
+    pub name: String,
+    pub span: SourceSpan,
+}
+
+/// An assignment such as `self.paused = true` or `total += amount`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    /// Source text of the assigned place, e.g. `self.paused`
+    pub target: String,
+    pub span: SourceSpan,
+}
+
+/// An operation that can overflow, such as `a + b` or `total -= amount`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arithmetic {
+    /// The operator, e.g. `+` or `-=`
+    pub operator: String,
+    /// Source text of the left operand
+    pub left: String,
+    /// Source text of the right operand
+    pub right: String,
+    pub span: SourceSpan,
+}
+
+impl Arithmetic {
+    /// Name of the checked method replacing the operator, e.g. `checked_add`
+    pub fn checked_method(&self) -> &'static str {
+        match self.operator.trim_end_matches('=') {
+            "+" => "checked_add",
+            "-" => "checked_sub",
+            _ => "checked_mul",
+        }
+    }
+
+    /// Whether the operator assigns its result to the left operand
+    pub fn is_assignment(&self) -> bool {
+        self.operator.ends_with('=')
+    }
+}
+
+/// A condition checked before the function goes on, such as
+/// `if n > MAX { return Err(..) }` or `assert!(n <= MAX)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guard {
+    pub kind: GuardKind,
+    /// Source text of the condition
+    pub condition: String,
+    pub span: SourceSpan,
+}
+
+/// How a guard stops the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardKind {
+    /// `assert!(condition)`: the function goes on when the condition holds
+    Assert,
+    /// `if condition { return .. }` or a panic: the function goes on when it fails
+    Exit,
+}
+
+/// A statement in a function body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub kind: StatementKind,
+    /// Block nesting depth, 0 for the function's top-level statements
+    pub depth: usize,
+    pub span: SourceSpan,
+}
+
+/// Kinds of statements
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    /// `let pattern: type_name = init;`
+    Let {
+        pattern: String,
+        type_name: Option<String>,
+        init: Option<String>,
+    },
+    /// An expression, with or without a trailing semicolon
+    Expr,
+    /// A macro invocation in statement position, e.g. `panic!(..);`
+    Macro { name: String },
+    /// A nested item such as a `use` or a local `fn`
+    Item,
+}
+
+/// A function call, method call or macro invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub kind: CallKind,
+    /// Called function, method or macro name without its path, e.g. `new`, `get`, `format`
+    pub name: String,
+    /// Full callee path for function calls and macros, e.g. `token::Client::new`,
+    /// or the method name for method calls
+    pub path: String,
+    /// Method chain leading to the receiver of a method call, e.g. `["env", "storage",
+    /// "instance"]` for `env.storage().instance().get(&key)`
+    pub receiver_chain: Vec<String>,
+    /// Source text of the receiver of a method call
+    pub receiver: Option<String>,
+    /// Source text of each argument
+    pub args: Vec<String>,
+    /// Location of the whole call expression
+    pub span: SourceSpan,
+    /// Location of the call itself; for method calls this starts at the `.` and
+    /// excludes the receiver
+    pub call_span: SourceSpan,
+}
+
+/// Kinds of calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    Function,
+    Method,
+    Macro,
+}
+
+/// A loop expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loop {
+    pub kind: LoopKind,
+    /// Loop variable pattern of a `for` loop
+    pub pattern: Option<String>,
+    /// Iterated expression of a `for` loop or condition of a `while` loop
+    pub header: Option<String>,
+    /// Location of the loop keyword and header, up to the start of the body
+    pub header_span: SourceSpan,
+    /// Location of the body block
+    pub body_span: SourceSpan,
+    /// Location of the whole loop, including its body
+    pub span: SourceSpan,
+}
+
+/// Kinds of loops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopKind {
+    For,
+    While,
+    Loop,
+}
+
+/// An access to contract storage through `env.storage().<tier>().<operation>(..)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageAccess {
+    pub tier: StorageTier,
+    pub operation: StorageOperation,
+    /// Source text of the key argument without a leading `&`, absent for
+    /// operations on the whole tier such as `instance().extend_ttl(..)`
+    pub key: Option<String>,
+    /// Source text of each argument, including the key
+    pub args: Vec<String>,
+    /// Location of the whole accessor chain
+    pub span: SourceSpan,
+}
+
+/// Soroban storage tiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageTier {
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+impl StorageTier {
+    fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "instance" => Some(Self::Instance),
+            "persistent" => Some(Self::Persistent),
+            "temporary" => Some(Self::Temporary),
+            _ => None,
+        }
+    }
+
+    /// Name of the `env.storage()` method selecting this tier
+    pub fn method(&self) -> &'static str {
+        match self {
+            Self::Instance => "instance",
+            Self::Persistent => "persistent",
+            Self::Temporary => "temporary",
+        }
+    }
+}
+
+/// Storage operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageOperation {
+    Get,
+    Has,
+    Set,
+    Update,
+    Remove,
+    ExtendTtl,
+}
+
+impl StorageOperation {
+    fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "get" => Some(Self::Get),
+            "has" => Some(Self::Has),
+            "set" => Some(Self::Set),
+            "update" | "try_update" => Some(Self::Update),
+            "remove" => Some(Self::Remove),
+            // `bump` is the name used before soroban-sdk 20
+            "extend_ttl" | "bump" => Some(Self::ExtendTtl),
+            _ => None,
+        }
+    }
+
+    /// Whether the operation reads the stored value
+    pub fn is_read(&self) -> bool {
+        matches!(self, Self::Get | Self::Has | Self::Update)
+    }
+
+    /// Whether the operation modifies the stored value
+    pub fn is_write(&self) -> bool {
+        matches!(self, Self::Set | Self::Update | Self::Remove)
+    }
+}
+
+impl FunctionBody {
+    /// Lower a function's signature and body
+    pub fn from_fn(sig: &syn::Signature, block: &syn::Block, source: &str) -> Self {
+        let mut collector = BodyCollector {
+            source,
+            depth: 0,
+            body: FunctionBody::default(),
+        };
+        collector.visit_signature(sig);
+        for stmt in &block.stmts {
+            collector.visit_stmt(stmt);
+        }
+
+        let mut body = collector.body;
+        body.calls.sort_by_key(|call| call.call_span.end_byte);
+        body.storage_accesses.sort_by_key(|access| access.span.end_byte);
+        body
+    }
+
+    /// Calls to a function, method or macro with the given name
+    pub fn calls_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Call> + 'a {
+        self.calls.iter().filter(move |call| call.name == name)
+    }
+
+    /// Whether the body calls a function, method or macro with the given name
+    pub fn has_call(&self, name: &str) -> bool {
+        self.calls_named(name).next().is_some()
+    }
+
+    /// Whether the body performs an explicit authorization check
+    pub fn has_auth_check(&self) -> bool {
+        self.calls.iter().any(|call| {
+            matches!(call.name.as_str(), "require_auth" | "require_auth_for_args" | "authorize")
+        })
+    }
+
+    /// Calls located inside `span`, e.g. the body of a loop
+    pub fn calls_within(&self, span: SourceSpan) -> impl Iterator<Item = &Call> + '_ {
+        self.calls.iter().filter(move |call| span.contains(&call.span))
+    }
+
+    /// Storage accesses located inside `span`
+    pub fn storage_accesses_within(&self, span: SourceSpan) -> impl Iterator<Item = &StorageAccess> + '_ {
+        self.storage_accesses.iter().filter(move |access| span.contains(&access.span))
+    }
+
+    /// Whether the body assigns to a field of `self`, changing contract state held
+    /// in the receiver
+    pub fn assigns_self_field(&self) -> bool {
+        self.assignments.iter().any(|assignment| assignment.target.starts_with("self."))
+    }
+
+    /// Whether any identifier in the function contains `fragment`
+    pub fn mentions(&self, fragment: &str) -> bool {
+        self.identifiers.iter().any(|identifier| identifier.contains(fragment))
+    }
+}
+
+impl Loop {
+    /// Whether code at `span` runs on every iteration: anything in the body,
+    /// and the condition of a `while` loop, which is checked before each one.
+    /// The iterated expression of a `for` loop is evaluated once.
+    pub fn repeats(&self, span: &SourceSpan) -> bool {
+        self.body_span.contains(span) || (self.kind == LoopKind::While && self.header_span.contains(span))
+    }
+}
+
+/// Walks a function and records its statements, calls, loops and storage accesses
+struct BodyCollector<'a> {
+    source: &'a str,
+    depth: usize,
+    body: FunctionBody,
+}
+
+impl BodyCollector<'_> {
+    fn text(&self, node: &impl Spanned) -> String {
+        SorobanParser::collapse_whitespace(SourceSpan::from_span(node.span()).snippet(self.source))
+    }
+
+    /// Whether a macro aborts the contract call
+    fn is_panic(mac: &syn::Macro) -> bool {
+        let path = Self::path_text(&mac.path);
+        matches!(path.rsplit("::").next(), Some("panic" | "panic_with_error" | "unreachable"))
+    }
+
+    fn path_text(path: &syn::Path) -> String {
+        path.segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    /// Names along a method chain, from the root expression to `expr`
+    fn receiver_chain(expr: &Expr) -> Vec<String> {
+        match expr {
+            Expr::MethodCall(call) => {
+                let mut chain = Self::receiver_chain(&call.receiver);
+                chain.push(call.method.to_string());
+                chain
+            }
+            Expr::Field(field) => {
+                let mut chain = Self::receiver_chain(&field.base);
+                chain.push(match &field.member {
+                    Member::Named(ident) => ident.to_string(),
+                    Member::Unnamed(index) => index.index.to_string(),
+                });
+                chain
+            }
+            Expr::Call(call) => Self::receiver_chain(&call.func),
+            Expr::Path(path) => vec![Self::path_text(&path.path)],
+            Expr::Reference(reference) => Self::receiver_chain(&reference.expr),
+            Expr::Paren(paren) => Self::receiver_chain(&paren.expr),
+            Expr::Try(try_expr) => Self::receiver_chain(&try_expr.expr),
+            _ => Vec::new(),
+        }
+    }
+
+    fn record_storage_access(&mut self, call: &syn::ExprMethodCall, chain: &[String], args: &[String]) {
+        let [.., storage, tier] = chain else {
+            return;
+        };
+        let (Some(tier), Some(operation)) = (
+            StorageTier::from_method(tier),
+            StorageOperation::from_method(&call.method.to_string()),
+        ) else {
+            return;
+        };
+        if storage != "storage" {
+            return;
+        }
+
+        let keyless = operation == StorageOperation::ExtendTtl && tier == StorageTier::Instance;
+        let key = args
+            .first()
+            .filter(|_| !keyless)
+            .map(|key| key.trim_start_matches('&').trim_start().to_string());
+        self.body.storage_accesses.push(StorageAccess {
+            tier,
+            operation,
+            key,
+            args: args.to_vec(),
+            span: SourceSpan::from_span(call.span()),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for BodyCollector<'_> {
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.body.blocks.push(SourceSpan::from_span(block.span()));
+        self.depth += 1;
+        visit::visit_block(self, block);
+        self.depth -= 1;
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        let kind = match stmt {
+            Stmt::Local(local) => {
+                let (pattern, type_name) = match &local.pat {
+                    Pat::Type(typed) => (self.text(&typed.pat), Some(self.text(&typed.ty))),
+                    pattern => (self.text(pattern), None),
+                };
+                StatementKind::Let {
+                    pattern,
+                    type_name,
+                    init: local.init.as_ref().map(|init| self.text(&init.expr)),
+                }
+            }
+            Stmt::Expr(..) => StatementKind::Expr,
+            Stmt::Macro(stmt_macro) => StatementKind::Macro {
+                name: Self::path_text(&stmt_macro.mac.path)
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+            },
+            Stmt::Item(..) => StatementKind::Item,
+        };
+        self.body.statements.push(Statement {
+            kind,
+            depth: self.depth,
+            span: SourceSpan::from_span(stmt.span()),
+        });
+        visit::visit_stmt(self, stmt);
+    }
+
+    fn visit_item(&mut self, _item: &'ast syn::Item) {
+        // Nested items are separate functions, not part of this body
+    }
+
+    fn visit_ident(&mut self, ident: &'ast proc_macro2::Ident) {
+        self.body.identifiers.insert(ident.to_string());
+    }
+
+    fn visit_expr_path(&mut self, path: &'ast syn::ExprPath) {
+        if let (None, Some(ident)) = (&path.qself, path.path.get_ident()) {
+            self.body.local_references.push(LocalReference {
+                name: ident.to_string(),
+                span: SourceSpan::from_span(ident.span()),
+            });
+        }
+        visit::visit_expr_path(self, path);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        let path = match call.func.as_ref() {
+            Expr::Path(path) => Self::path_text(&path.path),
+            func => self.text(func),
+        };
+        let span = SourceSpan::from_span(call.span());
+        self.body.calls.push(Call {
+            kind: CallKind::Function,
+            name: path.rsplit("::").next().unwrap_or_default().to_string(),
+            path,
+            receiver_chain: Vec::new(),
+            receiver: None,
+            args: call.args.iter().map(|arg| self.text(arg)).collect(),
+            span,
+            call_span: span,
+        });
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        let receiver_chain = Self::receiver_chain(&call.receiver);
+        let args: Vec<String> = call.args.iter().map(|arg| self.text(arg)).collect();
+        self.record_storage_access(call, &receiver_chain, &args);
+        self.body.calls.push(Call {
+            kind: CallKind::Method,
+            name: method.clone(),
+            path: method,
+            receiver_chain,
+            receiver: Some(self.text(&call.receiver)),
+            args,
+            span: SourceSpan::from_span(call.span()),
+            call_span: SourceSpan::between(call.dot_token.span, call.paren_token.span.close()),
+        });
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let path = Self::path_text(&mac.path);
+        // Format-like macros take comma separated expressions; lower them so calls
+        // inside `format!`, `assert!` or `vec!` are seen too
+        let args = mac
+            .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+            .unwrap_or_default();
+        let span = SourceSpan::from_span(mac.span());
+        if path.rsplit("::").next() == Some("assert") {
+            if let Some(condition) = args.first() {
+                self.body.guards.push(Guard {
+                    kind: GuardKind::Assert,
+                    condition: self.text(condition),
+                    span,
+                });
+            }
+        }
+        self.body.calls.push(Call {
+            kind: CallKind::Macro,
+            name: path.rsplit("::").next().unwrap_or_default().to_string(),
+            path,
+            receiver_chain: Vec::new(),
+            receiver: None,
+            args: args.iter().map(|arg| self.text(arg)).collect(),
+            span,
+            call_span: span,
+        });
+        visit::visit_macro(self, mac);
+        for arg in &args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign) {
+        self.body.assignments.push(Assignment {
+            target: self.text(&assign.left),
+            span: SourceSpan::from_span(assign.span()),
+        });
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        use syn::BinOp::*;
+        if matches!(binary.op, Add(_) | Sub(_) | Mul(_) | AddAssign(_) | SubAssign(_) | MulAssign(_)) {
+            self.body.arithmetic.push(Arithmetic {
+                operator: self.text(&binary.op),
+                left: self.text(&binary.left),
+                right: self.text(&binary.right),
+                span: SourceSpan::from_span(binary.span()),
+            });
+        }
+        if matches!(
+            binary.op,
+            AddAssign(_) | SubAssign(_) | MulAssign(_) | DivAssign(_) | RemAssign(_) | BitXorAssign(_)
+                | BitAndAssign(_) | BitOrAssign(_) | ShlAssign(_) | ShrAssign(_)
+        ) {
+            self.body.assignments.push(Assignment {
+                target: self.text(&binary.left),
+                span: SourceSpan::from_span(binary.span()),
+            });
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast syn::ExprIf) {
+        let exits = expr_if.then_branch.stmts.last().is_some_and(|last| match last {
+            Stmt::Expr(Expr::Return(_), _) => true,
+            Stmt::Expr(Expr::Macro(expr_macro), _) => Self::is_panic(&expr_macro.mac),
+            Stmt::Macro(stmt_macro) => Self::is_panic(&stmt_macro.mac),
+            _ => false,
+        });
+        if exits {
+            self.body.guards.push(Guard {
+                kind: GuardKind::Exit,
+                condition: self.text(&expr_if.cond),
+                span: SourceSpan::from_span(expr_if.span()),
+            });
+        }
+        visit::visit_expr_if(self, expr_if);
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast syn::ExprForLoop) {
+        self.body.loops.push(Loop {
+            kind: LoopKind::For,
+            pattern: Some(self.text(&for_loop.pat)),
+            header: Some(self.text(&for_loop.expr)),
+            header_span: SourceSpan::between(for_loop.for_token.span, for_loop.expr.span()),
+            body_span: SourceSpan::from_span(for_loop.body.span()),
+            span: SourceSpan::from_span(for_loop.span()),
+        });
+        visit::visit_expr_for_loop(self, for_loop);
+    }
+
+    fn visit_expr_while(&mut self, while_loop: &'ast syn::ExprWhile) {
+        self.body.loops.push(Loop {
+            kind: LoopKind::While,
+            pattern: None,
+            header: Some(self.text(&while_loop.cond)),
+            header_span: SourceSpan::between(while_loop.while_token.span, while_loop.cond.span()),
+            body_span: SourceSpan::from_span(while_loop.body.span()),
+            span: SourceSpan::from_span(while_loop.span()),
+        });
+        visit::visit_expr_while(self, while_loop);
+    }
+
+    fn visit_expr_loop(&mut self, loop_expr: &'ast syn::ExprLoop) {
+        self.body.loops.push(Loop {
+            kind: LoopKind::Loop,
+            pattern: None,
+            header: None,
+            header_span: SourceSpan::from_span(loop_expr.loop_token.span),
+            body_span: SourceSpan::from_span(loop_expr.body.span()),
+            span: SourceSpan::from_span(loop_expr.span()),
+        });
+        visit::visit_expr_loop(self, loop_expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_body(function: &str) -> FunctionBody {
+        let source = format!(
+            "#[contract]\npub struct C;\n\n#[contractimpl]\nimpl C {{\n{}\n}}\n",
+            function
+        );
+        let contract = SorobanParser::parse_contract(&source, "body.rs").unwrap();
+        contract.implementations[0].functions[0].body.clone()
+    }
+
+    #[test]
+    fn test_storage_accessor_chains() {
+        let body = parse_body(
+            r#"
+    pub fn bump(env: Env, user: Address) {
+        let count: u32 = env.storage().persistent().get(&DataKey::Count(user.clone())).unwrap_or(0);
+        env.storage().persistent().set(&DataKey::Count(user), &(count + 1));
+        env.storage().instance().extend_ttl(100, 200);
+    }"#,
+        );
+
+        let accesses: Vec<_> = body
+            .storage_accesses
+            .iter()
+            .map(|access| (access.tier, access.operation, access.key.as_deref()))
+            .collect();
+        assert_eq!(
+            accesses,
+            vec![
+                (StorageTier::Persistent, StorageOperation::Get, Some("DataKey::Count(user.clone())")),
+                (StorageTier::Persistent, StorageOperation::Set, Some("DataKey::Count(user)")),
+                (StorageTier::Instance, StorageOperation::ExtendTtl, None),
+            ]
+        );
+
+        let get = body.calls_named("get").next().unwrap();
+        assert_eq!(get.receiver_chain, vec!["env", "storage", "persistent"]);
+        assert_eq!(body.statements.len(), 3);
+        assert!(matches!(
+            &body.statements[0].kind,
+            StatementKind::Let { pattern, type_name: Some(ty), .. } if pattern == "count" && ty == "u32"
+        ));
+    }
+
+    #[test]
+    fn test_comments_strings_and_identifiers_are_not_code() {
+        let body = parse_body(
+            r#"
+    pub fn format_date(env: Env) -> Symbol {
+        // for each entry call .to_string() and loop { }
+        let label = "while format!(x)";
+        symbol_short!("date")
+    }"#,
+        );
+
+        assert!(body.loops.is_empty());
+        assert!(!body.has_call("to_string"));
+        assert!(!body.has_call("format"));
+        assert!(body.has_call("symbol_short"));
+        assert!(body.mentions("label"));
+        assert!(!body.mentions("entry"));
+    }
+
+    #[test]
+    fn test_loops_and_nested_calls() {
+        let body = parse_body(
+            r#"
+    pub fn sum(env: Env, items: Vec<u32>) -> u32 {
+        let mut total = 0;
+        for i in 0..items.len() {
+            total += items.get(i).unwrap();
+        }
+        while total > 10 {
+            total = helper(total);
+        }
+        total
+    }"#,
+        );
+
+        assert_eq!(body.loops.len(), 2);
+        let for_loop = &body.loops[0];
+        assert_eq!(for_loop.kind, LoopKind::For);
+        assert_eq!(for_loop.pattern.as_deref(), Some("i"));
+        assert_eq!(for_loop.header.as_deref(), Some("0..items.len()"));
+
+        let in_for: Vec<_> = body.calls_within(for_loop.span).map(|call| call.name.as_str()).collect();
+        assert_eq!(in_for, vec!["len", "get", "unwrap"]);
+        let repeated: Vec<_> = body.calls.iter().filter(|call| for_loop.repeats(&call.span)).map(|call| call.name.as_str()).collect();
+        assert_eq!(repeated, vec!["get", "unwrap"]);
+        let helper = body.calls_named("helper").next().unwrap();
+        assert_eq!(helper.kind, CallKind::Function);
+        assert!(body.loops[1].span.contains(&helper.span));
+        assert!(body.statements.iter().any(|statement| statement.depth == 1));
+        let total_uses = body.local_references.iter().filter(|r| r.name == "total").count();
+        assert_eq!(total_uses, 5);
+    }
+}
@@ -0,0 +1,329 @@
+//! Call graph
+//!
+//! Resolves the calls each function makes to the functions of the contract
+//! file they name: methods of `#[contractimpl]` blocks, methods of other impl
+//! blocks and free functions. Rules use it to ask whether an entry point
+//! reaches some operation through its helpers, rather than only looking at the
+//! entry point's own body.
+
+use super::{Call, CallKind, ExternalCall, FunctionVisibility, SorobanContract, SorobanFunction, StatementKind};
+use std::collections::VecDeque;
+
+/// A function in the call graph
+#[derive(Debug, Clone, Copy)]
+pub struct CallNode<'a> {
+    pub function: &'a SorobanFunction,
+    /// Type the function is implemented on, or `None` for free functions
+    pub owner: Option<&'a str>,
+    /// Whether the function is a public method of a `#[contractimpl]` block,
+    /// callable from outside the contract
+    pub entry_point: bool,
+}
+
+/// Calls between the functions of a contract file
+#[derive(Debug, Clone)]
+pub struct CallGraph<'a> {
+    nodes: Vec<CallNode<'a>>,
+    /// Callees of each node, by index, paired with the call making them
+    edges: Vec<Vec<(usize, &'a Call)>>,
+}
+
+impl<'a> CallGraph<'a> {
+    pub fn build(contract: &'a SorobanContract) -> Self {
+        let mut nodes = Vec::new();
+        for implementation in &contract.implementations {
+            for function in &implementation.functions {
+                nodes.push(CallNode {
+                    function,
+                    owner: Some(&implementation.target),
+                    entry_point: function.visibility == FunctionVisibility::Public,
+                });
+            }
+        }
+        for implementation in &contract.helper_impls {
+            for function in &implementation.functions {
+                nodes.push(CallNode {
+                    function,
+                    owner: Some(&implementation.target),
+                    entry_point: false,
+                });
+            }
+        }
+        for function in &contract.free_functions {
+            nodes.push(CallNode {
+                function,
+                owner: None,
+                entry_point: false,
+            });
+        }
+
+        let mut graph = Self {
+            edges: vec![Vec::new(); nodes.len()],
+            nodes,
+        };
+        for caller in 0..graph.nodes.len() {
+            let node = graph.nodes[caller];
+            for call in &node.function.body.calls {
+                if let Some(callee) = graph.resolve_index(node, call) {
+                    graph.edges[caller].push((callee, call));
+                }
+            }
+        }
+        graph
+    }
+
+    /// Every function in the graph
+    pub fn nodes(&self) -> impl Iterator<Item = &CallNode<'a>> {
+        self.nodes.iter()
+    }
+
+    /// Public `#[contractimpl]` functions
+    pub fn entry_points(&self) -> impl Iterator<Item = &'a SorobanFunction> + '_ {
+        self.nodes.iter().filter(|node| node.entry_point).map(|node| node.function)
+    }
+
+    /// The function `call`, made in `caller`, runs
+    pub fn resolve(&self, caller: &SorobanFunction, call: &Call) -> Option<&'a SorobanFunction> {
+        let node = self.nodes[self.index(caller)?];
+        self.resolve_index(node, call).map(|index| self.nodes[index].function)
+    }
+
+    /// Functions `function` calls directly, with the calls making them
+    pub fn callees(&self, function: &SorobanFunction) -> impl Iterator<Item = (&'a SorobanFunction, &'a Call)> + '_ {
+        self.index(function)
+            .into_iter()
+            .flat_map(|index| &self.edges[index])
+            .map(|(callee, call)| (self.nodes[*callee].function, *call))
+    }
+
+    /// Functions calling `function` directly, with the calls they make
+    pub fn callers(&self, function: &SorobanFunction) -> impl Iterator<Item = (&'a SorobanFunction, &'a Call)> + '_ {
+        let target = self.index(function);
+        self.edges.iter().enumerate().flat_map(move |(caller, edges)| {
+            edges
+                .iter()
+                .filter(move |(callee, _)| Some(*callee) == target)
+                .map(move |(_, call)| (self.nodes[caller].function, *call))
+        })
+    }
+
+    /// `function` and every function it reaches, breadth first
+    pub fn reachable_from(&self, function: &SorobanFunction) -> Vec<&'a SorobanFunction> {
+        let Some(start) = self.index(function) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([start]);
+        let mut reached = Vec::new();
+        seen[start] = true;
+        while let Some(index) = queue.pop_front() {
+            reached.push(self.nodes[index].function);
+            for (callee, _) in &self.edges[index] {
+                if !seen[*callee] {
+                    seen[*callee] = true;
+                    queue.push_back(*callee);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Every function reachable from some entry point
+    pub fn reachable(&self) -> Vec<&'a SorobanFunction> {
+        let mut reached: Vec<&'a SorobanFunction> = Vec::new();
+        for entry in self.entry_points() {
+            for function in self.reachable_from(entry) {
+                if !reached.iter().any(|seen| std::ptr::eq(*seen, function)) {
+                    reached.push(function);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Whether `function` or anything it calls satisfies `predicate`
+    pub fn reaches(&self, function: &SorobanFunction, predicate: impl Fn(&SorobanFunction) -> bool) -> bool {
+        self.reachable_from(function).into_iter().any(predicate)
+    }
+
+    /// Whether `function` writes storage, directly or through a helper
+    pub fn reaches_storage_write(&self, function: &SorobanFunction) -> bool {
+        self.reaches(function, |f| f.body.storage_accesses.iter().any(|access| access.operation.is_write()))
+    }
+
+    /// Whether `function` checks authorization, directly or through a helper
+    pub fn reaches_auth_check(&self, function: &SorobanFunction) -> bool {
+        self.reaches(function, |f| f.body.has_auth_check())
+    }
+
+    /// Whether `function` runs a loop, directly or through a helper
+    pub fn reaches_loop(&self, function: &SorobanFunction) -> bool {
+        self.reaches(function, |f| !f.body.loops.is_empty())
+    }
+
+    /// Whether `function` calls into another contract in a way that may change
+    /// state, directly or through a helper
+    pub fn reaches_external_call(&self, function: &SorobanFunction) -> bool {
+        self.reaches(function, |f| ExternalCall::find(f).iter().any(|call| !call.is_read_only()))
+    }
+
+    /// For each outermost loop of `function`, the first call repeated on every
+    /// iteration that satisfies `direct` itself or runs a helper reaching a
+    /// function that satisfies `predicate`, paired with that helper
+    pub fn calls_in_loops(
+        &self,
+        function: &'a SorobanFunction,
+        direct: impl Fn(&Call) -> bool,
+        predicate: impl Fn(&SorobanFunction) -> bool,
+    ) -> Vec<(&'a Call, Option<&'a SorobanFunction>)> {
+        let loops = &function.body.loops;
+        loops
+            .iter()
+            // Report nested loops once, at the outermost
+            .filter(|lp| !loops.iter().any(|outer| outer.span != lp.span && outer.span.contains(&lp.span)))
+            .filter_map(|lp| {
+                let mut inside = function.body.calls.iter().filter(|call| lp.repeats(&call.span));
+                inside.find_map(|call| {
+                    if direct(call) {
+                        return Some((call, None));
+                    }
+                    let helper = self.resolve(function, call)?;
+                    self.reaches(helper, &predicate).then_some((call, Some(helper)))
+                })
+            })
+            .collect()
+    }
+
+    /// Type of a parameter or local, from its declaration or from a `Type::..`
+    /// initializer such as `let config = Config::load(&env);`
+    fn local_type<'f>(function: &'f SorobanFunction, name: &str) -> Option<&'f str> {
+        let type_path = |text: &'f str| -> Option<&'f str> {
+            let text = text.trim_start_matches('&').trim_start_matches("mut ").trim();
+            let end = text.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len());
+            Some(&text[..end]).filter(|path| path.starts_with(char::is_uppercase))
+        };
+
+        let declared = function.body.statements.iter().rev().find_map(|statement| match &statement.kind {
+            StatementKind::Let { pattern, type_name, init } if pattern.trim_start_matches("mut ") == name => {
+                Some(type_name.as_deref().or(init.as_deref()).and_then(type_path))
+            }
+            _ => None,
+        });
+        match declared {
+            Some(declared) => declared,
+            None => function
+                .params
+                .iter()
+                .find(|param| param.name == name)
+                .and_then(|param| type_path(&param.type_name)),
+        }
+    }
+
+    fn index(&self, function: &SorobanFunction) -> Option<usize> {
+        self.nodes.iter().position(|node| std::ptr::eq(node.function, function))
+    }
+
+    /// Resolve a call by the path it names, e.g. `Self::helper`, `Config::load`,
+    /// `self.validate` or `helper`
+    fn resolve_index(&self, caller: CallNode<'a>, call: &Call) -> Option<usize> {
+        let owner = match call.kind {
+            CallKind::Function => {
+                let mut segments: Vec<&str> = call.path.split("::").collect();
+                segments.pop();
+                match segments.last().copied() {
+                    None => None,
+                    Some("Self") => caller.owner,
+                    Some(segment) if segment.starts_with(char::is_uppercase) => Some(segment),
+                    // A module path such as `utils::helper` names a free function
+                    Some(_) => None,
+                }
+            }
+            CallKind::Method => match call.receiver.as_deref()? {
+                "self" => caller.owner,
+                receiver => Some(Self::local_type(caller.function, receiver)?),
+            },
+            CallKind::Macro => return None,
+        };
+
+        self.nodes
+            .iter()
+            .position(|node| node.function.name == call.name && node.owner == owner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_call_graph_resolves_helpers() {
+        let source = r#"
+#[contracttype]
+pub struct Config {
+    pub limit: u32,
+}
+
+impl Config {
+    pub fn load(env: &Env) -> Self {
+        env.storage().instance().get(&DataKey::Config).unwrap()
+    }
+
+    pub fn check(&self, amount: u32) {
+        for _ in 0..amount {}
+    }
+}
+
+fn save(env: &Env, config: &Config) {
+    env.storage().instance().set(&DataKey::Config, config);
+}
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn update(env: Env, admin: Address, limit: u32) {
+        Self::authorize(&admin);
+        let config = Config::load(&env);
+        config.check(limit);
+        save(&env, &config);
+    }
+
+    pub fn limit(env: Env) -> u32 {
+        Config::load(&env).limit
+    }
+
+    fn authorize(admin: &Address) {
+        admin.require_auth();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn save() {}
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        assert_eq!(contract.free_functions.len(), 1);
+        assert_eq!(contract.helper_impls[0].functions.len(), 2);
+
+        let graph = CallGraph::build(&contract);
+        let update = contract.functions().find(|f| f.name == "update").unwrap();
+        let limit = contract.functions().find(|f| f.name == "limit").unwrap();
+
+        let callees: Vec<_> = graph.callees(update).map(|(f, _)| f.name.as_str()).collect();
+        assert_eq!(callees, vec!["authorize", "load", "check", "save"]);
+        assert!(graph.reaches_storage_write(update));
+        assert!(graph.reaches_auth_check(update));
+        assert!(!graph.reaches_storage_write(limit));
+        assert!(graph.reaches_loop(update));
+        assert!(!graph.reaches_loop(limit));
+
+        let load = &contract.helper_impls[0].functions[0];
+        let callers: Vec<_> = graph.callers(load).map(|(f, _)| f.name.as_str()).collect();
+        assert_eq!(callers, vec!["update", "limit"]);
+        assert_eq!(graph.entry_points().count(), 2);
+        assert_eq!(graph.reachable().len(), 6);
+    }
+}
@@ -0,0 +1,328 @@
+//! Test coverage of entry points
+//!
+//! Reads the `#[test]` functions of a contract's test files and records which
+//! entry points each one invokes through the generated `<Contract>Client`,
+//! directly or through helpers such as a `setup` function returning the
+//! client, and whether it calls `mock_all_auths`. An entry point only ever
+//! invoked under `mock_all_auths` never has its `require_auth` checks tested.
+
+use super::events::tuple_elements;
+use super::{base_type, client_type, AuthFlow, CallGraph, CallKind, SorobanContract, SorobanFunction, StatementKind};
+use crate::SourceSpan;
+use serde::Serialize;
+use std::fmt;
+
+/// Calls making every `require_auth` in the test pass
+const MOCK_ALL_AUTHS: &[&str] = &["mock_all_auths", "mock_all_auths_allowing_non_root_auth"];
+
+/// Client methods configuring authorization for the next invocation
+const CLIENT_SETTINGS: &[&str] = &["mock_auths", "mock_all_auths", "mock_all_auths_allowing_non_root_auth", "set_auths"];
+
+/// Functions of a test file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFile {
+    pub file_path: String,
+    /// Functions marked `#[test]`
+    pub tests: Vec<SorobanFunction>,
+    /// Every other free function, such as setup helpers
+    pub helpers: Vec<SorobanFunction>,
+}
+
+/// What one test does with the contract
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestCase {
+    pub name: String,
+    pub file_path: String,
+    pub line_number: usize,
+    /// Client methods called, directly or through helpers, `try_` variants as written
+    pub calls: Vec<String>,
+    /// Whether the test calls `mock_all_auths`, directly or through a helper
+    pub mocks_all_auths: bool,
+}
+
+/// Tests reaching one entry point
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryPointCoverage {
+    pub function: String,
+    pub line_number: usize,
+    /// Whether the entry point calls `require_auth`, directly or through a helper
+    pub requires_auth: bool,
+    /// Tests calling the entry point
+    pub tests: Vec<String>,
+    /// Tests calling the entry point without `mock_all_auths`
+    pub tests_without_mock_all_auths: Vec<String>,
+}
+
+impl EntryPointCoverage {
+    pub fn is_tested(&self) -> bool {
+        !self.tests.is_empty()
+    }
+
+    /// Whether some test runs the entry point's authorization checks without
+    /// `mock_all_auths`; always true when it has none
+    pub fn is_auth_tested(&self) -> bool {
+        !self.requires_auth || !self.tests_without_mock_all_auths.is_empty()
+    }
+}
+
+/// Entry points of a contract against the tests calling them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestCoverage {
+    pub contract: String,
+    /// Generated client type the tests call the contract through
+    pub client: String,
+    pub tests: Vec<TestCase>,
+    pub entry_points: Vec<EntryPointCoverage>,
+}
+
+impl TestCoverage {
+    pub fn build(contract: &SorobanContract, files: &[TestFile]) -> Self {
+        let client = format!("{}Client", contract.name);
+        let helpers: Vec<&SorobanFunction> = files.iter().flat_map(|file| &file.helpers).collect();
+        let tests: Vec<TestCase> = files
+            .iter()
+            .flat_map(|file| file.tests.iter().map(move |test| (file, test)))
+            .map(|(file, test)| TestCase::trace(test, &file.file_path, &helpers, &client))
+            .collect();
+
+        let graph = CallGraph::build(contract);
+        let entry_points = graph
+            .entry_points()
+            // The constructor runs on registration, not through the client
+            .filter(|function| function.name != "__constructor")
+            .map(|function| {
+                let calling: Vec<&TestCase> = tests.iter().filter(|test| test.calls_entry_point(&function.name)).collect();
+                EntryPointCoverage {
+                    function: function.name.clone(),
+                    line_number: function.line_number,
+                    requires_auth: AuthFlow::analyze_in(&graph, function).is_authorized(),
+                    tests: calling.iter().map(|test| test.name.clone()).collect(),
+                    tests_without_mock_all_auths: calling
+                        .iter()
+                        .filter(|test| !test.mocks_all_auths)
+                        .map(|test| test.name.clone())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            contract: contract.name.clone(),
+            client,
+            tests,
+            entry_points,
+        }
+    }
+
+    /// Entry points no test calls
+    pub fn untested(&self) -> impl Iterator<Item = &EntryPointCoverage> {
+        self.entry_points.iter().filter(|entry_point| !entry_point.is_tested())
+    }
+
+    /// Entry points with authorization checks that are only called under `mock_all_auths`
+    pub fn auth_untested(&self) -> impl Iterator<Item = &EntryPointCoverage> {
+        self.entry_points
+            .iter()
+            .filter(|entry_point| entry_point.is_tested() && !entry_point.is_auth_tested())
+    }
+
+    /// Whether every entry point is tested, with its authorization checks
+    pub fn is_complete(&self) -> bool {
+        self.untested().next().is_none() && self.auth_untested().next().is_none()
+    }
+}
+
+impl TestCase {
+    /// Client calls and auth mocking of `test`, following calls to `helpers`
+    fn trace(test: &SorobanFunction, file_path: &str, helpers: &[&SorobanFunction], client: &str) -> Self {
+        let mut case = Self {
+            name: test.name.clone(),
+            file_path: file_path.to_string(),
+            line_number: test.line_number,
+            calls: Vec::new(),
+            mocks_all_auths: false,
+        };
+
+        let mut visited = vec![test.name.as_str()];
+        let mut queue = vec![test];
+        while let Some(function) = queue.pop() {
+            for call in &function.body.calls {
+                match call.kind {
+                    CallKind::Method if MOCK_ALL_AUTHS.contains(&call.name.as_str()) => case.mocks_all_auths = true,
+                    CallKind::Method => {
+                        let receiver = client_receiver(call.receiver.as_deref().unwrap_or_default(), &call.receiver_chain);
+                        if client_of(function, receiver, call.span, helpers).is_some_and(|found| found == client) {
+                            case.calls.push(call.name.clone());
+                        }
+                    }
+                    CallKind::Function => {
+                        let helper = helpers.iter().find(|helper| helper.name == call.name);
+                        if let Some(helper) = helper.filter(|helper| !visited.contains(&helper.name.as_str())) {
+                            visited.push(&helper.name);
+                            queue.push(helper);
+                        }
+                    }
+                    CallKind::Macro => {}
+                }
+            }
+        }
+        case
+    }
+
+    /// Whether the test calls `name` or its `try_` variant
+    pub fn calls_entry_point(&self, name: &str) -> bool {
+        self.calls
+            .iter()
+            .any(|call| call == name || call.strip_prefix("try_") == Some(name))
+    }
+}
+
+/// The client a method is invoked on, e.g. `client` for
+/// `client.mock_auths(&[..]).transfer(..)`
+fn client_receiver<'c>(receiver: &'c str, chain: &'c [String]) -> &'c str {
+    match chain.split_first() {
+        Some((root, rest)) if !rest.is_empty() && rest.iter().all(|method| CLIENT_SETTINGS.contains(&method.as_str())) => root,
+        _ => receiver,
+    }
+}
+
+/// Client type `receiver` is an instance of in `function`, also when it is
+/// returned by a helper, e.g. `let (env, client) = setup();`
+fn client_of(function: &SorobanFunction, receiver: &str, at: SourceSpan, helpers: &[&SorobanFunction]) -> Option<String> {
+    if let Some(path) = client_type(function, receiver, at) {
+        return Some(base_type(path).to_string());
+    }
+
+    let receiver = receiver.trim_start_matches('&');
+    let (index, count, init) = function
+        .body
+        .statements
+        .iter()
+        .rev()
+        .filter(|statement| statement.span.end_byte <= at.start_byte)
+        .find_map(|statement| {
+            let StatementKind::Let { pattern, init, .. } = &statement.kind else {
+                return None;
+            };
+            let names = tuple_elements(pattern);
+            let index = names.iter().position(|name| name.trim_start_matches("mut ") == receiver)?;
+            Some((index, names.len(), init.as_deref()))
+        })?;
+
+    let callee = init?.trim_start_matches('&').split('(').next()?.rsplit("::").next()?.trim();
+    let helper = helpers.iter().find(|helper| helper.name == callee)?;
+    let returned = tuple_elements(helper.return_type.as_deref()?);
+    let type_name = base_type(returned.get(index).filter(|_| returned.len() == count)?);
+    type_name.ends_with("Client").then(|| type_name.to_string())
+}
+
+impl fmt::Display for TestCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🧪 Test coverage of {} ({} tests)", self.contract, self.tests.len())?;
+        for entry_point in &self.entry_points {
+            let (icon, status) = if !entry_point.is_tested() {
+                ("❌", "no test calls it".to_string())
+            } else if !entry_point.is_auth_tested() {
+                ("⚠️ ", format!("{} tests, all with mock_all_auths", entry_point.tests.len()))
+            } else {
+                (
+                    "✅",
+                    format!(
+                        "{} tests, {} without mock_all_auths",
+                        entry_point.tests.len(),
+                        entry_point.tests_without_mock_all_auths.len()
+                    ),
+                )
+            };
+            let auth = if entry_point.requires_auth { " [requires auth]" } else { "" };
+            writeln!(f, "   {} {}{}: {}", icon, entry_point.function, auth, status)?;
+        }
+
+        let tested = self.entry_points.iter().filter(|entry_point| entry_point.is_tested()).count();
+        writeln!(
+            f,
+            "   {}/{} entry points tested through {}; {} only tested with mock_all_auths",
+            tested,
+            self.entry_points.len(),
+            self.client,
+            self.auth_untested().count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_entry_point_coverage() {
+        let contract = r#"
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn __constructor(env: Env, admin: Address) {}
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+    }
+
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        to.require_auth();
+    }
+
+    pub fn balance(env: Env, user: Address) -> i128 {
+        0
+    }
+
+    pub fn sweep(env: Env) {}
+}
+"#;
+        let tests = r#"
+use crate::{Vault, VaultClient};
+
+fn setup(env: &Env) -> (VaultClient<'static>, Address) {
+    let id = env.register(Vault, (Address::generate(env),));
+    (VaultClient::new(env, &id), Address::generate(env))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_deposit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, user) = setup(&env);
+        client.deposit(&user, &10);
+        client.try_withdraw(&user, &5);
+    }
+
+    #[test]
+    fn test_withdraw_requires_auth() {
+        let env = Env::default();
+        let (client, user) = setup(&env);
+        client.mock_auths(&[]).withdraw(&user, &5);
+        assert_eq!(client.balance(&user), 0);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(contract, "vault.rs").unwrap();
+        let file = SorobanParser::parse_test_file(tests, "tests/vault.rs").unwrap();
+        assert_eq!(file.tests.len(), 2);
+        let coverage = TestCoverage::build(&contract, &[file]);
+
+        let names: Vec<_> = coverage.entry_points.iter().map(|entry_point| entry_point.function.as_str()).collect();
+        assert_eq!(names, vec!["deposit", "withdraw", "balance", "sweep"]);
+        assert_eq!(coverage.tests[0].calls, vec!["deposit", "try_withdraw"]);
+        assert!(coverage.tests[0].mocks_all_auths);
+
+        let untested: Vec<_> = coverage.untested().map(|entry_point| entry_point.function.as_str()).collect();
+        assert_eq!(untested, vec!["sweep"]);
+        let auth_untested: Vec<_> = coverage.auth_untested().map(|entry_point| entry_point.function.as_str()).collect();
+        assert_eq!(auth_untested, vec!["deposit"]);
+        assert_eq!(coverage.entry_points[1].tests_without_mock_all_auths, vec!["test_withdraw_requires_auth"]);
+        assert!(!coverage.is_complete());
+    }
+}
@@ -0,0 +1,238 @@
+//! Storage data flow
+//!
+//! Follows each function's storage accesses in order, grouped by tier and
+//! normalized key, to find values read again with no write in between and
+//! values written again before anything reads them.
+
+use super::storage::identifiers;
+use super::{CallGraph, SorobanFunction, StorageAccess, StorageKey, StorageOperation};
+use crate::{Applicability, Fix, SourceSpan, TextEdit};
+
+/// How a pair of accesses to the same key is redundant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedundancyKind {
+    /// The key is read again although nothing wrote it since the first read
+    RepeatedRead,
+    /// The key is written again before anything read the first write
+    OverwrittenWrite,
+}
+
+/// Two accesses to the same key where the first makes the second, or the
+/// second the first, unnecessary
+#[derive(Debug, Clone)]
+pub struct RedundantAccess<'a> {
+    pub function: &'a SorobanFunction,
+    pub kind: RedundancyKind,
+    pub first: &'a StorageAccess,
+    pub second: &'a StorageAccess,
+    pub key: StorageKey,
+}
+
+impl<'a> RedundantAccess<'a> {
+    /// Redundant access pairs in `function`, treating helper calls that touch
+    /// storage as reads and writes of every key
+    pub fn find(graph: &CallGraph<'a>, function: &'a SorobanFunction) -> Vec<Self> {
+        let body = &function.body;
+        let keyed: Vec<(&StorageAccess, StorageKey, String)> = body
+            .storage_accesses
+            .iter()
+            .filter(|access| access.operation != StorageOperation::ExtendTtl)
+            .filter_map(|access| {
+                let key = StorageKey::resolve(function, access)?;
+                let normalized = normalize(&key.expression);
+                Some((access, key, normalized))
+            })
+            .collect();
+
+        let mut found = Vec::new();
+        for (index, (second, key, normalized)) in keyed.iter().enumerate() {
+            let previous = keyed[..index]
+                .iter()
+                .rev()
+                .find(|(access, _, other)| access.tier == second.tier && other == normalized);
+            let Some((first, _, _)) = previous else { continue };
+
+            let kind = match (first.operation, second.operation) {
+                (StorageOperation::Get, StorageOperation::Get) => RedundancyKind::RepeatedRead,
+                (StorageOperation::Set, StorageOperation::Set) => RedundancyKind::OverwrittenWrite,
+                _ => continue,
+            };
+            if !runs_before(function, first.span, second.span) || changed_between(graph, function, key, first.span, second.span) {
+                continue;
+            }
+            found.push(Self {
+                function,
+                kind,
+                first,
+                second,
+                key: key.clone(),
+            });
+        }
+        found
+    }
+
+    /// Edits hoisting a repeated read into a local, or deleting an overwritten write
+    pub fn fix(&self, source: &str) -> Option<Fix> {
+        match self.kind {
+            RedundancyKind::RepeatedRead => {
+                let statement = self.statement(self.first.span)?;
+                let line_start = source[..statement.start_byte].rfind('\n').map_or(0, |index| index + 1);
+                let indent = &source[line_start..statement.start_byte];
+                if !indent.chars().all(char::is_whitespace) {
+                    return None;
+                }
+
+                let local = self.local_name();
+                let read = self.first.span.snippet(source);
+                let insert_at = SourceSpan {
+                    end_byte: statement.start_byte,
+                    end_line: statement.start_line,
+                    end_column: statement.start_column,
+                    ..statement
+                };
+                let reuse = format!("{}.clone()", local);
+                Some(Fix::new(
+                    format!("Read '{}' once into '{}'", self.key.name, local),
+                    Applicability::MaybeIncorrect,
+                    vec![
+                        TextEdit::replace(insert_at, format!("let {} = {};\n{}", local, read, indent)),
+                        TextEdit::replace(self.first.span, reuse.clone()),
+                        TextEdit::replace(self.second.span, reuse),
+                    ],
+                ))
+            }
+            RedundancyKind::OverwrittenWrite => {
+                // Only a write standing as its own statement can go
+                let statement = self.statement(self.first.span)?;
+                let text = statement.snippet(source).trim_end_matches(';').trim_end();
+                (text == self.first.span.snippet(source)).then(|| {
+                    Fix::new(
+                        format!("Remove the overwritten write of '{}'", self.key.name),
+                        Applicability::MaybeIncorrect,
+                        vec![TextEdit::delete(statement)],
+                    )
+                })
+            }
+        }
+    }
+
+    /// Innermost statement containing `span`
+    fn statement(&self, span: SourceSpan) -> Option<SourceSpan> {
+        self.function
+            .body
+            .statements
+            .iter()
+            .filter(|statement| statement.span.contains(&span))
+            .max_by_key(|statement| statement.depth)
+            .map(|statement| statement.span)
+    }
+
+    /// Name of the local holding the value, e.g. `cached_balance` for `DataKey::Balance(user)`
+    fn local_name(&self) -> String {
+        let key = self.first.key.as_deref().unwrap_or_default().trim_start_matches('&');
+        let base = if key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            key.to_string()
+        } else if let Some(literal) = self.key.expression.split('"').nth(1) {
+            literal.to_string()
+        } else {
+            self.key.name.rsplit("::").next().unwrap_or_default().to_string()
+        };
+
+        let mut name = String::from("cached");
+        for (index, c) in base.chars().filter(|c| c.is_alphanumeric() || *c == '_').enumerate() {
+            if c.is_uppercase() || index == 0 {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        }
+        name.replace("__", "_")
+    }
+}
+
+/// Key expression without whitespace, references or clones
+fn normalize(expression: &str) -> String {
+    expression
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '&')
+        .collect::<String>()
+        .replace(".clone()", "")
+}
+
+/// Whether reaching `second` means `first` ran earlier in the same pass:
+/// `first` is in a block that also holds `second`, and `second` is not in a
+/// loop that `first` is outside of
+fn runs_before(function: &SorobanFunction, first: SourceSpan, second: SourceSpan) -> bool {
+    let body = &function.body;
+    let innermost = body
+        .blocks
+        .iter()
+        .filter(|block| block.contains(&first))
+        .min_by_key(|block| block.end_byte - block.start_byte);
+    let same_block = innermost.is_none_or(|block| block.contains(&second));
+    let repeated = body.loops.iter().any(|lp| lp.span.contains(&second) && !lp.span.contains(&first));
+    same_block && !repeated
+}
+
+/// Whether anything between the two accesses may change which entry the key
+/// names or what it holds: an assignment to a variable in the key, or a helper
+/// call that touches storage
+fn changed_between(graph: &CallGraph<'_>, function: &SorobanFunction, key: &StorageKey, first: SourceSpan, second: SourceSpan) -> bool {
+    let body = &function.body;
+    let between = |span: &SourceSpan| span.start_byte >= first.end_byte && span.end_byte <= second.start_byte;
+
+    let names: Vec<&str> = identifiers(&key.expression).collect();
+    let reassigned = body.assignments.iter().any(|assignment| {
+        between(&assignment.span) && identifiers(&assignment.target).next().is_some_and(|target| names.contains(&target))
+    });
+    let helper = body.calls.iter().filter(|call| between(&call.span)).any(|call| {
+        graph
+            .resolve(function, call)
+            .is_some_and(|helper| graph.reaches(helper, |f| !f.body.storage_accesses.is_empty()))
+    });
+    reassigned || helper
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apply_edits;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_repeated_reads_and_overwritten_writes() {
+        let source = r#"
+#[contract]
+pub struct Counter;
+
+#[contractimpl]
+impl Counter {
+    pub fn bump(env: Env, user: Address, flag: bool) -> u32 {
+        let a: u32 = env.storage().persistent().get(&DataKey::Count(user.clone())).unwrap_or(0);
+        let b: u32 = env.storage().persistent().get(&DataKey::Count(user)).unwrap_or(0);
+        if flag {
+            let c: u32 = env.storage().instance().get(&LIMIT).unwrap();
+        } else {
+            let d: u32 = env.storage().instance().get(&LIMIT).unwrap();
+        }
+        env.storage().persistent().set(&DataKey::Count(user.clone()), &a);
+        env.storage().persistent().set(&DataKey::Count(user.clone()), &(a + b));
+        a + b
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "counter.rs").unwrap();
+        let graph = CallGraph::build(&contract);
+        let function = &contract.implementations[0].functions[0];
+        let found = RedundantAccess::find(&graph, function);
+
+        let kinds: Vec<_> = found.iter().map(|r| (r.kind, r.second.span.start_line)).collect();
+        assert_eq!(kinds, vec![(RedundancyKind::RepeatedRead, 9), (RedundancyKind::OverwrittenWrite, 16)]);
+
+        let fixed = apply_edits(source, &found[0].fix(source).unwrap().edits).unwrap();
+        assert!(fixed.contains(
+            "        let cached_count = env.storage().persistent().get(&DataKey::Count(user.clone()));\n        let a: u32 = cached_count.clone().unwrap_or(0);\n        let b: u32 = cached_count.clone().unwrap_or(0);"
+        ));
+        let fixed = apply_edits(source, &found[1].fix(source).unwrap().edits).unwrap();
+        assert!(!fixed.contains("&a);"));
+    }
+}
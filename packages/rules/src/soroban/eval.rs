@@ -0,0 +1,163 @@
+//! Constant evaluation
+//!
+//! Evaluates the integer expressions contracts pass as TTLs, limits and loop
+//! bounds, such as `DAY_IN_LEDGERS * 30`, resolving the contract's `const` items.
+
+use super::SorobanContract;
+
+/// Maximum depth of nested constant references, guarding against cycles
+const MAX_DEPTH: usize = 16;
+
+impl SorobanContract {
+    /// Value of an integer expression made of literals, constants of this
+    /// contract, `+ - * / % << >>`, parentheses and `as` casts
+    pub fn evaluate(&self, expression: &str) -> Option<u128> {
+        Evaluator::new(self, expression, 0)?.evaluate()
+    }
+}
+
+struct Evaluator<'a> {
+    contract: &'a SorobanContract,
+    tokens: Vec<String>,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(contract: &'a SorobanContract, expression: &str, depth: usize) -> Option<Self> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        Some(Self {
+            contract,
+            tokens: tokenize(expression)?,
+            pos: 0,
+            depth,
+        })
+    }
+
+    fn evaluate(mut self) -> Option<u128> {
+        let value = self.expression(0)?;
+        (self.pos == self.tokens.len()).then_some(value)
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Binary operators by precedence climbing
+    fn expression(&mut self, min_precedence: u8) -> Option<u128> {
+        let mut left = self.operand()?;
+        while let Some(operator) = self.peek() {
+            let precedence = match operator {
+                "<<" | ">>" => 1,
+                "+" | "-" => 2,
+                "*" | "/" | "%" => 3,
+                _ => break,
+            };
+            if precedence < min_precedence {
+                break;
+            }
+            let operator = self.next()?;
+            let right = self.expression(precedence + 1)?;
+            left = match operator.as_str() {
+                "<<" => left.checked_shl(u32::try_from(right).ok()?),
+                ">>" => left.checked_shr(u32::try_from(right).ok()?),
+                "+" => left.checked_add(right),
+                "-" => left.checked_sub(right),
+                "*" => left.checked_mul(right),
+                "/" => left.checked_div(right),
+                _ => left.checked_rem(right),
+            }?;
+        }
+        Some(left)
+    }
+
+    fn operand(&mut self) -> Option<u128> {
+        let token = self.next()?;
+        let value = if token == "(" {
+            let value = self.expression(0)?;
+            (self.next()? == ")").then_some(value)?
+        } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+            parse_integer(&token)?
+        } else {
+            // `Self::LIMIT` and `crate::LIMIT` name the same constant as `LIMIT`
+            let name = token.rsplit("::").next()?;
+            let constant = self.contract.constants.iter().find(|constant| constant.name == name)?;
+            Evaluator::new(self.contract, &constant.value, self.depth + 1)?.evaluate()?
+        };
+
+        // Casts do not change the small values found in limits
+        while self.peek() == Some("as") {
+            self.pos += 2;
+        }
+        Some(value)
+    }
+}
+
+/// Split into numbers, paths, operators and parentheses
+fn tokenize(expression: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim();
+    while !rest.is_empty() {
+        let len = if rest.starts_with("<<") || rest.starts_with(">>") {
+            2
+        } else if rest.starts_with(|c: char| "+-*/%()".contains(c)) {
+            1
+        } else if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).unwrap_or(rest.len())
+        } else {
+            return None;
+        };
+        tokens.push(rest[..len].to_string());
+        rest = rest[len..].trim_start();
+    }
+    Some(tokens)
+}
+
+/// Parse an integer literal such as `17_280`, `0x10` or `100u32`
+fn parse_integer(literal: &str) -> Option<u128> {
+    let digits: String = literal.chars().filter(|c| *c != '_').collect();
+    if let Some(hex) = digits.strip_prefix("0x") {
+        let end = hex.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(hex.len());
+        return u128::from_str_radix(&hex[..end], 16).ok();
+    }
+    let end = digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_evaluate_constant_expressions() {
+        let source = r#"
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+const BUMP: u32 = 30 * DAY_IN_LEDGERS;
+
+#[contract]
+pub struct C;
+
+#[contractimpl]
+impl C {
+    const THRESHOLD: u32 = BUMP - DAY_IN_LEDGERS;
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "c.rs").unwrap();
+        assert_eq!(contract.constants.len(), 3);
+
+        assert_eq!(contract.evaluate("BUMP"), Some(518_400));
+        assert_eq!(contract.evaluate("Self::THRESHOLD"), Some(501_120));
+        assert_eq!(contract.evaluate("(1 + 2) * 3u64 as u32"), Some(9));
+        assert_eq!(contract.evaluate("1 << 4"), Some(16));
+        assert_eq!(contract.evaluate("UNKNOWN + 1"), None);
+        assert_eq!(contract.evaluate("1 - 2"), None);
+    }
+}
@@ -0,0 +1,222 @@
+//! Event emission
+//!
+//! Finds the events a contract publishes, either through
+//! `env.events().publish(topics, data)` or by calling `publish` on a
+//! `#[contractevent]` type, and breaks each one down into its topics and data
+//! values.
+
+use super::{value_type, Call, CallKind, SorobanContract, SorobanEvent, SorobanFunction, StatementKind};
+use crate::SourceSpan;
+
+/// Collection types whose size grows with their contents
+const UNBOUNDED_TYPES: &[&str] = &["String", "Vec", "Bytes", "Map"];
+
+/// An event published by a function
+#[derive(Debug, Clone)]
+pub struct EventEmission<'a> {
+    pub function: &'a SorobanFunction,
+    pub call: &'a Call,
+    /// Name of the event: its first topic when that is a symbol, or its
+    /// `#[contractevent]` type
+    pub name: Option<String>,
+    /// Source text of each topic, absent for `#[contractevent]` types whose
+    /// topics come from the type definition
+    pub topics: Vec<String>,
+    /// Source text of each data value
+    pub data: Vec<String>,
+    /// The `#[contractevent]` type published, if any
+    pub event_type: Option<&'a SorobanEvent>,
+}
+
+impl<'a> EventEmission<'a> {
+    /// Every event `function` publishes, in source order
+    pub fn find(contract: &'a SorobanContract, function: &'a SorobanFunction) -> Vec<Self> {
+        function
+            .body
+            .calls
+            .iter()
+            .filter(|call| call.kind == CallKind::Method && call.name == "publish")
+            .filter_map(|call| {
+                if call.receiver_chain.last().is_some_and(|accessor| accessor == "events") {
+                    let topics = call.args.first().map(|topics| tuple_elements(topics)).unwrap_or_default();
+                    return Some(Self {
+                        function,
+                        call,
+                        name: topics.first().and_then(|topic| symbol_name(topic)),
+                        data: call.args.get(1).map(|data| tuple_elements(data)).unwrap_or_default(),
+                        topics,
+                        event_type: None,
+                    });
+                }
+
+                let event_type = event_type(contract, function, call.receiver.as_deref()?, call.span)?;
+                Some(Self {
+                    function,
+                    call,
+                    name: Some(event_type.name.clone()),
+                    topics: Vec::new(),
+                    data: Vec::new(),
+                    event_type: Some(event_type),
+                })
+            })
+            .collect()
+    }
+
+    /// Location of the `publish` call
+    pub fn span(&self) -> SourceSpan {
+        self.call.span
+    }
+
+    /// Topics and data values whose type grows with its contents, paired with that type
+    pub fn unbounded_values(&self) -> Vec<(String, String)> {
+        let values = self.topics.iter().chain(&self.data);
+        values
+            .filter_map(|value| {
+                let type_name = value_type(self.function, value, self.call.span)?;
+                is_unbounded(&type_name).then(|| (value.clone(), type_name))
+            })
+            .collect()
+    }
+}
+
+impl SorobanContract {
+    /// Every event published anywhere in the file
+    pub fn event_emissions(&self) -> impl Iterator<Item = EventEmission<'_>> {
+        self.all_functions().flat_map(move |function| EventEmission::find(self, function))
+    }
+}
+
+/// Whether `function` publishes an event itself
+pub fn publishes_event(function: &SorobanFunction) -> bool {
+    function.body.calls.iter().any(|call| call.kind == CallKind::Method && call.name == "publish")
+}
+
+/// Whether a type grows with its contents, e.g. `Vec<Symbol>` or `soroban_sdk::String`
+pub fn is_unbounded(type_name: &str) -> bool {
+    let base = type_name.trim_start_matches('&').split('<').next().unwrap_or_default().trim();
+    let base = base.rsplit("::").next().unwrap_or(base);
+    UNBOUNDED_TYPES.iter().any(|unbounded| base.ends_with(unbounded))
+}
+
+/// Elements of a tuple expression, or the expression itself if it is not a tuple
+pub(crate) fn tuple_elements(expression: &str) -> Vec<String> {
+    let expression = expression.trim();
+    let Some(inner) = expression.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) else {
+        return vec![expression.to_string()];
+    };
+
+    let mut elements = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut in_string = false;
+    for (index, c) in inner.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '(' | '[' | '{' | '<' => depth += 1,
+            // A closing paren the tuple did not open means `(a)(b)`, not a tuple
+            ')' | ']' | '}' | '>' if depth == 0 => return vec![expression.to_string()],
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                elements.push(inner[start..index].trim().to_string());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        elements.push(last.to_string());
+    }
+    if elements.len() == 1 && !inner.trim_end().ends_with(',') {
+        // `(x)` is a parenthesized expression
+        return vec![expression.to_string()];
+    }
+    elements
+}
+
+/// The symbol a topic spells out, e.g. `transfer` for `symbol_short!("transfer")`
+/// or the constant name for `TRANSFER`
+fn symbol_name(topic: &str) -> Option<String> {
+    let topic = topic.trim_start_matches('&');
+    if topic.starts_with("symbol_short!") || topic.starts_with("Symbol::new") {
+        let start = topic.find('"')? + 1;
+        let end = start + topic[start..].find('"')?;
+        return Some(topic[start..end].to_string());
+    }
+    let constant = !topic.is_empty() && topic.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    constant.then(|| topic.to_string())
+}
+
+/// The `#[contractevent]` type a `publish` receiver is an instance of
+fn event_type<'a>(contract: &'a SorobanContract, function: &SorobanFunction, receiver: &str, at: SourceSpan) -> Option<&'a SorobanEvent> {
+    let type_of = |text: &str| -> Option<&'a SorobanEvent> {
+        let name = text.trim_start_matches('&').split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).next()?;
+        let name = name.rsplit("::").next().unwrap_or(name);
+        contract.events.iter().find(|event| event.name == name)
+    };
+    if let Some(event) = type_of(receiver) {
+        return Some(event);
+    }
+
+    let receiver = receiver.trim_start_matches('&');
+    function
+        .body
+        .statements
+        .iter()
+        .rev()
+        .filter(|statement| statement.span.end_byte <= at.start_byte)
+        .find_map(|statement| match &statement.kind {
+            StatementKind::Let { pattern, type_name, init } if pattern.trim_start_matches("mut ") == receiver => {
+                Some(type_name.as_deref().or(init.as_deref()).and_then(type_of))
+            }
+            _ => None,
+        })
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_find_event_emissions() {
+        let source = r#"
+#[contractevent]
+pub struct Paused {
+    #[topic]
+    pub admin: Address,
+}
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn batch(env: Env, keys: Vec<Symbol>, note: String) {
+        env.events().publish((symbol_short!("batch"), keys.len()), (keys, note.clone()));
+        env.events().publish((UPDATED,), 1u32);
+    }
+
+    pub fn pause(env: Env, admin: Address) {
+        let event = Paused { admin };
+        event.publish(&env);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let emissions: Vec<_> = contract.event_emissions().collect();
+        assert_eq!(emissions.len(), 3);
+
+        assert_eq!(emissions[0].name.as_deref(), Some("batch"));
+        assert_eq!(emissions[0].topics, vec!["symbol_short!(\"batch\")", "keys.len()"]);
+        let unbounded: Vec<_> = emissions[0].unbounded_values().into_iter().map(|(value, _)| value).collect();
+        assert_eq!(unbounded, vec!["keys", "note.clone()"]);
+
+        assert_eq!(emissions[1].name.as_deref(), Some("UPDATED"));
+        assert_eq!(emissions[1].topics.len(), 1);
+        assert_eq!(emissions[1].data, vec!["1u32"]);
+
+        assert_eq!(emissions[2].event_type.map(|event| event.name.as_str()), Some("Paused"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Cross-contract calls
+//!
+//! Finds the calls a contract makes into other contracts: through token
+//! clients (`token::Client::new(&env, &addr)`), through the `*Client` types
+//! generated by `contractimport!`/`#[contractclient]`, and through
+//! `env.invoke_contract`.
+
+use super::{Call, CallKind, SorobanContract, SorobanFunction, StatementKind};
+use crate::SourceSpan;
+
+/// Token methods that only read state
+const READ_ONLY_TOKEN_METHODS: &[&str] = &["balance", "allowance", "decimals", "name", "symbol", "spendable_balance"];
+
+/// What an external call is made through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalTarget {
+    /// A token client, `token::Client` or `TokenClient`
+    Token,
+    /// A generated contract client, by type name
+    Client(String),
+    /// `env.invoke_contract` or `env.try_invoke_contract`
+    InvokeContract,
+}
+
+/// A call into another contract
+#[derive(Debug, Clone, Copy)]
+pub struct ExternalCall<'a> {
+    pub function: &'a SorobanFunction,
+    pub call: &'a Call,
+    pub target: &'a str,
+}
+
+impl<'a> ExternalCall<'a> {
+    /// Every external call `function` makes, in source order
+    pub fn find(function: &'a SorobanFunction) -> Vec<Self> {
+        function
+            .body
+            .calls
+            .iter()
+            .filter(|call| call.kind == CallKind::Method)
+            .filter_map(|call| {
+                let receiver = call.receiver.as_deref()?;
+                let target = if matches!(call.name.as_str(), "invoke_contract" | "try_invoke_contract") {
+                    "invoke_contract"
+                } else {
+                    client_type(function, receiver, call.span)?
+                };
+                Some(Self { function, call, target })
+            })
+            .collect()
+    }
+
+    /// What the call is made through
+    pub fn target(&self) -> ExternalTarget {
+        match self.target {
+            "invoke_contract" => ExternalTarget::InvokeContract,
+            "token::Client" | "TokenClient" | "token::TokenClient" => ExternalTarget::Token,
+            client => ExternalTarget::Client(client.to_string()),
+        }
+    }
+
+    /// Whether the call returns its failure as a `Result` instead of panicking,
+    /// as the `try_` variants of client methods do
+    pub fn is_fallible(&self) -> bool {
+        self.call.name.starts_with("try_")
+    }
+
+    /// Whether the call is known not to change the callee's state
+    pub fn is_read_only(&self) -> bool {
+        self.target() == ExternalTarget::Token && READ_ONLY_TOKEN_METHODS.contains(&self.call.name.as_str())
+    }
+
+    /// Whether the value the call returns is thrown away, either as an
+    /// expression statement or through `let _ = ..`
+    pub fn is_result_discarded(&self, source: &str) -> bool {
+        let call = self.call.span.snippet(source);
+        self.function.body.statements.iter().any(|statement| match &statement.kind {
+            StatementKind::Expr => statement.span.snippet(source).trim_end_matches(';').trim_end() == call,
+            StatementKind::Let { pattern, init: Some(init), .. } => {
+                pattern == "_" && statement.span.contains(&self.call.span) && init.as_str() == self.call_text(source)
+            }
+            _ => false,
+        })
+    }
+
+    /// The call on a single line, as statement initializers are recorded
+    fn call_text(&self, source: &str) -> String {
+        super::SorobanParser::collapse_whitespace(self.call.span.snippet(source))
+    }
+
+    /// Location of the call
+    pub fn span(&self) -> SourceSpan {
+        self.call.span
+    }
+}
+
+impl SorobanContract {
+    /// Every call into another contract, across all functions in the file
+    pub fn external_calls(&self) -> impl Iterator<Item = ExternalCall<'_>> {
+        self.all_functions().flat_map(ExternalCall::find)
+    }
+}
+
+/// Client type a receiver is an instance of, e.g. `token::Client` for
+/// `token::Client::new(&env, &addr)` or for a local bound to it
+pub fn client_type<'f>(function: &'f SorobanFunction, receiver: &'f str, at: SourceSpan) -> Option<&'f str> {
+    let constructed = |text: &'f str| -> Option<&'f str> {
+        let text = text.trim_start_matches('&').trim_start_matches('(');
+        let path = text.split("::new(").next().filter(|path| path.len() < text.len())?;
+        path.ends_with("Client").then_some(path)
+    };
+    if let Some(path) = constructed(receiver) {
+        return Some(path);
+    }
+
+    let receiver = receiver.trim_start_matches('&');
+    let binding = function
+        .body
+        .statements
+        .iter()
+        .rev()
+        .filter(|statement| statement.span.end_byte <= at.start_byte)
+        .find_map(|statement| match &statement.kind {
+            StatementKind::Let { pattern, type_name, init } if pattern.trim_start_matches("mut ") == receiver => {
+                Some((type_name.as_deref(), init.as_deref()))
+            }
+            _ => None,
+        });
+    let declared = match binding {
+        Some((Some(type_name), _)) => Some(type_name),
+        Some((None, Some(init))) => return constructed(init),
+        Some((None, None)) => None,
+        None => function
+            .params
+            .iter()
+            .find(|param| param.name == receiver)
+            .map(|param| param.type_name.as_str()),
+    };
+    let declared = declared?.trim_start_matches('&').split('<').next()?.trim();
+    declared.ends_with("Client").then_some(declared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_find_external_calls() {
+        let source = r#"
+#[contract]
+pub struct Router;
+
+#[contractimpl]
+impl Router {
+    pub fn swap(env: Env, pool: Address, token: Address, user: Address, amount: i128) {
+        let client = token::Client::new(&env, &token);
+        let balance = client.balance(&user);
+        client.transfer(&user, &pool, &amount);
+        PoolClient::new(&env, &pool).try_swap(&amount);
+        let _ = env.try_invoke_contract::<i128, Error>(&pool, &symbol_short!("sync"), vec![&env]);
+        let price = oracle(&env).lastprice();
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "router.rs").unwrap();
+        let calls: Vec<_> = contract.external_calls().collect();
+        let found: Vec<_> = calls.iter().map(|c| (c.call.name.as_str(), c.target())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("balance", ExternalTarget::Token),
+                ("transfer", ExternalTarget::Token),
+                ("try_swap", ExternalTarget::Client("PoolClient".to_string())),
+                ("try_invoke_contract", ExternalTarget::InvokeContract),
+            ]
+        );
+        assert!(calls[0].is_read_only());
+        assert!(!calls[0].is_result_discarded(source));
+        assert!(calls[2].is_fallible());
+        assert!(calls[2].is_result_discarded(source));
+        assert!(calls[3].is_result_discarded(source));
+    }
+}
@@ -0,0 +1,120 @@
+//! soroban_sdk host objects
+//!
+//! `soroban_sdk::Vec`, `Map`, `Bytes`, `String` and `Address` are handles to
+//! objects owned by the host rather than data in the contract's memory:
+//! cloning one copies the handle, every `get` is a host call, and converting
+//! between them goes through the host. This module works out which values in
+//! a function are host objects.
+
+use super::{SorobanFunction, StatementKind};
+use crate::SourceSpan;
+
+/// soroban_sdk types backed by host objects
+const HOST_TYPES: &[&str] = &["Env", "Address", "Vec", "Map", "Bytes", "BytesN", "String", "Symbol", "I256", "U256"];
+
+/// Whether a type is a soroban_sdk host object, e.g. `Vec<Address>` or `&soroban_sdk::Bytes`
+pub fn is_host_type(type_name: &str) -> bool {
+    HOST_TYPES.contains(&base_type(type_name))
+}
+
+/// Type name without references, path or generic arguments, e.g. `Vec` for `&soroban_sdk::Vec<Address>`
+pub fn base_type(type_name: &str) -> &str {
+    let base = type_name.trim().trim_start_matches('&').trim_start_matches("mut ");
+    let base = base.split('<').next().unwrap_or_default().trim();
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+/// Type of a value used in `function` at `at`, from how it is built or from
+/// the declaration of the local or parameter it names
+pub fn value_type(function: &SorobanFunction, value: &str, at: SourceSpan) -> Option<String> {
+    let value = value.trim_start_matches('&').trim().trim_end_matches(".clone()");
+    if let Some(built) = constructed_type(value) {
+        return Some(built.to_string());
+    }
+
+    let binding = function
+        .body
+        .statements
+        .iter()
+        .rev()
+        .filter(|statement| statement.span.end_byte <= at.start_byte)
+        .find_map(|statement| match &statement.kind {
+            StatementKind::Let { pattern, type_name, init } if pattern.trim_start_matches("mut ") == value => {
+                Some((type_name.clone(), init.clone()))
+            }
+            _ => None,
+        });
+    match binding {
+        Some((Some(type_name), _)) => Some(type_name),
+        Some((None, init)) => init.as_deref().and_then(constructed_type).map(str::to_string),
+        None => function
+            .params
+            .iter()
+            .find(|param| param.name == value)
+            .map(|param| param.type_name.trim_start_matches('&').trim().to_string()),
+    }
+}
+
+/// Type an expression builds, e.g. `Vec` for `vec![&env, a, b]`
+pub fn constructed_type(expression: &str) -> Option<&'static str> {
+    const CONSTRUCTORS: &[(&str, &str)] = &[
+        ("vec!", "Vec"),
+        ("Vec::", "Vec"),
+        ("map!", "Map"),
+        ("Map::", "Map"),
+        ("String::", "String"),
+        ("Bytes::", "Bytes"),
+        ("bytes!", "Bytes"),
+    ];
+    CONSTRUCTORS
+        .iter()
+        .find(|(prefix, _)| expression.starts_with(prefix))
+        .map(|(_, type_name)| *type_name)
+}
+
+/// Name of the local or parameter an expression starts from, e.g. `data` for
+/// `&data.slice(1..)`
+pub fn root_name(expression: &str) -> &str {
+    let expression = expression.trim().trim_start_matches('&').trim_start_matches("mut ").trim();
+    let end = expression.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(expression.len());
+    &expression[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_value_types_of_host_objects() {
+        let source = r#"
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn run(env: Env, users: Vec<Address>, count: u32) {
+        let names = Map::new(&env);
+        let raw: Bytes = Bytes::new(&env);
+        let total = count;
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let function = &contract.implementations[0].functions[0];
+        let end = SourceSpan {
+            start_byte: source.len(),
+            end_byte: source.len(),
+            ..SourceSpan::default()
+        };
+        let type_of = |value: &str| value_type(function, value, end);
+
+        assert_eq!(type_of("&users").as_deref(), Some("Vec<Address>"));
+        assert_eq!(type_of("names").as_deref(), Some("Map"));
+        assert_eq!(type_of("raw.clone()").as_deref(), Some("Bytes"));
+        assert_eq!(type_of("total"), None);
+        assert!(is_host_type("&soroban_sdk::Vec<Address>"));
+        assert!(!is_host_type("u32"));
+        assert_eq!(root_name("&data.slice(1..)"), "data");
+    }
+}
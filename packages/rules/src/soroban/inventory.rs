@@ -0,0 +1,345 @@
+//! Storage key inventory
+//!
+//! Lists every storage key a contract touches, with the tier it lives in, the
+//! type of the value stored under it and the functions that read, write and
+//! extend it. This is the ledger footprint of the contract as far as it can be
+//! told from the source.
+
+use super::{
+    KeyedAccess, SorobanContract, SorobanFunction, StatementKind, StorageKey, StorageOperation, StorageTier,
+};
+use serde::Serialize;
+use std::fmt;
+
+/// How a storage key is built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyKind {
+    /// A variant of a `#[contracttype]` enum such as `DataKey::Balance`
+    Variant,
+    /// A `Symbol`, through `symbol_short!`, `Symbol::new` or a `Symbol` constant
+    Symbol,
+    /// Any other expression
+    Other,
+}
+
+/// A storage key in one storage tier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InventoryKey {
+    /// Name of the key, as in [`StorageKey::name`]
+    pub key: String,
+    pub kind: KeyKind,
+    pub tier: StorageTier,
+    /// Whether the key names one entry per distinct value, e.g. `DataKey::Balance(Address)`
+    pub parameterized: bool,
+    /// Types of the values the key is built from, taken from the enum variant when
+    /// there is one
+    pub key_types: Vec<String>,
+    /// Type of the stored value, when the source states it
+    pub value_type: Option<String>,
+    /// Functions reading the entry
+    pub readers: Vec<String>,
+    /// Functions writing or removing the entry
+    pub writers: Vec<String>,
+    /// Functions extending the entry's TTL
+    pub extenders: Vec<String>,
+}
+
+/// Every storage key a contract can touch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageInventory {
+    pub contract: String,
+    pub keys: Vec<InventoryKey>,
+}
+
+impl StorageInventory {
+    pub fn build(contract: &SorobanContract) -> Self {
+        let mut keys: Vec<InventoryKey> = Vec::new();
+
+        for keyed in contract.storage_accesses() {
+            let Some(key) = keyed.key() else { continue };
+            let access = keyed.access;
+
+            let index = match keys.iter().position(|entry| entry.key == key.name && entry.tier == access.tier) {
+                Some(index) => index,
+                None => {
+                    let (kind, key_types) = Self::classify(contract, &key);
+                    keys.push(InventoryKey {
+                        key: key.name.clone(),
+                        kind,
+                        tier: access.tier,
+                        parameterized: key.is_parameterized(),
+                        key_types,
+                        value_type: None,
+                        readers: Vec::new(),
+                        writers: Vec::new(),
+                        extenders: Vec::new(),
+                    });
+                    keys.len() - 1
+                }
+            };
+            let entry = &mut keys[index];
+
+            let function = &keyed.function.name;
+            let lists = [
+                (access.operation.is_read(), &mut entry.readers),
+                (access.operation.is_write(), &mut entry.writers),
+                (access.operation == StorageOperation::ExtendTtl, &mut entry.extenders),
+            ];
+            for (applies, list) in lists {
+                if applies && !list.contains(function) {
+                    list.push(function.clone());
+                }
+            }
+            if entry.value_type.is_none() {
+                entry.value_type = value_type(keyed, &contract.source);
+            }
+        }
+
+        Self {
+            contract: contract.name.clone(),
+            keys,
+        }
+    }
+
+    /// Kind of a key and the types of its payload
+    fn classify(contract: &SorobanContract, key: &StorageKey) -> (KeyKind, Vec<String>) {
+        if let Some((enum_name, variant_name)) = key.name.rsplit_once("::") {
+            let enum_name = enum_name.rsplit("::").next().unwrap_or(enum_name);
+            let variant = contract
+                .enums
+                .iter()
+                .filter(|definition| definition.name == enum_name)
+                .find_map(|definition| definition.variant(variant_name));
+            if let Some(variant) = variant {
+                return (KeyKind::Variant, variant.fields.clone());
+            }
+        }
+
+        let symbol = key.name.starts_with("symbol_short!")
+            || key.name.starts_with("Symbol::")
+            || contract
+                .constants
+                .iter()
+                .any(|constant| constant.name == key.name && constant.type_name == "Symbol");
+        (if symbol { KeyKind::Symbol } else { KeyKind::Other }, Vec::new())
+    }
+}
+
+/// Type of the value an access stores or loads, when the source states it
+fn value_type(keyed: KeyedAccess<'_>, source: &str) -> Option<String> {
+    let function = keyed.function;
+    let access = keyed.access;
+    match access.operation {
+        StorageOperation::Set => {
+            let value = access.args.get(1)?.trim_start_matches('&').trim();
+            local_type(function, value, access.span.start_byte).or_else(|| literal_type(value))
+        }
+        StorageOperation::Get => {
+            let statement = function
+                .body
+                .statements
+                .iter()
+                .rev()
+                .find(|statement| statement.span.contains(&access.span))?;
+            let declared = match &statement.kind {
+                StatementKind::Let { type_name: Some(type_name), .. } => type_name.clone(),
+                // The tail expression of the function is its return value
+                StatementKind::Expr
+                    if statement.depth == 0
+                        && function.body.statements.last().is_some_and(|last| std::ptr::eq(last, statement))
+                        && !statement.span.snippet(source).ends_with(';') =>
+                {
+                    function.return_type.clone()?
+                }
+                _ => return None,
+            };
+            Some(strip_option(&declared).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Declared type of a parameter or typed `let` binding named `name` before `before`
+fn local_type(function: &SorobanFunction, name: &str, before: usize) -> Option<String> {
+    let binding = function.body.statements.iter().rev().find_map(|statement| match &statement.kind {
+        StatementKind::Let { pattern, type_name: Some(type_name), .. }
+            if statement.span.end_byte <= before && pattern.trim_start_matches("mut ") == name =>
+        {
+            Some(type_name.clone())
+        }
+        _ => None,
+    });
+    binding.or_else(|| {
+        function
+            .params
+            .iter()
+            .find(|param| param.name == name)
+            .map(|param| param.type_name.trim_start_matches('&').trim().to_string())
+    })
+}
+
+/// Type of a literal value such as `true` or `0u64`
+fn literal_type(value: &str) -> Option<String> {
+    if value == "true" || value == "false" {
+        return Some("bool".to_string());
+    }
+    if !value.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    const SUFFIXES: &[&str] = &["u32", "u64", "u128", "i32", "i64", "i128"];
+    SUFFIXES.iter().find(|suffix| value.ends_with(*suffix)).map(|suffix| suffix.to_string())
+}
+
+/// `T` for `Option<T>`, the type `get` actually returns, or for `Result<T, E>`
+/// when a missing entry is turned into an error
+fn strip_option(type_name: &str) -> &str {
+    if let Some(inner) = type_name.strip_prefix("Result<").and_then(|inner| inner.strip_suffix('>')) {
+        let mut depth = 0usize;
+        for (index, c) in inner.char_indices() {
+            match c {
+                '<' | '(' => depth += 1,
+                '>' | ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => return inner[..index].trim(),
+                _ => {}
+            }
+        }
+    }
+    type_name
+        .strip_prefix("Option<")
+        .and_then(|inner| inner.strip_suffix('>'))
+        .map(str::trim)
+        .unwrap_or(type_name)
+}
+
+impl fmt::Display for StorageInventory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🗄️  Storage keys of {} ({})", self.contract, self.keys.len())?;
+        for key in &self.keys {
+            let mut name = key.key.clone();
+            if !key.key_types.is_empty() {
+                name = format!("{}({})", name, key.key_types.join(", "));
+            }
+            writeln!(
+                f,
+                "   • {} [{}] -> {}",
+                name,
+                key.tier.method(),
+                key.value_type.as_deref().unwrap_or("?")
+            )?;
+            let list = |functions: &[String]| {
+                if functions.is_empty() {
+                    "-".to_string()
+                } else {
+                    functions.join(", ")
+                }
+            };
+            writeln!(
+                f,
+                "     read by: {}; written by: {}; extended by: {}",
+                list(&key.readers),
+                list(&key.writers),
+                list(&key.extenders)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_storage_inventory() {
+        let source = r#"
+const ADMIN: Symbol = symbol_short!("ADMIN");
+
+#[contracttype]
+pub enum DataKey {
+    Balance(Address),
+    Allowance(Address, Address),
+    Paused,
+}
+
+#[contract]
+pub struct Token;
+
+#[contractimpl]
+impl Token {
+    pub fn init(env: Env, admin: Address) {
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&DataKey::Paused, &false);
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let key = DataKey::Balance(to);
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + amount));
+        env.storage().persistent().extend_ttl(&key, 100, 1000);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Balance(id)).unwrap_or(0)
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "token.rs").unwrap();
+        let inventory = StorageInventory::build(&contract);
+
+        let keys: Vec<_> = inventory.keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(keys, vec!["ADMIN", "DataKey::Paused", "DataKey::Balance"]);
+
+        let admin = &inventory.keys[0];
+        assert_eq!((admin.kind, admin.tier), (KeyKind::Symbol, StorageTier::Instance));
+        assert_eq!(admin.value_type.as_deref(), Some("Address"));
+        assert!(!admin.parameterized);
+
+        assert_eq!(inventory.keys[1].value_type.as_deref(), Some("bool"));
+
+        let balance = &inventory.keys[2];
+        assert_eq!(balance.kind, KeyKind::Variant);
+        assert!(balance.parameterized);
+        assert_eq!(balance.key_types, vec!["Address"]);
+        assert_eq!(balance.value_type.as_deref(), Some("i128"));
+        assert_eq!(balance.readers, vec!["mint", "balance"]);
+        assert_eq!(balance.writers, vec!["mint"]);
+        assert_eq!(balance.extenders, vec!["mint"]);
+
+        let report = inventory.to_string();
+        assert!(report.contains("DataKey::Balance(Address) [persistent] -> i128"));
+    }
+
+    #[test]
+    fn test_constant_symbol_keys_are_separate_entries() {
+        let source = r#"
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn init(env: Env, admin: Address, name: String) {
+        env.storage().instance().set(&Symbol::new(&env, "A"), &admin);
+        env.storage().instance().set(
+            &Symbol::new(&env, "B").clone(),
+            &true,
+        );
+        env.storage().persistent().set(&Symbol::new(&env, &name), &admin);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let inventory = StorageInventory::build(&contract);
+
+        let keys: Vec<_> = inventory.keys.iter().map(|k| (k.key.as_str(), k.kind, k.parameterized)).collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("Symbol::new(&env, \"A\")", KeyKind::Symbol, false),
+                ("Symbol::new(&env, \"B\")", KeyKind::Symbol, false),
+                ("Symbol::new", KeyKind::Symbol, true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,417 @@
+//! Loop bounds
+//!
+//! Works out what limits the number of iterations of each loop: a literal, a
+//! contract constant, a value the caller passes in, or something loaded from
+//! storage. Alongside the bound it records whether each iteration reads or
+//! writes storage or calls another contract, directly or through a helper.
+
+use super::{root_name, CallGraph, ExternalCall, GuardKind, Loop, LoopKind, SorobanContract, SorobanFunction, StatementKind, StorageKey, StorageOperation};
+use crate::SourceSpan;
+
+/// Maximum number of local bindings followed back from a loop bound
+const MAX_DEPTH: usize = 4;
+
+/// What limits a loop's iteration count
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopBound {
+    /// A literal, e.g. `0..10`
+    Constant(u128),
+    /// A `const` of the contract, e.g. `0..MAX_BATCH`
+    ContractConstant { name: String, value: u128 },
+    /// A parameter, or the length of one, e.g. `0..n` or `items.iter()`
+    Parameter(String),
+    /// A value loaded from storage, or its length, by key when it resolves
+    Storage(Option<String>),
+    /// A local the analysis cannot trace back
+    Unknown,
+    /// Nothing in the header limits the loop: `loop`, or a `while` condition
+    /// that compares no counter
+    Unbounded,
+}
+
+impl LoopBound {
+    /// Whether the iteration count is fixed when the contract is built
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, Self::Constant(_) | Self::ContractConstant { .. })
+    }
+
+    /// Short description such as `caller-supplied parameter 'n'`
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Constant(value) => format!("the constant {}", value),
+            Self::ContractConstant { name, value } => format!("the contract constant '{}' ({})", name, value),
+            Self::Parameter(name) => format!("caller-supplied parameter '{}'", name),
+            Self::Storage(Some(key)) => format!("a value loaded from storage under '{}'", key),
+            Self::Storage(None) => "a value loaded from storage".to_string(),
+            Self::Unknown => "a local value".to_string(),
+            Self::Unbounded => "nothing in its header".to_string(),
+        }
+    }
+}
+
+/// A loop, its bound and what each iteration costs
+#[derive(Debug, Clone)]
+pub struct LoopAnalysis<'a> {
+    pub function: &'a SorobanFunction,
+    pub lp: &'a Loop,
+    pub bound: LoopBound,
+    /// Whether the body reads or writes storage, directly or through a helper
+    pub touches_storage: bool,
+    /// Whether the body calls another contract, directly or through a helper
+    pub calls_contract: bool,
+}
+
+impl<'a> LoopAnalysis<'a> {
+    /// Analysis of every loop in `function`, following helper calls through `graph`
+    pub fn analyze(contract: &SorobanContract, graph: &CallGraph<'a>, function: &'a SorobanFunction) -> Vec<Self> {
+        let body = &function.body;
+        let external = ExternalCall::find(function);
+        body.loops
+            .iter()
+            .map(|lp| {
+                let helpers: Vec<_> = body
+                    .calls
+                    .iter()
+                    .filter(|call| lp.repeats(&call.span))
+                    .filter_map(|call| graph.resolve(function, call))
+                    .collect();
+                let touches_storage = body.storage_accesses.iter().any(|access| lp.repeats(&access.span))
+                    || helpers.iter().any(|helper| graph.reaches(helper, |f| !f.body.storage_accesses.is_empty()));
+                let calls_contract = external.iter().any(|call| lp.repeats(&call.span()) && !call.is_read_only())
+                    || helpers.iter().any(|helper| graph.reaches_external_call(helper));
+                Self {
+                    function,
+                    lp,
+                    bound: bound(contract, function, lp),
+                    touches_storage,
+                    calls_contract,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether each iteration touches storage or calls another contract
+    pub fn has_expensive_body(&self) -> bool {
+        self.touches_storage || self.calls_contract
+    }
+}
+
+/// Bound of `lp` in `function`
+fn bound(contract: &SorobanContract, function: &SorobanFunction, lp: &Loop) -> LoopBound {
+    let Some(expression) = bound_expression(lp) else {
+        return LoopBound::Unbounded;
+    };
+
+    // `for i in 0..env.storage().instance().get(&COUNT).unwrap()`
+    let read = function
+        .body
+        .storage_accesses_within(lp.header_span)
+        .find(|access| access.operation == StorageOperation::Get);
+    if let Some(access) = read {
+        return LoopBound::Storage(StorageKey::resolve(function, access).map(|key| key.name));
+    }
+    classify(contract, function, expression, lp.header_span, 0)
+}
+
+/// The expression limiting the iteration count: the end of a range, the
+/// collection iterated, or what a `while` counter is compared against
+fn bound_expression(lp: &Loop) -> Option<&str> {
+    let header = lp.header.as_deref()?.trim();
+    match lp.kind {
+        LoopKind::Loop => None,
+        LoopKind::For => match header.split_once("..") {
+            Some((_, end)) => {
+                let end = end.trim_start_matches('=');
+                // `(0..n).rev()` ends the range at the unmatched parenthesis
+                let mut depth = 0i32;
+                let close = end.char_indices().find_map(|(index, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' if depth == 0 => return Some(index),
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    None
+                });
+                let end = end[..close.unwrap_or(end.len())].trim();
+                (!end.is_empty()).then_some(end)
+            }
+            None => Some(header),
+        },
+        LoopKind::While => {
+            if let Some(pattern) = header.strip_prefix("let ") {
+                return pattern.split_once('=').map(|(_, scrutinee)| scrutinee.trim());
+            }
+            header.split("&&").find_map(|condition| {
+                for operator in [" <= ", " < "] {
+                    if let Some((_, right)) = condition.split_once(operator) {
+                        return Some(right.trim());
+                    }
+                }
+                for operator in [" >= ", " > "] {
+                    if let Some((left, _)) = condition.split_once(operator) {
+                        return Some(left.trim());
+                    }
+                }
+                None
+            })
+        }
+    }
+}
+
+/// What `expression`, used in `function` at `at`, is derived from
+fn classify(contract: &SorobanContract, function: &SorobanFunction, expression: &str, at: SourceSpan, depth: usize) -> LoopBound {
+    let expression = expression.trim().trim_start_matches('&').trim();
+    if let Some(value) = contract.evaluate(expression) {
+        return match expression.find(|c: char| c.is_ascii_uppercase()) {
+            Some(_) => LoopBound::ContractConstant {
+                name: expression.to_string(),
+                value,
+            },
+            None => LoopBound::Constant(value),
+        };
+    }
+
+    // `n.min(MAX_BATCH)` is capped by its argument
+    if let Some((_, cap)) = expression.split_once(".min(") {
+        let cap = cap.split(')').next().unwrap_or_default();
+        let capped = classify(contract, function, cap, at, depth);
+        if capped.is_fixed() {
+            return capped;
+        }
+    }
+
+    let root = root_name(expression);
+    if root.is_empty() {
+        return LoopBound::Unknown;
+    }
+    if let Some(limit) = guard_limit(contract, function, root, at) {
+        return limit;
+    }
+    if function.params.iter().any(|param| param.name == root) {
+        return LoopBound::Parameter(root.to_string());
+    }
+
+    let binding = function
+        .body
+        .statements
+        .iter()
+        .rev()
+        .filter(|statement| statement.span.end_byte <= at.start_byte)
+        .find(|statement| {
+            matches!(&statement.kind, StatementKind::Let { pattern, .. } if pattern.trim_start_matches("mut ") == root)
+        });
+    let Some(statement) = binding else {
+        return LoopBound::Unknown;
+    };
+    let read = function
+        .body
+        .storage_accesses_within(statement.span)
+        .find(|access| access.operation == StorageOperation::Get);
+    if let Some(access) = read {
+        return LoopBound::Storage(StorageKey::resolve(function, access).map(|key| key.name));
+    }
+    match &statement.kind {
+        StatementKind::Let { init: Some(init), .. } if depth < MAX_DEPTH => classify(contract, function, init, statement.span, depth + 1),
+        _ => LoopBound::Unknown,
+    }
+}
+
+/// The fixed upper limit a guard before `at` enforces on `name`, or on a local
+/// derived from it such as `let len = name.len();`: `assert!(name <= MAX)` or
+/// `if name > MAX { return .. }`
+fn guard_limit(contract: &SorobanContract, function: &SorobanFunction, name: &str, at: SourceSpan) -> Option<LoopBound> {
+    let mut names = vec![name];
+    for statement in &function.body.statements {
+        if let StatementKind::Let { pattern, init: Some(init), .. } = &statement.kind {
+            if statement.span.end_byte <= at.start_byte && root_name(init) == name {
+                names.push(pattern.trim_start_matches("mut "));
+            }
+        }
+    }
+
+    function
+        .body
+        .guards
+        .iter()
+        // Either side of `a || b` may be the one that holds
+        .filter(|guard| guard.span.end_byte <= at.start_byte && !guard.condition.contains("||"))
+        .flat_map(|guard| {
+            // An exit lets the function go on when its condition fails, and
+            // `!(a && b)` bounds neither side
+            let comparisons = match guard.kind {
+                GuardKind::Assert => guard.condition.split("&&").collect(),
+                GuardKind::Exit if guard.condition.contains("&&") => Vec::new(),
+                GuardKind::Exit => vec![guard.condition.as_str()],
+            };
+            comparisons.into_iter().map(move |comparison| (guard.kind, comparison))
+        })
+        .find_map(|(kind, comparison)| {
+            let (operator, left, right) = ["<=", ">=", "<", ">"].iter().find_map(|operator| {
+                let (left, right) = comparison.split_once(operator)?;
+                Some((*operator, left.trim(), right.trim()))
+            })?;
+            // Whether the comparison holds when `name` is below the limit
+            let (below, limit) = if names.contains(&root_name(left)) {
+                (matches!(operator, "<" | "<="), right)
+            } else if names.contains(&root_name(right)) {
+                (matches!(operator, ">" | ">="), left)
+            } else {
+                return None;
+            };
+            if below != (kind == GuardKind::Assert) {
+                return None;
+            }
+            let limit = classify(contract, function, limit, at, MAX_DEPTH);
+            limit.is_fixed().then_some(limit)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_classify_loop_bounds() {
+        let source = r#"
+const MAX_BATCH: u32 = 50;
+
+#[contract]
+pub struct Payout;
+
+#[contractimpl]
+impl Payout {
+    pub fn run(env: Env, n: u32, users: Vec<Address>) {
+        for i in 0..10 {}
+        for i in 0..MAX_BATCH {}
+        for i in 0..n.min(MAX_BATCH) {}
+        if users.len() > MAX_BATCH {
+            return;
+        }
+        for user in users.iter() {
+            Self::pay(&env, &user);
+        }
+        let stored: Vec<Address> = env.storage().persistent().get(&DataKey::Members).unwrap();
+        let count = stored.len();
+        let mut i = 0;
+        while i < count {
+            i += 1;
+        }
+        loop {}
+    }
+
+    fn pay(env: &Env, user: &Address) {
+        env.storage().persistent().set(&DataKey::Paid(user.clone()), &true);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "payout.rs").unwrap();
+        let graph = CallGraph::build(&contract);
+        let loops = LoopAnalysis::analyze(&contract, &graph, &contract.implementations[0].functions[0]);
+
+        let bounds: Vec<_> = loops.iter().map(|analysis| analysis.bound.clone()).collect();
+        assert_eq!(
+            bounds,
+            vec![
+                LoopBound::Constant(10),
+                LoopBound::ContractConstant { name: "MAX_BATCH".to_string(), value: 50 },
+                LoopBound::ContractConstant { name: "MAX_BATCH".to_string(), value: 50 },
+                LoopBound::ContractConstant { name: "MAX_BATCH".to_string(), value: 50 },
+                LoopBound::Storage(Some("DataKey::Members".to_string())),
+                LoopBound::Unbounded,
+            ]
+        );
+        assert!(loops[3].touches_storage);
+        assert!(!loops[4].has_expensive_body());
+    }
+
+    #[test]
+    fn test_for_header_runs_once() {
+        let source = r#"
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn copy(env: Env) {
+        for x in env.storage().persistent().get::<u32, Vec<Address>>(&1).unwrap().iter() {
+            let y = x;
+        }
+        while env.storage().instance().has(&DataKey::Pending) {
+            break;
+        }
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let graph = CallGraph::build(&contract);
+        let loops = LoopAnalysis::analyze(&contract, &graph, &contract.implementations[0].functions[0]);
+
+        assert_eq!(loops[0].bound, LoopBound::Storage(Some("1".to_string())));
+        assert!(!loops[0].touches_storage);
+        // A `while` condition is checked again before every iteration
+        assert!(loops[1].touches_storage);
+    }
+
+    #[test]
+    fn test_guards_bound_only_from_above() {
+        let source = r#"
+const MAX_BATCH: u32 = 50;
+
+#[contract]
+pub struct Payout;
+
+#[contractimpl]
+impl Payout {
+    pub fn capped(env: Env, users: Vec<Address>) {
+        assert!(users.len() <= MAX_BATCH);
+        for user in users.iter() {}
+    }
+
+    pub fn reversed(env: Env, users: Vec<Address>) {
+        if users.len() < MAX_BATCH {
+            return;
+        }
+        for user in users.iter() {}
+    }
+
+    pub fn reversed_assert(env: Env, users: Vec<Address>) {
+        assert!(users.len() >= MAX_BATCH);
+        for user in users.iter() {}
+    }
+
+    pub fn either(env: Env, users: Vec<Address>, admin: bool) {
+        assert!(users.len() <= MAX_BATCH || admin);
+        for user in users.iter() {}
+    }
+
+    pub fn both(env: Env, users: Vec<Address>, strict: bool) {
+        if users.len() > MAX_BATCH && strict {
+            return;
+        }
+        for user in users.iter() {}
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "payout.rs").unwrap();
+        let graph = CallGraph::build(&contract);
+        let bounds: Vec<_> = contract.implementations[0]
+            .functions
+            .iter()
+            .map(|function| LoopAnalysis::analyze(&contract, &graph, function)[0].bound.clone())
+            .collect();
+
+        let parameter = LoopBound::Parameter("users".to_string());
+        assert_eq!(
+            bounds,
+            vec![
+                LoopBound::ContractConstant { name: "MAX_BATCH".to_string(), value: 50 },
+                parameter.clone(),
+                parameter.clone(),
+                parameter.clone(),
+                parameter,
+            ]
+        );
+    }
+}
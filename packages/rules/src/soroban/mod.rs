@@ -5,12 +5,39 @@
 //! `#[contract]`, `#[contractimpl]`, and `#[contracttype]`.
 
 pub mod parser;
-pub mod analyzer;
+pub mod auth;
+pub mod body;
+pub mod call_graph;
+pub mod coverage;
+pub mod dataflow;
 pub mod rule_engine;
+pub mod eval;
+pub mod events;
+pub mod external;
+pub mod host;
+pub mod inventory;
+pub mod loops;
+pub mod panics;
+pub mod storage;
+pub mod ttl;
 
 pub use parser::*;
-pub use analyzer::*;
+pub use auth::*;
+pub use body::*;
+pub use call_graph::*;
+pub use coverage::*;
+pub use dataflow::*;
 pub use rule_engine::*;
+pub use events::*;
+pub use external::*;
+pub use host::*;
+pub use inventory::*;
+pub use loops::*;
+pub use panics::*;
+pub use storage::*;
+pub use ttl::*;
+
+use crate::SourceSpan;
 
 /// Represents a Soroban contract structure
 #[derive(Debug, Clone, PartialEq)]
@@ -19,8 +46,22 @@ pub struct SorobanContract {
     pub name: String,
     /// Struct definitions marked with #[contracttype]
     pub contract_types: Vec<SorobanStruct>,
+    /// Enum definitions marked with #[contracttype], such as storage key enums
+    pub enums: Vec<SorobanEnum>,
+    /// Error enums marked with #[contracterror]
+    pub errors: Vec<SorobanEnum>,
+    /// Event types marked with #[contractevent]
+    pub events: Vec<SorobanEvent>,
     /// Implementation blocks marked with #[contractimpl]
     pub implementations: Vec<SorobanImpl>,
+    /// Impl blocks without #[contractimpl], e.g. methods of #[contracttype] structs
+    pub helper_impls: Vec<SorobanImpl>,
+    /// Free-standing functions
+    pub free_functions: Vec<SorobanFunction>,
+    /// `const` items at module level and in impl blocks
+    pub constants: Vec<SorobanConstant>,
+    /// Location of the contract declaration
+    pub span: SourceSpan,
     /// Raw contract source code
     pub source: String,
     /// File path of the contract
@@ -36,6 +77,8 @@ pub struct SorobanStruct {
     pub fields: Vec<SorobanField>,
     /// Line number where the struct is defined
     pub line_number: usize,
+    /// Location of the struct definition, excluding its attributes
+    pub span: SourceSpan,
     /// Raw struct definition
     pub raw_definition: String,
 }
@@ -51,6 +94,65 @@ pub struct SorobanField {
     pub visibility: FieldVisibility,
     /// Line number of the field
     pub line_number: usize,
+    /// Location of the field declaration
+    pub span: SourceSpan,
+}
+
+/// Represents an enum definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct SorobanEnum {
+    /// Name of the enum
+    pub name: String,
+    /// Variants of the enum
+    pub variants: Vec<SorobanVariant>,
+    /// Line number where the enum is defined
+    pub line_number: usize,
+    /// Location of the enum definition, excluding its attributes
+    pub span: SourceSpan,
+    /// Raw enum definition
+    pub raw_definition: String,
+}
+
+impl SorobanEnum {
+    /// The variant with the given name
+    pub fn variant(&self, name: &str) -> Option<&SorobanVariant> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+}
+
+/// Represents an event type with #[contractevent] macro
+#[derive(Debug, Clone, PartialEq)]
+pub struct SorobanEvent {
+    /// Name of the event type
+    pub name: String,
+    /// Static topics published before the `#[topic]` fields; `#[contractevent(topics = [..])]`
+    /// or else the event name in snake case
+    pub prefix_topics: Vec<String>,
+    /// Fields of the event
+    pub fields: Vec<SorobanField>,
+    /// Names of the fields published as topics rather than data
+    pub topic_fields: Vec<String>,
+    /// Line number where the event is defined
+    pub line_number: usize,
+    /// Location of the event definition, excluding its attributes
+    pub span: SourceSpan,
+    /// Raw event definition
+    pub raw_definition: String,
+}
+
+/// Represents an enum variant
+#[derive(Debug, Clone, PartialEq)]
+pub struct SorobanVariant {
+    /// Name of the variant
+    pub name: String,
+    /// Types of the values the variant carries, e.g. `["Address"]` for `Balance(Address)`
+    pub fields: Vec<String>,
+    /// Source text of an explicit discriminant, e.g. `1` for `NotFound = 1`
+    pub discriminant: Option<String>,
+    /// Line number of the variant
+    pub line_number: usize,
+    /// Location of the variant, excluding its attributes
+    pub span: SourceSpan,
 }
 
 /// Visibility modifiers for struct fields
@@ -69,6 +171,8 @@ pub struct SorobanImpl {
     pub functions: Vec<SorobanFunction>,
     /// Line number where the impl starts
     pub line_number: usize,
+    /// Location of the impl block, excluding its attributes
+    pub span: SourceSpan,
     /// Raw impl definition
     pub raw_definition: String,
 }
@@ -88,10 +192,29 @@ pub struct SorobanFunction {
     pub is_constructor: bool,
     /// Line number where the function is defined
     pub line_number: usize,
+    /// Location of the function, excluding its attributes and doc comments
+    pub span: SourceSpan,
+    /// Location of the function signature, up to the start of the body
+    pub signature_span: SourceSpan,
+    /// Statements, calls, loops and storage accesses in the function body
+    pub body: FunctionBody,
     /// Raw function definition
     pub raw_definition: String,
 }
 
+/// Represents a `const` item
+#[derive(Debug, Clone, PartialEq)]
+pub struct SorobanConstant {
+    /// Name of the constant
+    pub name: String,
+    /// Declared type
+    pub type_name: String,
+    /// Source text of the value expression
+    pub value: String,
+    /// Location of the constant declaration
+    pub span: SourceSpan,
+}
+
 /// Represents a function parameter
 #[derive(Debug, Clone, PartialEq)]
 pub struct SorobanParam {
@@ -99,6 +222,8 @@ pub struct SorobanParam {
     pub name: String,
     /// Parameter type
     pub type_name: String,
+    /// Location of the parameter
+    pub span: SourceSpan,
 }
 
 /// Function visibility modifiers
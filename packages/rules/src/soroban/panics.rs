@@ -0,0 +1,323 @@
+//! Panicking paths
+//!
+//! Finds where contract functions can panic, through `panic!` and its
+//! relatives or through `.unwrap()` and `.expect(..)`, and works out which
+//! `#[contracterror]` variant each one should return instead, proposing a new
+//! variant when none of the existing ones fits.
+
+use super::{Call, CallKind, SorobanContract, SorobanEnum, SorobanFunction, StorageKey, StorageOperation};
+use crate::SourceSpan;
+
+/// Macros that always panic
+const PANIC_MACROS: &[&str] = &["panic", "unreachable", "todo", "unimplemented"];
+
+/// Words ignored when matching a panic to an error variant
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "is", "are", "be", "been", "has", "have", "to", "of", "for", "in", "on", "with", "this", "must",
+    "should", "contract", "data", "key", "datakey", "symbol", "short", "unwrap", "expect",
+];
+
+/// Words too common in error names to identify a variant on their own
+const WEAK_WORDS: &[&str] = &["not", "no", "invalid", "already", "too"];
+
+/// Word pairs of opposite meaning; a variant using one never matches a panic using the other
+const OPPOSITES: &[(&str, &str)] = &[("not", "already"), ("not", "exists")];
+
+/// How a function panics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicKind {
+    /// `panic!`, `unreachable!`, `todo!` or `unimplemented!`
+    Macro,
+    /// `.unwrap()`
+    Unwrap,
+    /// `.expect(..)`
+    Expect,
+}
+
+/// A point where a contract function can panic
+#[derive(Debug, Clone)]
+pub struct PanicSite<'a> {
+    pub function: &'a SorobanFunction,
+    pub call: &'a Call,
+    pub kind: PanicKind,
+    /// Text of the panic message, without quotes
+    pub message: Option<String>,
+    /// Key of the storage read being unwrapped, if any
+    pub storage_key: Option<StorageKey>,
+}
+
+/// The `#[contracterror]` variant a panic should be replaced with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorVariant {
+    /// Name of the error enum, `Error` when the contract defines none
+    pub error_type: String,
+    pub name: String,
+    /// Discriminant to give a variant that does not exist yet
+    pub discriminant: Option<u32>,
+}
+
+impl ErrorVariant {
+    /// Whether the variant has to be added to the error enum
+    pub fn is_new(&self) -> bool {
+        self.discriminant.is_some()
+    }
+
+    /// Path of the variant, e.g. `Error::NotFound`
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.error_type, self.name)
+    }
+}
+
+impl<'a> PanicSite<'a> {
+    /// Every point `function` can panic, in source order
+    pub fn find(function: &'a SorobanFunction) -> Vec<Self> {
+        function
+            .body
+            .calls
+            .iter()
+            .filter_map(|call| {
+                let kind = match (call.kind, call.name.as_str()) {
+                    (CallKind::Macro, name) if PANIC_MACROS.contains(&name) => PanicKind::Macro,
+                    (CallKind::Method, "unwrap") if call.args.is_empty() => PanicKind::Unwrap,
+                    (CallKind::Method, "expect") if call.args.len() == 1 => PanicKind::Expect,
+                    _ => return None,
+                };
+                let storage_key = (kind != PanicKind::Macro)
+                    .then(|| {
+                        function
+                            .body
+                            .storage_accesses_within(call.span)
+                            .filter(|access| access.operation == StorageOperation::Get)
+                            .last()
+                            .and_then(|access| StorageKey::resolve(function, access))
+                    })
+                    .flatten();
+                Some(Self {
+                    function,
+                    call,
+                    kind,
+                    message: call.args.first().and_then(|message| string_literal(message)),
+                    storage_key,
+                })
+            })
+            .collect()
+    }
+
+    /// Location of the panicking call
+    pub fn span(&self) -> SourceSpan {
+        self.call.span
+    }
+
+    /// Short description such as `unwrap() on storage key 'DataKey::Admin'`
+    pub fn describe(&self) -> String {
+        let what = match self.kind {
+            PanicKind::Macro => format!("{}!", self.call.name),
+            PanicKind::Unwrap => "unwrap()".to_string(),
+            PanicKind::Expect => "expect()".to_string(),
+        };
+        match (&self.message, &self.storage_key) {
+            (Some(message), _) => format!("{} (\"{}\")", what, message),
+            (None, Some(key)) => format!("{} on storage key '{}'", what, key.name),
+            (None, None) => what,
+        }
+    }
+
+    /// The error variant to return instead: the best match among the contract's
+    /// `#[contracterror]` enums, or a new one named after the failure
+    pub fn error_variant(&self, contract: &SorobanContract) -> ErrorVariant {
+        let words = self.words();
+        let best = contract
+            .errors
+            .iter()
+            .flat_map(|error| error.variants.iter().map(move |variant| (error, variant)))
+            .filter_map(|(error, variant)| {
+                let variant_words = split_words(&variant.name);
+                let has = |words: &[String], word: &str| words.iter().any(|other| other == word);
+                let contradicts = OPPOSITES.iter().any(|(one, other)| {
+                    (has(&words, one) && has(&variant_words, other)) || (has(&words, other) && has(&variant_words, one))
+                });
+                if contradicts {
+                    return None;
+                }
+                let matched: Vec<&String> =
+                    words.iter().filter(|word| variant_words.iter().any(|other| same_word(word, other))).collect();
+                let strong = matched.iter().any(|word| !WEAK_WORDS.contains(&word.as_str()));
+                strong.then_some((matched.len(), error, variant))
+            })
+            .fold(None, |best: Option<(usize, _, _)>, candidate| match best {
+                Some(best) if best.0 >= candidate.0 => Some(best),
+                _ => Some(candidate),
+            });
+        if let Some((_, error, variant)) = best {
+            return ErrorVariant {
+                error_type: error.name.clone(),
+                name: variant.name.clone(),
+                discriminant: None,
+            };
+        }
+
+        let error = contract.errors.first();
+        ErrorVariant {
+            error_type: error.map_or_else(|| "Error".to_string(), |error| error.name.clone()),
+            name: self.new_variant_name(&words),
+            discriminant: Some(error.map_or(1, next_discriminant)),
+        }
+    }
+
+    /// Words describing the failure: from the message, the storage key read, or
+    /// the checked operation unwrapped
+    fn words(&self) -> Vec<String> {
+        if let Some(message) = &self.message {
+            return split_words(message);
+        }
+        if let Some(key) = &self.storage_key {
+            let mut words = split_words(&key.name);
+            words.extend(["not".to_string(), "found".to_string()]);
+            return words;
+        }
+        if self.call.receiver.as_deref().is_some_and(|receiver| receiver.contains(".checked_")) {
+            return vec!["overflow".to_string()];
+        }
+        Vec::new()
+    }
+
+    /// Name for a new variant, e.g. `AlreadyInitialized` for "Contract already initialized"
+    fn new_variant_name(&self, words: &[String]) -> String {
+        if words.is_empty() {
+            return format!("{}Failed", pascal_case(&self.function.name));
+        }
+        let name: String = words.iter().take(3).map(|word| pascal_case(word)).collect();
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("Error{}", name)
+        } else {
+            name
+        }
+    }
+}
+
+impl SorobanContract {
+    /// Every panicking point in the contract's `#[contractimpl]` functions
+    pub fn panic_sites(&self) -> impl Iterator<Item = PanicSite<'_>> {
+        self.functions().flat_map(PanicSite::find)
+    }
+}
+
+/// Contents of a string literal argument
+fn string_literal(expression: &str) -> Option<String> {
+    let inner = expression.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some(inner.to_string())
+}
+
+/// Lowercase words of a message or identifier, splitting `CamelCase` and
+/// `snake_case`, without stop words or format placeholders
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    let mut in_placeholder = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_placeholder = true,
+            '}' => in_placeholder = false,
+            _ if in_placeholder => continue,
+            _ => {}
+        }
+        let boundary = !c.is_alphanumeric() || (c.is_uppercase() && previous_lower);
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if c.is_alphanumeric() && !in_placeholder {
+            current.extend(c.to_lowercase());
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words.retain(|word| !STOP_WORDS.contains(&word.as_str()));
+    words
+}
+
+/// Whether two words are forms of the same word, e.g. `balance` and `balances`
+/// or `authorized` and `authorization`
+fn same_word(left: &str, right: &str) -> bool {
+    if left == right {
+        return true;
+    }
+    let common = left.chars().zip(right.chars()).take_while(|(a, b)| a == b).count();
+    common >= 5
+}
+
+fn pascal_case(word: &str) -> String {
+    word.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Discriminant following the highest one in `error`
+fn next_discriminant(error: &SorobanEnum) -> u32 {
+    let highest = error
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            variant
+                .discriminant
+                .as_deref()
+                .and_then(|value| value.trim().parse::<u32>().ok())
+                .unwrap_or(index as u32 + 1)
+        })
+        .max();
+    highest.map_or(1, |highest| highest + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_panic_sites_map_to_error_variants() {
+        let source = r#"
+#[contracterror]
+pub enum VaultError {
+    NotInitialized = 1,
+    InsufficientBalance = 2,
+    BalanceNotFound = 3,
+}
+
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn withdraw(env: Env, user: Address, amount: i128) {
+        let balance: i128 = env.storage().persistent().get(&DataKey::Balance(user.clone())).unwrap();
+        if balance < amount {
+            panic!("Insufficient balance: {}", amount);
+        }
+        let total = balance.checked_sub(amount).expect("math");
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap_or(user);
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Contract already initialized");
+        }
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "vault.rs").unwrap();
+        let sites: Vec<_> = contract.panic_sites().collect();
+        assert_eq!(sites.len(), 4);
+
+        let variants: Vec<_> = sites.iter().map(|site| site.error_variant(&contract)).collect();
+        assert_eq!(sites[0].describe(), "unwrap() on storage key 'DataKey::Balance'");
+        assert_eq!(variants[0].path(), "VaultError::BalanceNotFound");
+        assert_eq!(variants[1].path(), "VaultError::InsufficientBalance");
+        assert!(!variants[1].is_new());
+        assert_eq!(variants[2].name, "Math");
+        assert_eq!(variants[3].name, "AlreadyInitialized");
+        assert_eq!(variants[3].discriminant, Some(4));
+    }
+}
@@ -1,10 +1,12 @@
 //! Soroban contract parser implementation
 //!
 //! This module provides parsing capabilities for Soroban smart contracts,
-//! extracting AST-like structures from Rust code containing Soroban macros.
+//! building the Soroban model from the `syn` AST of the contract source so that
+//! every item carries an exact source span.
 
 use super::*;
-use regex::Regex;
+use syn::spanned::Spanned;
+use syn::{Attribute, Fields, FnArg, ImplItem, Item, ItemEnum, ItemImpl, ItemStruct, ReturnType, Type, Visibility};
 
 /// Parses Soroban contracts from source code
 pub struct SorobanParser;
@@ -12,448 +14,537 @@ pub struct SorobanParser;
 impl SorobanParser {
     /// Parse a Soroban contract from source code
     pub fn parse_contract(source: &str, file_path: &str) -> SorobanResult<SorobanContract> {
-        let lines: Vec<&str> = source.lines().collect();
-        
-        // Extract contract name from #[contract] attribute, or fallback to first struct
-        let contract_name = Self::extract_contract_name(source)
-            .unwrap_or_else(|_| "UnknownContract".to_string());
-        
+        let file = syn::parse_file(source).map_err(|e| {
+            let start = e.span().start();
+            SorobanParseError::ParseError(format!(
+                "{} (line {}, column {})",
+                e,
+                start.line,
+                start.column + 1
+            ))
+        })?;
+
+        // Contracts are frequently wrapped in inline modules, so flatten them first
+        let mut items = Vec::new();
+        Self::collect_items(&file.items, &mut items);
+
         // Parse struct definitions with #[contracttype]
-        let contract_types = Self::parse_contract_types(&lines)?;
-        
+        let contract_types = Self::parse_contract_types(&items, source);
+
+        // Parse enum definitions with #[contracttype]
+        let enums = Self::parse_enums(&items, "contracttype", source);
+
+        // Parse error enums with #[contracterror] and events with #[contractevent]
+        let errors = Self::parse_enums(&items, "contracterror", source);
+        let events = Self::parse_events(&items, source);
+
         // Parse implementation blocks with #[contractimpl]
-        let implementations = Self::parse_implementations(&lines)?;
-        
+        let implementations = Self::parse_implementations(&items, source);
+
+        // Helpers live in other impl blocks and in free functions
+        let helper_impls = Self::parse_helper_impls(&items, source);
+        let free_functions = Self::parse_free_functions(&items, source);
+
+        let constants = Self::parse_constants(&items, source);
+
+        // Extract contract name from #[contract] attribute, or fallback to first struct
+        let (contract_name, span) = Self::extract_contract_name(&items).unwrap_or_else(|_| {
+            let span = implementations.first().map(|imp| imp.span).unwrap_or_default();
+            ("UnknownContract".to_string(), span)
+        });
+
         Ok(SorobanContract {
             name: contract_name,
             contract_types,
+            enums,
+            errors,
+            events,
             implementations,
+            helper_impls,
+            free_functions,
+            constants,
+            span,
             source: source.to_string(),
             file_path: file_path.to_string(),
         })
     }
-    
+
+    /// Parse the free functions of a test file, including those in
+    /// `#[cfg(test)]` modules, separating `#[test]` functions from helpers
+    pub fn parse_test_file(source: &str, file_path: &str) -> SorobanResult<TestFile> {
+        let file = syn::parse_file(source).map_err(|e| {
+            let start = e.span().start();
+            SorobanParseError::ParseError(format!("{} (line {}, column {})", e, start.line, start.column + 1))
+        })?;
+
+        let mut test_file = TestFile {
+            file_path: file_path.to_string(),
+            tests: Vec::new(),
+            helpers: Vec::new(),
+        };
+        Self::collect_test_functions(&file.items, source, &mut test_file);
+        Ok(test_file)
+    }
+
+    fn collect_test_functions(items: &[Item], source: &str, out: &mut TestFile) {
+        for item in items {
+            match item {
+                Item::Fn(item_fn) => {
+                    let function = Self::parse_fn(&item_fn.vis, &item_fn.sig, &item_fn.block, item_fn.span(), source);
+                    if Self::has_attribute(&item_fn.attrs, "test") {
+                        out.tests.push(function);
+                    } else {
+                        out.helpers.push(function);
+                    }
+                }
+                Item::Mod(module) => {
+                    if let Some((_, content)) = &module.content {
+                        Self::collect_test_functions(content, source, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Collect top-level items, descending into inline `mod` blocks
+    fn collect_items<'a>(items: &'a [Item], out: &mut Vec<&'a Item>) {
+        for item in items {
+            out.push(item);
+            if let Item::Mod(module) = item {
+                // Unit tests are not part of the deployed contract
+                if Self::is_test_module(&module.attrs) {
+                    continue;
+                }
+                if let Some((_, content)) = &module.content {
+                    Self::collect_items(content, out);
+                }
+            }
+        }
+    }
+
+    /// Whether a module is marked `#[cfg(test)]`
+    fn is_test_module(attrs: &[Attribute]) -> bool {
+        attrs.iter().any(|attr| {
+            attr.path().is_ident("cfg")
+                && attr
+                    .parse_args::<syn::Ident>()
+                    .is_ok_and(|ident| ident == "test")
+        })
+    }
+
     /// Extract contract name from #[contract] attribute
-    fn extract_contract_name(source: &str) -> SorobanResult<String> {
-        let contract_re = Regex::new(r#"#\s*\[\s*contract\s*\(\s*(.*?)\s*\)\s*\]"#).unwrap();
-        
-        if let Some(captures) = contract_re.captures(source) {
-            if let Some(name) = captures.get(1) {
-                return Ok(name.as_str().trim().to_string());
+    fn extract_contract_name(items: &[&Item]) -> SorobanResult<(String, SourceSpan)> {
+        for item in items {
+            if let Item::Struct(item_struct) = item {
+                if let Some(attr) = Self::find_attribute(&item_struct.attrs, "contract") {
+                    // `#[contract(Name)]` names the contract explicitly
+                    let name = attr
+                        .parse_args::<syn::Ident>()
+                        .unwrap_or_else(|_| item_struct.ident.clone());
+                    return Ok((name.to_string(), Self::struct_span(item_struct)));
+                }
             }
         }
-        
-        let struct_re = Regex::new(r#"#\s*\[\s*contracttype\s*\][\s\S]*?(?:pub\s+)?struct\s+(\w+)"#).unwrap();
-        if let Some(captures) = struct_re.captures(source) {
-            if let Some(name) = captures.get(1) {
-                return Ok(name.as_str().to_string());
+
+        for item in items {
+            if let Item::Struct(item_struct) = item {
+                if Self::has_attribute(&item_struct.attrs, "contracttype") {
+                    return Ok((item_struct.ident.to_string(), Self::struct_span(item_struct)));
+                }
             }
         }
-        
+
         Err(SorobanParseError::MissingMacro(
             "Could not determine contract name from #[contract] or #[contracttype] attributes".to_string()
         ))
     }
-    
+
     /// Parse struct definitions with #[contracttype] macro
-    fn parse_contract_types(lines: &[&str]) -> SorobanResult<Vec<SorobanStruct>> {
-        let mut structs = Vec::new();
-        let mut i = 0;
-        
-        while i < lines.len() {
-            if lines[i].trim().starts_with("#[contracttype]") {
-                let line_number = i + 1;
-                i += 1;
-                while i < lines.len() && !lines[i].trim().contains("struct") {
-                    i += 1;
+    fn parse_contract_types(items: &[&Item], source: &str) -> Vec<SorobanStruct> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(item_struct) if Self::has_attribute(&item_struct.attrs, "contracttype") => {
+                    Some(Self::parse_struct(item_struct, source))
                 }
-                
-                if i >= lines.len() {
-                    break;
-                }
-                
-                if let Some(soroban_struct) = Self::parse_single_struct(&lines[i..], line_number)? {
-                    structs.push(soroban_struct);
-                }
-            }
-            i += 1;
-        }
-        
-        Ok(structs)
+                _ => None,
+            })
+            .collect()
     }
-    
+
     /// Parse a single struct definition
-    fn parse_single_struct(lines: &[&str], start_line: usize) -> SorobanResult<Option<SorobanStruct>> {
-        if lines.is_empty() || !lines[0].trim().contains("struct") {
-            return Ok(None);
-        }
-        
-        let struct_line = lines[0].trim();
-        let name_re = Regex::new(r"struct\s+(\w+)").unwrap();
-        let name = name_re.captures(struct_line)
-            .and_then(|caps| caps.get(1))
-            .map(|m| m.as_str().to_string())
-            .ok_or_else(|| SorobanParseError::ParseError(
-                format!("Could not parse struct name from: {}", struct_line)
-            ))?;
-        
-        let mut brace_count = 0;
-        let mut struct_lines = vec![struct_line];
-        let mut i = 1;
-        
-        if struct_line.contains('{') {
-            brace_count += 1;
+    fn parse_struct(item_struct: &ItemStruct, source: &str) -> SorobanStruct {
+        let span = Self::struct_span(item_struct);
+
+        let fields = match &item_struct.fields {
+            Fields::Named(named) => named.named.iter().collect::<Vec<_>>(),
+            Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+        };
+
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let first = field.ident.as_ref().map_or_else(|| field.ty.span(), |ident| ident.span());
+                let field_span = SourceSpan::between(Self::start_span(&field.vis, first), field.ty.span());
+                SorobanField {
+                    // Tuple struct fields are named by their position
+                    name: field
+                        .ident
+                        .as_ref()
+                        .map(|ident| ident.to_string())
+                        .unwrap_or_else(|| index.to_string()),
+                    type_name: Self::type_text(&field.ty, source),
+                    visibility: match field.vis {
+                        Visibility::Public(_) => FieldVisibility::Public,
+                        _ => FieldVisibility::Private,
+                    },
+                    line_number: field_span.start_line,
+                    span: field_span,
+                }
+            })
+            .collect();
+
+        SorobanStruct {
+            name: item_struct.ident.to_string(),
+            fields,
+            line_number: span.start_line,
+            span,
+            raw_definition: span.snippet(source).to_string(),
         }
-        
-        while i < lines.len() {
-            let line = lines[i].trim();
-            struct_lines.push(line);
-            
-            if line.contains('{') && i > 0 {
-                brace_count += 1;
-            }
-            if line.contains('}') {
-                brace_count -= 1;
-                if brace_count == 0 {
-                    break;
+    }
+
+    /// Parse enum definitions carrying the `attribute` macro
+    fn parse_enums(items: &[&Item], attribute: &str, source: &str) -> Vec<SorobanEnum> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Enum(item_enum) if Self::has_attribute(&item_enum.attrs, attribute) => {
+                    Some(Self::parse_enum(item_enum, source))
                 }
-            }
-            i += 1;
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Parse a single enum definition
+    fn parse_enum(item_enum: &ItemEnum, source: &str) -> SorobanEnum {
+        let span = SourceSpan::between(
+            Self::start_span(&item_enum.vis, item_enum.enum_token.span),
+            item_enum.span(),
+        );
+
+        let variants = item_enum
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_span = SourceSpan::between(variant.ident.span(), variant.span());
+                let fields = match &variant.fields {
+                    Fields::Named(named) => named.named.iter().collect::<Vec<_>>(),
+                    Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect::<Vec<_>>(),
+                    Fields::Unit => Vec::new(),
+                };
+                SorobanVariant {
+                    name: variant.ident.to_string(),
+                    fields: fields.iter().map(|field| Self::type_text(&field.ty, source)).collect(),
+                    discriminant: variant.discriminant.as_ref().map(|(_, expr)| {
+                        Self::collapse_whitespace(SourceSpan::from_span(expr.span()).snippet(source))
+                    }),
+                    line_number: variant_span.start_line,
+                    span: variant_span,
+                }
+            })
+            .collect();
+
+        SorobanEnum {
+            name: item_enum.ident.to_string(),
+            variants,
+            line_number: span.start_line,
+            span,
+            raw_definition: span.snippet(source).to_string(),
         }
-        
-        let fields = Self::parse_struct_fields(&struct_lines, start_line)?;
-        
-        Ok(Some(SorobanStruct {
-            name,
-            fields,
-            line_number: start_line,
-            raw_definition: struct_lines.join("\n"),
-        }))
-    }
-    
-    /// Parse fields from a struct definition
-    fn parse_struct_fields(lines: &[&str], base_line: usize) -> SorobanResult<Vec<SorobanField>> {
-        let mut fields = Vec::new();
-        let full_content = lines.join(" ");
-        let fields_content = Self::extract_between_braces(&full_content)
-            .ok_or_else(|| SorobanParseError::ParseError("Could not extract struct fields".to_string()))?;
-        
-        let field_parts = Self::split_preserving_parentheses(&fields_content, ',');
-        
-        for (index, field_part) in field_parts.iter().enumerate() {
-            let field_part = field_part.trim();
-            if field_part.is_empty() {
-                continue;
-            }
-            if let Some(field) = Self::parse_field(field_part, base_line + index)? {
-                fields.push(field);
+    }
+
+    /// Parse event types with #[contractevent] macro
+    fn parse_events(items: &[&Item], source: &str) -> Vec<SorobanEvent> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(item_struct) => {
+                    let attr = Self::find_attribute(&item_struct.attrs, "contractevent")?;
+                    Some(Self::parse_event(item_struct, attr, source))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Parse a single event type
+    fn parse_event(item_struct: &ItemStruct, attr: &Attribute, source: &str) -> SorobanEvent {
+        let definition = Self::parse_struct(item_struct, source);
+
+        // `topics = ["transfer"]` replaces the default topic derived from the name
+        let mut prefix_topics = Vec::new();
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("topics") {
+                let topics: syn::ExprArray = meta.value()?.parse()?;
+                for topic in topics.elems {
+                    if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(topic), .. }) = topic {
+                        prefix_topics.push(topic.value());
+                    }
+                }
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
             }
+            Ok(())
+        });
+        if prefix_topics.is_empty() {
+            prefix_topics.push(Self::snake_case(&definition.name));
         }
-        
-        Ok(fields)
-    }
-    
-    /// Parse a single field definition
-    fn parse_field(field_str: &str, line_number: usize) -> SorobanResult<Option<SorobanField>> {
-        let field_str = field_str.trim();
-        if field_str.is_empty() {
-            return Ok(None);
+
+        let topic_fields = item_struct
+            .fields
+            .iter()
+            .zip(&definition.fields)
+            .filter(|(field, _)| Self::has_attribute(&field.attrs, "topic"))
+            .map(|(_, field)| field.name.clone())
+            .collect();
+
+        SorobanEvent {
+            name: definition.name,
+            prefix_topics,
+            fields: definition.fields,
+            topic_fields,
+            line_number: definition.line_number,
+            span: definition.span,
+            raw_definition: definition.raw_definition,
         }
-        
-        let (visibility, remaining) = if field_str.starts_with("pub ") {
-            (FieldVisibility::Public, &field_str[4..])
-        } else {
-            (FieldVisibility::Private, field_str)
-        };
-        
-        let parts: Vec<&str> = remaining.split(':').collect();
-        if parts.len() < 2 {
-            return Ok(None);
+    }
+
+    /// `TransferEvent` as `transfer_event`
+    fn snake_case(name: &str) -> String {
+        let mut snake = String::new();
+        for (index, c) in name.chars().enumerate() {
+            if c.is_uppercase() && index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
         }
-        
-        let name = parts[0].trim().to_string();
-        let type_name = parts[1..].join(":").trim().to_string();
-        
-        Ok(Some(SorobanField {
-            name,
-            type_name,
-            visibility,
-            line_number,
-        }))
+        snake
     }
-    
+
     /// Parse implementation blocks with #[contractimpl] macro
-    fn parse_implementations(lines: &[&str]) -> SorobanResult<Vec<SorobanImpl>> {
-        let mut implementations = Vec::new();
-        let mut i = 0;
-        
-        while i < lines.len() {
-            if lines[i].trim().starts_with("#[contractimpl]") {
-                let line_number = i + 1;
-                i += 1;
-                while i < lines.len() && !lines[i].trim().starts_with("impl") {
-                    i += 1;
+    fn parse_implementations(items: &[&Item], source: &str) -> Vec<SorobanImpl> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Impl(item_impl) if Self::has_attribute(&item_impl.attrs, "contractimpl") => {
+                    Self::parse_impl(item_impl, source)
                 }
-                
-                if i >= lines.len() {
-                    break;
-                }
-                
-                if let Some(implementation) = Self::parse_single_impl(&lines[i..], line_number)? {
-                    implementations.push(implementation);
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Parse impl blocks without #[contractimpl], such as methods of #[contracttype] structs
+    fn parse_helper_impls(items: &[&Item], source: &str) -> Vec<SorobanImpl> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Impl(item_impl) if !Self::has_attribute(&item_impl.attrs, "contractimpl") => {
+                    Self::parse_impl(item_impl, source)
                 }
-            }
-            i += 1;
-        }
-        
-        Ok(implementations)
+                _ => None,
+            })
+            .collect()
     }
-    
+
+    /// Parse free-standing functions
+    fn parse_free_functions(items: &[&Item], source: &str) -> Vec<SorobanFunction> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Fn(item_fn) => Some(Self::parse_fn(
+                    &item_fn.vis,
+                    &item_fn.sig,
+                    &item_fn.block,
+                    item_fn.span(),
+                    source,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Parse a single implementation block
-    fn parse_single_impl(lines: &[&str], start_line: usize) -> SorobanResult<Option<SorobanImpl>> {
-        if lines.is_empty() || !lines[0].trim().starts_with("impl") {
-            return Ok(None);
-        }
-        
-        let impl_line = lines[0].trim();
-        let target_re = Regex::new(r"impl\s+(?:.*?\s+for\s+)?(\w+)").unwrap();
-        let target = target_re.captures(impl_line)
-            .and_then(|caps| caps.get(1))
-            .map(|m| m.as_str().to_string())
-            .ok_or_else(|| SorobanParseError::ParseError(
-                format!("Could not parse impl target from: {}", impl_line)
-            ))?;
-        
-        let mut brace_count = 0;
-        let mut impl_lines = vec![impl_line];
-        let mut i = 1;
-        let mut functions = Vec::new();
-        
-        if impl_line.contains('{') {
-            brace_count += 1;
-        }
-        
-        while i < lines.len() {
-            let line = lines[i].trim();
-            impl_lines.push(line);
-            
-            if line.contains('{') && i > 0 {
-                brace_count += 1;
-            }
-            
-            if line.contains('}') {
-                brace_count -= 1;
-                if brace_count == 0 {
-                    break;
+    fn parse_impl(item_impl: &ItemImpl, source: &str) -> Option<SorobanImpl> {
+        // `impl Contract` and `impl Trait for Contract` both target `Contract`
+        let target = match item_impl.self_ty.as_ref() {
+            Type::Path(type_path) => type_path.path.segments.last()?.ident.to_string(),
+            _ => return None,
+        };
+
+        let start = item_impl
+            .unsafety
+            .map(|token| token.span)
+            .unwrap_or(item_impl.impl_token.span);
+        let span = SourceSpan::between(start, item_impl.span());
+
+        let functions = item_impl
+            .items
+            .iter()
+            .filter_map(|impl_item| match impl_item {
+                ImplItem::Fn(method) => Some(Self::parse_function(method, source)),
+                _ => None,
+            })
+            .collect();
+
+        Some(SorobanImpl {
+            target,
+            functions,
+            line_number: span.start_line,
+            span,
+            raw_definition: span.snippet(source).to_string(),
+        })
+    }
+
+    /// Parse `const` items, both free-standing and associated with any impl block
+    fn parse_constants(items: &[&Item], source: &str) -> Vec<SorobanConstant> {
+        let mut constants = Vec::new();
+        let mut push = |ident: &syn::Ident, ty: &Type, expr: &syn::Expr, span: SourceSpan| {
+            constants.push(SorobanConstant {
+                name: ident.to_string(),
+                type_name: Self::type_text(ty, source),
+                value: Self::collapse_whitespace(SourceSpan::from_span(expr.span()).snippet(source)),
+                span,
+            });
+        };
+
+        for item in items {
+            match item {
+                Item::Const(item_const) => {
+                    let start = Self::start_span(&item_const.vis, item_const.const_token.span);
+                    push(&item_const.ident, &item_const.ty, &item_const.expr, SourceSpan::between(start, item_const.span()));
                 }
-            }
-            
-            // Correct logic to identify functions inside impl block:
-            // We allow brace_count 2 IF the current line starts the function (contains '{')
-            // Otherwise brace_count must be 1 (direct child of impl)
-            let is_fn_def = line.starts_with("pub ") && line.contains("fn ");
-            let correct_depth = brace_count == 1 || (brace_count == 2 && line.contains('{'));
-
-            if is_fn_def && correct_depth {
-                if let Some(function) = Self::parse_function(&lines[i..], start_line + i)? {
-                    functions.push(function);
+                Item::Impl(item_impl) => {
+                    for impl_item in &item_impl.items {
+                        if let ImplItem::Const(item_const) = impl_item {
+                            let start = Self::start_span(&item_const.vis, item_const.const_token.span);
+                            push(&item_const.ident, &item_const.ty, &item_const.expr, SourceSpan::between(start, item_const.span()));
+                        }
+                    }
                 }
+                _ => {}
             }
-            
-            i += 1;
         }
-        
-        Ok(Some(SorobanImpl {
-            target,
-            functions,
-            line_number: start_line,
-            raw_definition: impl_lines.join("\n"),
-        }))
+        constants
     }
-    
+
     /// Parse a function definition
-    fn parse_function(lines: &[&str], start_line: usize) -> SorobanResult<Option<SorobanFunction>> {
-        if lines.is_empty() {
-            return Ok(None);
-        }
-        
-        let func_line = lines[0].trim();
-        if !func_line.starts_with("pub ") || !func_line.contains("fn ") {
-            return Ok(None);
-        }
-        
-        let name_re = Regex::new(r"fn\s+(\w+)").unwrap();
-        let name = name_re.captures(func_line)
-            .and_then(|caps| caps.get(1))
-            .map(|m| m.as_str().to_string())
-            .ok_or_else(|| SorobanParseError::ParseError(
-                format!("Could not parse function name from: {}", func_line)
-            ))?;
-        
-        let params = Self::extract_parameters(func_line).unwrap_or_default();
-        let return_type = Self::extract_return_type(func_line).unwrap_or(None);
-        let is_constructor = name == "new" || name.ends_with("_init");
-        
-        let mut brace_count = 0;
-        let mut func_lines = vec![func_line];
-        let mut i = 1;
-        
-        if func_line.contains('{') {
-            brace_count += 1;
-        }
-        
-        while i < lines.len() && brace_count > 0 {
-            let line = lines[i].trim();
-            func_lines.push(line);
-            
-            if line.contains('{') {
-                brace_count += 1;
-            }
-            if line.contains('}') {
-                brace_count -= 1;
-            }
-            i += 1;
-        }
-        
-        Ok(Some(SorobanFunction {
+    fn parse_function(method: &syn::ImplItemFn, source: &str) -> SorobanFunction {
+        Self::parse_fn(&method.vis, &method.sig, &method.block, method.span(), source)
+    }
+
+    /// Parse a function from its parts, shared by methods and free functions
+    fn parse_fn(
+        vis: &Visibility,
+        sig: &syn::Signature,
+        block: &syn::Block,
+        whole: proc_macro2::Span,
+        source: &str,
+    ) -> SorobanFunction {
+        let start = Self::start_span(vis, sig.span());
+        let span = SourceSpan::between(start, whole);
+        let signature_span = SourceSpan::between(start, sig.span());
+        let name = sig.ident.to_string();
+
+        let params = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                // `self` receivers are not contract parameters
+                FnArg::Receiver(_) => None,
+                FnArg::Typed(pat_type) => Some(SorobanParam {
+                    name: Self::collapse_whitespace(SourceSpan::from_span(pat_type.pat.span()).snippet(source)),
+                    type_name: Self::type_text(&pat_type.ty, source),
+                    span: SourceSpan::from_span(pat_type.span()),
+                }),
+            })
+            .collect();
+
+        let return_type = match &sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some(Self::type_text(ty, source)),
+        };
+
+        let is_constructor = name == "new" || name == "__constructor" || name.ends_with("_init");
+
+        SorobanFunction {
             name,
             params,
             return_type,
-            visibility: FunctionVisibility::Public,
+            visibility: match vis {
+                Visibility::Public(_) => FunctionVisibility::Public,
+                _ => FunctionVisibility::Private,
+            },
             is_constructor,
-            line_number: start_line,
-            raw_definition: func_lines.join("\n"),
-        }))
-    }
-    
-    /// Extract function parameters
-    fn extract_parameters(func_signature: &str) -> SorobanResult<Vec<SorobanParam>> {
-        let params_section = Self::extract_between_parentheses(func_signature)
-            .ok_or_else(|| SorobanParseError::ParseError("Could not extract parameters".to_string()))?;
-        
-        let mut params = Vec::new();
-        let param_parts = Self::split_preserving_parentheses(&params_section, ',');
-        
-        for param_part in param_parts {
-            let param_part = param_part.trim();
-            if param_part.is_empty() {
-                continue;
-            }
-            
-            let parts: Vec<&str> = param_part.split(':').collect();
-            if parts.len() >= 2 {
-                let name = parts[0].trim().to_string();
-                let type_name = parts[1..].join(":").trim().to_string();
-                params.push(SorobanParam { name, type_name });
-            }
-        }
-        
-        Ok(params)
-    }
-    
-    /// Extract return type from function signature
-    fn extract_return_type(func_signature: &str) -> SorobanResult<Option<String>> {
-        let return_re = Regex::new(r"->\s*([^{\n]+)").unwrap();
-        
-        if let Some(captures) = return_re.captures(func_signature) {
-            if let Some(return_type) = captures.get(1) {
-                let clean_type = return_type.as_str().trim().to_string();
-                if !clean_type.is_empty() {
-                    return Ok(Some(clean_type));
-                }
-            }
-        }
-        
-        Ok(None)
-    }
-    
-    /// Helper function to extract content between parentheses
-    fn extract_between_parentheses(text: &str) -> Option<String> {
-        let start = text.find('(')?;
-        let mut paren_count = 1;
-        let mut end = start + 1;
-        
-        while end < text.len() && paren_count > 0 {
-            match text.chars().nth(end).unwrap() {
-                '(' => paren_count += 1,
-                ')' => paren_count -= 1,
-                _ => {}
-            }
-            if paren_count > 0 {
-                end += 1;
-            }
-        }
-        
-        if paren_count == 0 {
-            Some(text[start + 1..end].to_string())
-        } else {
-            None
+            line_number: span.start_line,
+            span,
+            signature_span,
+            body: FunctionBody::from_fn(sig, block, source),
+            raw_definition: span.snippet(source).to_string(),
         }
     }
-    
-    /// Helper function to extract content between braces
-    fn extract_between_braces(text: &str) -> Option<String> {
-        let start = text.find('{')?;
-        let mut brace_count = 1;
-        let mut end = start + 1;
-        
-        while end < text.len() && brace_count > 0 {
-            match text.chars().nth(end).unwrap() {
-                '{' => brace_count += 1,
-                '}' => brace_count -= 1,
-                _ => {}
-            }
-            if brace_count > 0 {
-                end += 1;
-            }
-        }
-        
-        if brace_count == 0 {
-            Some(text[start + 1..end].to_string())
-        } else {
-            None
-        }
+
+    /// Whether any attribute's final path segment matches `name`
+    fn has_attribute(attrs: &[Attribute], name: &str) -> bool {
+        Self::find_attribute(attrs, name).is_some()
     }
-    
-    /// Split string by delimiter while preserving parentheses nesting
-    fn split_preserving_parentheses(text: &str, delimiter: char) -> Vec<String> {
-        let mut result = Vec::new();
-        let mut current = String::new();
-        let mut paren_count = 0;
-        let mut bracket_count = 0;
-        let mut brace_count = 0;
-        
-        for ch in text.chars() {
-            match ch {
-                '(' => paren_count += 1,
-                ')' => paren_count -= 1,
-                '[' => bracket_count += 1,
-                ']' => bracket_count -= 1,
-                '{' => brace_count += 1,
-                '}' => brace_count -= 1,
-                _ => {}
-            }
-            
-            if ch == delimiter && paren_count == 0 && bracket_count == 0 && brace_count == 0 {
-                result.push(current.trim().to_string());
-                current = String::new();
-            } else {
-                current.push(ch);
-            }
-        }
-        
-        if !current.trim().is_empty() {
-            result.push(current.trim().to_string());
+
+    /// Find an attribute by its final path segment, so `#[soroban_sdk::contractimpl]`
+    /// and `#[contractimpl(contracttrait)]` both match `contractimpl`
+    fn find_attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+        attrs.iter().find(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == name)
+        })
+    }
+
+    /// Span of a struct definition, excluding its attributes
+    fn struct_span(item_struct: &ItemStruct) -> SourceSpan {
+        SourceSpan::between(
+            Self::start_span(&item_struct.vis, item_struct.struct_token.span),
+            item_struct.span(),
+        )
+    }
+
+    /// Span of the first token of an item once its attributes are skipped
+    fn start_span(vis: &Visibility, fallback: proc_macro2::Span) -> proc_macro2::Span {
+        match vis {
+            Visibility::Inherited => fallback,
+            vis => vis.span(),
         }
-        
-        result
+    }
+
+    /// Source text of a type, as written but on a single line
+    fn type_text(ty: &Type, source: &str) -> String {
+        Self::collapse_whitespace(SourceSpan::from_span(ty.span()).snippet(source))
+    }
+
+    /// Collapse runs of whitespace (including newlines) into single spaces
+    pub(crate) fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_parse_simple_contract() {
         let source = r#"
@@ -473,26 +564,150 @@ impl TokenContract {
             total_supply: supply,
         }
     }
-    
+
     pub fn get_total_supply(&self) -> u64 {
         self.total_supply
     }
 }
 "#;
-        
+
         let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
-        
+
         assert_eq!(contract.contract_types.len(), 1);
         assert_eq!(contract.implementations.len(), 1);
-        
+
         let struct_def = &contract.contract_types[0];
         assert_eq!(struct_def.name, "TokenContract");
         assert_eq!(struct_def.fields.len(), 2);
-        
+
         let impl_block = &contract.implementations[0];
         // This assertion failed previously because brace counting was off
         assert_eq!(impl_block.functions.len(), 2);
         assert_eq!(impl_block.functions[0].name, "new");
         assert_eq!(impl_block.functions[1].name, "get_total_supply");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_formatting_that_broke_line_parser() {
+        let source = r#"
+use soroban_sdk::{contract, contractimpl, Env, Map, String};
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl(contracttrait)]
+impl Registry {
+    pub fn describe(env: Env) -> String {
+        String::from_str(&env, "{ not a block }")
+    }
+
+    pub fn lookup<K: Clone>(
+        env: Env,
+        entries: Map<K, u64>,
+        key: K,
+    ) -> Option<u64> {
+        entries.get(key)
+    }
+
+    pub(crate) fn helper(env: &Env) {}
+}
+"#;
+
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        assert_eq!(contract.name, "Registry");
+        assert_eq!(contract.implementations.len(), 1);
+
+        let functions = &contract.implementations[0].functions;
+        let names: Vec<_> = functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["describe", "lookup", "helper"]);
+
+        let lookup = &functions[1];
+        assert_eq!(lookup.params.len(), 3);
+        assert_eq!(lookup.params[1].type_name, "Map<K, u64>");
+        assert_eq!(lookup.return_type, Some("Option<u64>".to_string()));
+        assert_eq!(lookup.visibility, FunctionVisibility::Public);
+        assert_eq!(functions[2].visibility, FunctionVisibility::Private);
+    }
+
+    #[test]
+    fn test_item_spans() {
+        let source = "#[contracttype]\npub struct Config {\n    pub admin: Address,\n}\n\n#[contractimpl]\nimpl Config {\n    /// Docs are not part of the span\n    pub fn admin(env: Env) -> Address {\n        todo!()\n    }\n}\n";
+
+        let contract = SorobanParser::parse_contract(source, "config.rs").unwrap();
+
+        let config = &contract.contract_types[0];
+        assert_eq!(config.span.start_line, 2);
+        assert_eq!(config.span.end_line, 4);
+        assert!(config.raw_definition.starts_with("pub struct Config"));
+
+        let field = &config.fields[0];
+        assert_eq!((field.span.start_line, field.span.start_column), (3, 5));
+        assert_eq!(field.span.snippet(source), "pub admin: Address");
+
+        let function = &contract.implementations[0].functions[0];
+        assert_eq!(function.line_number, 9);
+        assert_eq!((function.span.start_line, function.span.end_line), (9, 11));
+        assert!(function.raw_definition.starts_with("pub fn admin(env: Env)"));
+        assert!(function.raw_definition.ends_with('}'));
+        assert_eq!(function.params[0].span.snippet(source), "env: Env");
+    }
+
+    #[test]
+    fn test_parse_enums_errors_and_events() {
+        let source = r#"
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Allowance(Address, Address),
+}
+
+#[contracterror]
+#[derive(Copy, Clone)]
+#[repr(u32)]
+pub enum Error {
+    NotFound = 1,
+    Unauthorized = 2,
+}
+
+#[contractevent(topics = ["xfer"], data_format = "map")]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct AdminChanged {
+    pub admin: Address,
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "token.rs").unwrap();
+
+        assert_eq!(contract.enums.len(), 1);
+        let allowance = contract.enums[0].variant("Allowance").unwrap();
+        assert_eq!(allowance.fields, vec!["Address", "Address"]);
+
+        let error = &contract.errors[0];
+        assert_eq!(error.name, "Error");
+        assert_eq!(error.variants[1].discriminant.as_deref(), Some("2"));
+        assert_eq!(error.variants[1].span.snippet(source), "Unauthorized = 2");
+
+        let transfer = &contract.events[0];
+        assert_eq!(transfer.prefix_topics, vec!["xfer"]);
+        assert_eq!(transfer.topic_fields, vec!["from", "to"]);
+        assert_eq!(transfer.fields.len(), 3);
+        assert_eq!(contract.events[1].prefix_topics, vec!["admin_changed"]);
+    }
+
+    #[test]
+    fn test_invalid_source_reports_location() {
+        let result = SorobanParser::parse_contract("#[contractimpl]\nimpl Broken {\n    pub fn f( {\n}\n", "broken.rs");
+
+        match result {
+            Err(SorobanParseError::ParseError(msg)) => assert!(msg.contains("line")),
+            other => panic!("Expected ParseError, got {:?}", other.map(|c| c.name)),
+        }
+    }
+}
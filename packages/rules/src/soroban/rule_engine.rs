@@ -2,153 +2,155 @@
 //!
 //! This module provides a specialized rule engine for analyzing Soroban smart contracts.
 
-use crate::soroban::{SorobanAnalyzer, SorobanContract, SorobanParser, SorobanResult};
-use crate::{RuleViolation, ViolationSeverity};
-use std::collections::HashMap;
+use crate::soroban::{
+    base_type, is_unbounded, root_name, value_type, publishes_event, tier_span, AuthFlow, AuthSubject, Call, CallGraph, CallKind, Effect, EffectKind, ErrorVariant, EventEmission, ExternalCall, FieldVisibility, KeyKind, LoopAnalysis, RedundancyKind, RedundantAccess, LoopBound, PanicKind, PanicSite, SorobanContract, SorobanFunction, SorobanParser, SorobanResult,
+    StorageAccess, StorageInventory, StorageKey, StorageOperation, StorageTier, TtlCoverage,
+};
+use std::collections::{HashMap, HashSet};
+use crate::{
+    Applicability, Fix, Language, Rule, RuleCategory, RuleInput, RuleMetadata, RuleRegistry, RuleViolation,
+    SourceSpan, SuppressionReport, Suppressions, TextEdit, ViolationSeverity,
+};
 
 /// Soroban-specific rule engine
 pub struct SorobanRuleEngine {
-    /// Active rules in the engine
-    rules: HashMap<String, Box<dyn SorobanRule>>,
-    /// Whether to enable all rules by default
-    enable_all_by_default: bool,
+    /// Rules run by the engine
+    registry: RuleRegistry,
 }
 
 impl SorobanRuleEngine {
     /// Create a new Soroban rule engine with default rules
     pub fn with_default_rules() -> Self {
         let mut engine = Self::new();
-        engine.add_default_rules();
+        for rule in Self::default_rules() {
+            engine.registry.register(rule);
+        }
         engine
     }
     
     /// Create a new empty Soroban rule engine
     pub fn new() -> Self {
         Self {
-            rules: HashMap::new(),
-            enable_all_by_default: true,
+            registry: RuleRegistry::new(),
         }
     }
     
     /// Add a rule to the engine
-    pub fn add_rule<R: SorobanRule + 'static>(&mut self, rule: R) -> &mut Self {
-        self.rules.insert(rule.id().to_string(), Box::new(rule));
+    pub fn add_rule<R: Rule + 'static>(&mut self, rule: R) -> &mut Self {
+        self.registry.register(Box::new(rule));
         self
     }
     
-    /// Add all default Soroban rules
-    fn add_default_rules(&mut self) {
-        self.add_rule(UnusedStateVariablesRule::default())
-            .add_rule(InefficientStorageAccessRule::default())
-            .add_rule(UnboundedLoopRule::default())
-            .add_rule(ExpensiveStringOperationsRule::default())
-            .add_rule(MissingConstructorRule::default())
-            .add_rule(AdminPatternRule::default())
-            .add_rule(InefficientIntegerTypesRule::default())
-            .add_rule(MissingErrorHandlingRule::default())
-            .add_rule(EmergencyWithdrawalRule::default())
-            .add_rule(GovernanceVotingRule::default())
-            .add_rule(ClaimExpirationRule::default())    // #117
-            .add_rule(AntiFrontRunningRule::default())   // #118
-            .add_rule(SecureRandomnessRule::default())   // #119
-            .add_rule(UpgradeVersionTrackingRule::default()); // #123
+    /// The built-in Soroban rules
+    pub fn default_rules() -> Vec<Box<dyn Rule>> {
+        vec![
+            Box::new(UnusedStateVariablesRule),
+            Box::new(InefficientStorageAccessRule),
+            Box::new(UnboundedLoopRule),
+            Box::new(ExpensiveStringOperationsRule),
+            Box::new(MissingConstructorRule),
+            Box::new(AdminPatternRule),
+            Box::new(InefficientIntegerTypesRule),
+            Box::new(StringFieldRule),
+            Box::new(PrivateContractFieldRule),
+            Box::new(VecWithoutCapacityRule),
+            Box::new(MissingAddressValidationRule),
+            Box::new(MissingErrorHandlingRule),
+            Box::new(EmergencyWithdrawalRule),
+            Box::new(GovernanceVotingRule),
+            Box::new(ClaimExpirationRule),        // #117
+            Box::new(AntiFrontRunningRule),       // #118
+            Box::new(SecureRandomnessRule),       // #119
+            Box::new(UpgradeVersionTrackingRule), // #123
+            Box::new(RedundantCloneRule),
+            Box::new(InstanceStorageGrowthRule),
+            Box::new(PersistentShortLivedDataRule),
+            Box::new(StorageTierMismatchRule),
+            Box::new(MissingTtlExtensionRule),
+            Box::new(WastefulTtlExtensionRule::default()),
+            Box::new(DuplicateErrorCodeRule),
+            Box::new(UnusedErrorVariantRule),
+            Box::new(StorageKeyNeverWrittenRule),
+            Box::new(MissingAuthRule),
+            Box::new(AuthAfterEffectRule),
+            Box::new(UncheckedExternalCallRule),
+            Box::new(StateWriteAfterExternalCallRule),
+            Box::new(TransferFromUnauthenticatedRule),
+            Box::new(ExternalCallInLoopRule),
+            Box::new(AdminMissingEventRule),
+            Box::new(EventInLoopRule),
+            Box::new(OversizedEventPayloadRule),
+            Box::new(InconsistentEventTopicsRule),
+            Box::new(VecIndexInLoopRule),
+            Box::new(StoredMapRule),
+            Box::new(BytesStringRoundTripRule),
+            Box::new(AddressVecAcrossContractsRule),
+        ]
     }
     
     /// Analyze Soroban contract source code
     pub fn analyze(&self, source: &str, file_path: &str) -> SorobanResult<Vec<RuleViolation>> {
+        self.analyze_with_suppressions(source, file_path).map(|report| report.violations)
+    }
+    
+    /// Analyze Soroban contract source code, honouring `// gasguard-ignore`
+    /// comments and keeping the suppressed findings separately
+    pub fn analyze_with_suppressions(&self, source: &str, file_path: &str) -> SorobanResult<SuppressionReport> {
         // Parse the contract
         let contract = SorobanParser::parse_contract(source, file_path)?;
         
-        // Run analysis
-        let violations = SorobanAnalyzer::analyze_contract(&contract);
-        
         // Apply active rules
-        let mut all_violations = violations;
-        for rule in self.rules.values() {
-            if rule.is_enabled() {
-                all_violations.extend(rule.apply(&contract));
-            }
-        }
+        let violations = self.registry.check(&RuleInput::Soroban(&contract));
         
-        Ok(all_violations)
+        Ok(Suppressions::parse(source, Language::Soroban.comment_prefix()).apply(violations))
     }
     
     /// Get all registered rules
-    pub fn get_rules(&self) -> Vec<&dyn SorobanRule> {
-        self.rules.values().map(|r| r.as_ref()).collect()
+    pub fn get_rules(&self) -> Vec<&dyn Rule> {
+        self.registry.rules().collect()
     }
     
     /// Enable or disable a specific rule
     pub fn set_rule_enabled(&mut self, rule_id: &str, enabled: bool) {
-        if let Some(rule) = self.rules.get_mut(rule_id) {
-            rule.set_enabled(enabled);
-        }
+        self.registry.set_enabled(rule_id, enabled);
+    }
+    
+    /// Set a named threshold of a specific rule, returning whether the rule accepted it
+    pub fn set_rule_threshold(&mut self, rule_id: &str, name: &str, value: u64) -> bool {
+        self.registry.set_threshold(rule_id, name, value)
     }
 }
 
-/// Trait for Soroban-specific rules
-pub trait SorobanRule: Send + Sync {
-    /// Unique identifier for the rule
-    fn id(&self) -> &str;
-    
-    /// Human-readable name of the rule
-    fn name(&self) -> &str;
-    
-    /// Description of what the rule checks for
-    fn description(&self) -> &str;
-    
-    /// Severity level of violations from this rule
-    fn severity(&self) -> ViolationSeverity;
-    
-    /// Whether this rule is currently enabled
-    fn is_enabled(&self) -> bool;
-    
-    /// Enable or disable the rule
-    fn set_enabled(&mut self, enabled: bool);
-    
-    /// Apply the rule to a parsed Soroban contract
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation>;
+impl Default for SorobanRuleEngine {
+    fn default() -> Self {
+        Self::with_default_rules()
+    }
 }
 
 // --- Specific Rule Implementations ---
 
 /// Rule for detecting unused state variables
-pub struct UnusedStateVariablesRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct UnusedStateVariablesRule;
 
-impl Default for UnusedStateVariablesRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for UnusedStateVariablesRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-unused-state-variables",
+            name: "Unused State Variables",
+            description: "Detects state variables that are declared but never used",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["storage"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for UnusedStateVariablesRule {
-    fn id(&self) -> &str {
-        "soroban-unused-state-variables"
-    }
-    
-    fn name(&self) -> &str {
-        "Unused State Variables"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects state variables that are declared but never used"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Warning
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for contract_type in &contract.contract_types {
@@ -156,84 +158,132 @@ impl SorobanRule for UnusedStateVariablesRule {
                 // Simple heuristic: Definition + Initialization = 2 occurrences.
                 let occurrences = contract.source.matches(&field.name).count();
                 if occurrences <= 2 {
+                    // Removing a field changes the type's XDR layout, so entries
+                    // already stored with it no longer decode; never apply it unreviewed
+                    let fix = Fix::new(
+                        format!("Remove field '{}'", field.name),
+                        Applicability::MaybeIncorrect,
+                        vec![TextEdit::delete(Self::field_with_comma(field.span, &contract.source))],
+                    );
                     violations.push(RuleViolation {
                         rule_name: self.id().to_string(),
                         description: format!("State variable '{}' appears to be unused", field.name),
                         suggestion: format!("Remove unused state variable '{}' to save ledger storage costs", field.name),
-                        line_number: field.line_number,
-                        column_number: 0,
+                        line_number: field.span.start_line,
+                        column_number: field.span.start_column,
+                        span: field.span,
                         variable_name: field.name.clone(),
                         severity: self.severity(),
+                        fix: Some(fix),
                     });
                 }
             }
         }
+
+        // Storage key enums declare one variant per kind of entry; a variant no code
+        // ever names is an entry the contract never touches
+        let inventory = StorageInventory::build(contract);
+        for key_enum in &contract.enums {
+            let prefix = format!("{}::", key_enum.name);
+            if !inventory.keys.iter().any(|key| key.kind == KeyKind::Variant && key.key.starts_with(&prefix)) {
+                continue;
+            }
+            for variant in &key_enum.variants {
+                let path = format!("{}{}", prefix, variant.name);
+                if names_path(&contract.source, &path) {
+                    continue;
+                }
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("Storage key '{}' is declared but never used", path),
+                    suggestion: format!("Remove unused storage key variant '{}'", variant.name),
+                    line_number: variant.span.start_line,
+                    column_number: variant.span.start_column,
+                    span: variant.span,
+                    variable_name: path.clone(),
+                    severity: self.severity(),
+                    fix: Some(Fix::new(
+                        format!("Remove variant '{}'", variant.name),
+                        Applicability::MaybeIncorrect,
+                        vec![TextEdit::delete(Self::field_with_comma(variant.span, &contract.source))],
+                    )),
+                });
+            }
+        }
         
         violations
     }
 }
 
-/// Rule for detecting inefficient storage access patterns
-pub struct InefficientStorageAccessRule {
-    enabled: bool,
-}
-
-impl Default for InefficientStorageAccessRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl UnusedStateVariablesRule {
+    /// Extend a field's or variant's span over the comma separating it from the next one
+    fn field_with_comma(span: SourceSpan, source: &str) -> SourceSpan {
+        let rest = &source[span.end_byte..];
+        match rest.trim_start().strip_prefix(',') {
+            Some(after) => SourceSpan::from_byte_range(source, span.start_byte, source.len() - after.len()),
+            None => span,
+        }
     }
 }
 
-impl SorobanRule for InefficientStorageAccessRule {
-    fn id(&self) -> &str {
-        "soroban-inefficient-storage"
-    }
-    
-    fn name(&self) -> &str {
-        "Inefficient Storage Access"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects multiple reads/writes to the same storage key without caching"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Medium
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+/// Rule for detecting inefficient storage access patterns
+#[derive(Default)]
+pub struct InefficientStorageAccessRule;
+
+impl Rule for InefficientStorageAccessRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-inefficient-storage",
+            name: "Inefficient Storage Access",
+            description: "Detects storage keys read again with no write in between or written again before being read",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["storage"],
+        };
+        &METADATA
     }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let graph = CallGraph::build(contract);
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
             for function in &implementation.functions {
-                let func_source = &function.raw_definition;
-                
-                // Count storage operations
-                let get_count = func_source.matches(".get(").count();
-                let set_count = func_source.matches(".set(").count();
-                let load_count = func_source.matches(".load(").count();
-                let store_count = func_source.matches(".store(").count();
-                
-                let total_ops = get_count + set_count + load_count + store_count;
-                
-                // If there are many storage operations, flag for review
-                if total_ops > 3 {
+                for redundant in RedundantAccess::find(&graph, function) {
+                    let (first, second) = (redundant.first.span.start_line, redundant.second.span.start_line);
+                    let tier = redundant.first.tier.method();
+                    let (description, suggestion, span) = match redundant.kind {
+                        RedundancyKind::RepeatedRead => (
+                            format!(
+                                "Function '{}' reads {} key '{}' again on line {} with no write since line {}",
+                                function.name, tier, redundant.key.expression, second, first
+                            ),
+                            "Read the value once into a local and reuse it".to_string(),
+                            redundant.second.span,
+                        ),
+                        RedundancyKind::OverwrittenWrite => (
+                            format!(
+                                "Function '{}' writes {} key '{}' on line {} and overwrites it on line {} before it is read",
+                                function.name, tier, redundant.key.expression, first, second
+                            ),
+                            "Build the final value in a local and write the key once".to_string(),
+                            redundant.first.span,
+                        ),
+                    };
                     violations.push(RuleViolation {
                         rule_name: self.id().to_string(),
-                        description: format!("Function '{}' performs {} storage operations - consider caching", function.name, total_ops),
-                        suggestion: "Cache frequently accessed storage values in local variables to reduce ledger interactions".to_string(),
-                        line_number: function.line_number,
-                        column_number: 0,
-                        variable_name: function.name.clone(),
+                        description,
+                        suggestion,
+                        line_number: span.start_line,
+                        column_number: span.start_column,
+                        span,
+                        variable_name: redundant.key.name.clone(),
                         severity: self.severity(),
+                        fix: redundant.fix(&contract.source),
                     });
                 }
             }
@@ -244,128 +294,145 @@ impl SorobanRule for InefficientStorageAccessRule {
 }
 
 /// Rule for detecting unbounded loops
-pub struct UnboundedLoopRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct UnboundedLoopRule;
 
-impl Default for UnboundedLoopRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for UnboundedLoopRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-unbounded-loop",
+            name: "Unbounded Loop Detection",
+            description: "Detects loops bounded by caller-supplied parameters, storage-derived lengths or nothing at all, rating them higher when each iteration touches storage or calls another contract",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["loops", "denial-of-service"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for UnboundedLoopRule {
-    fn id(&self) -> &str {
-        "soroban-unbounded-loop"
-    }
-    
-    fn name(&self) -> &str {
-        "Unbounded Loop Detection"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects loops without clear termination conditions that could exhaust CPU limits"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::High
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let graph = CallGraph::build(contract);
         let mut violations = Vec::new();
-        
-        for implementation in &contract.implementations {
-            for function in &implementation.functions {
-                let func_source = &function.raw_definition;
-                
-                // Look for potentially unbounded loops
-                if (func_source.contains("loop {") || 
-                    func_source.contains("while ") || 
-                    func_source.contains("for ")) &&
-                   !(func_source.contains(".len()") || 
-                     func_source.contains("range(") || 
-                     func_source.contains("..")) {
-                    
-                    violations.push(RuleViolation {
-                        rule_name: self.id().to_string(),
-                        description: format!("Function '{}' contains potentially unbounded loop", function.name),
-                        suggestion: "Ensure loops have clear termination conditions to prevent CPU limit exhaustion".to_string(),
-                        line_number: function.line_number,
-                        column_number: 0,
-                        variable_name: function.name.clone(),
-                        severity: self.severity(),
-                    });
-                }
+
+        for function in contract.functions() {
+            for analysis in LoopAnalysis::analyze(contract, &graph, function) {
+                // Storage-derived bounds grow with contract state rather than with one call
+                let severity = match &analysis.bound {
+                    LoopBound::Parameter(_) | LoopBound::Unbounded => self.severity(),
+                    LoopBound::Storage(_) => Self::lower(self.severity()),
+                    _ => continue,
+                };
+                let severity = if analysis.has_expensive_body() { Self::raise(severity) } else { severity };
+
+                let description = match &analysis.bound {
+                    LoopBound::Unbounded => format!("Function '{}' contains potentially unbounded loop", function.name),
+                    bound => format!("Function '{}' contains a loop bounded by {}", function.name, bound.describe()),
+                };
+                let cost = match (analysis.touches_storage, analysis.calls_contract) {
+                    (true, true) => " that accesses storage and calls another contract on every iteration",
+                    (true, false) => " that accesses storage on every iteration",
+                    (false, true) => " that calls another contract on every iteration",
+                    (false, false) => "",
+                };
+                let suggestion = match &analysis.bound {
+                    LoopBound::Parameter(name) => format!(
+                        "Cap the iteration count with a contract constant, e.g. `{}.min(MAX_BATCH)`, or reject larger inputs, so a caller cannot exhaust the CPU budget",
+                        name
+                    ),
+                    LoopBound::Storage(_) => "Process the stored collection in pages, with a start index and a constant page size".to_string(),
+                    _ => "Ensure loops have clear termination conditions to prevent CPU limit exhaustion".to_string(),
+                };
+
+                let span = analysis.lp.header_span;
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("{}{}", description, cost),
+                    suggestion,
+                    line_number: span.start_line,
+                    column_number: span.start_column,
+                    span,
+                    variable_name: function.name.clone(),
+                    severity,
+                    fix: None,
+                });
             }
         }
-        
+
         violations
     }
 }
 
-/// Rule for detecting expensive string operations
-pub struct ExpensiveStringOperationsRule {
-    enabled: bool,
-}
+impl UnboundedLoopRule {
+    fn raise(severity: ViolationSeverity) -> ViolationSeverity {
+        match severity {
+            ViolationSeverity::Info => ViolationSeverity::Warning,
+            ViolationSeverity::Warning => ViolationSeverity::Medium,
+            ViolationSeverity::Medium => ViolationSeverity::High,
+            ViolationSeverity::High | ViolationSeverity::Error => ViolationSeverity::Error,
+        }
+    }
 
-impl Default for ExpensiveStringOperationsRule {
-    fn default() -> Self {
-        Self { enabled: true }
+    fn lower(severity: ViolationSeverity) -> ViolationSeverity {
+        match severity {
+            ViolationSeverity::Error => ViolationSeverity::High,
+            ViolationSeverity::High => ViolationSeverity::Medium,
+            ViolationSeverity::Medium => ViolationSeverity::Warning,
+            ViolationSeverity::Warning | ViolationSeverity::Info => ViolationSeverity::Info,
+        }
     }
 }
 
-impl SorobanRule for ExpensiveStringOperationsRule {
-    fn id(&self) -> &str {
-        "soroban-expensive-strings"
-    }
-    
-    fn name(&self) -> &str {
-        "Expensive String Operations"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects expensive string operations that increase gas/storage costs"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Medium
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+/// Rule for detecting expensive string operations
+#[derive(Default)]
+pub struct ExpensiveStringOperationsRule;
+
+impl Rule for ExpensiveStringOperationsRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-expensive-strings",
+            name: "Expensive String Operations",
+            description: "Detects expensive string operations that increase gas/storage costs",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["strings"],
+        };
+        &METADATA
     }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
             for function in &implementation.functions {
-                let func_source = &function.raw_definition;
+                let string_op = function.body.calls
+                    .iter()
+                    .filter(|call| match call.kind {
+                        CallKind::Method => call.name == "to_string",
+                        CallKind::Function => call.path.ends_with("String::from"),
+                        CallKind::Macro => call.name == "format",
+                    })
+                    .min_by_key(|call| call.call_span.start_byte);
                 
-                if func_source.contains(".to_string()") || 
-                   func_source.contains("String::from(") ||
-                   func_source.contains("format!(") {
-                    
+                if let Some(call) = string_op {
+                    let span = call.call_span;
                     violations.push(RuleViolation {
                         rule_name: self.id().to_string(),
                         description: format!("Function '{}' uses expensive string operations", function.name),
                         suggestion: "Consider using Symbol or Bytes for fixed data, or minimize string operations to reduce gas costs".to_string(),
-                        line_number: function.line_number,
-                        column_number: 0,
+                        line_number: span.start_line,
+                        column_number: span.start_column,
+                        span,
                         variable_name: function.name.clone(),
                         severity: self.severity(),
+                        fix: None,
                     });
                 }
             }
@@ -376,42 +443,27 @@ impl SorobanRule for ExpensiveStringOperationsRule {
 }
 
 /// Rule for detecting missing constructors
-pub struct MissingConstructorRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct MissingConstructorRule;
 
-impl Default for MissingConstructorRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for MissingConstructorRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-missing-constructor",
+            name: "Missing Constructor",
+            description: "Detects contracts without constructor functions for initialization",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["initialization"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for MissingConstructorRule {
-    fn id(&self) -> &str {
-        "soroban-missing-constructor"
-    }
-    
-    fn name(&self) -> &str {
-        "Missing Constructor"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects contracts without constructor functions for initialization"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Warning
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let has_constructor = contract.implementations.iter().any(|imp| {
             imp.functions.iter().any(|f| f.is_constructor)
         });
@@ -421,10 +473,12 @@ impl SorobanRule for MissingConstructorRule {
                 rule_name: self.id().to_string(),
                 description: "Contract lacks a constructor function for initialization".to_string(),
                 suggestion: "Add a 'new' function that initializes the contract state properly".to_string(),
-                line_number: 1,
-                column_number: 0,
+                line_number: contract.span.start_line,
+                column_number: contract.span.start_column,
+                span: contract.span,
                 variable_name: contract.name.clone(),
                 severity: self.severity(),
+                fix: None,
             }]
         } else {
             Vec::new()
@@ -433,42 +487,27 @@ impl SorobanRule for MissingConstructorRule {
 }
 
 /// Rule for suggesting admin pattern
-pub struct AdminPatternRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct AdminPatternRule;
 
-impl Default for AdminPatternRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for AdminPatternRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-admin-pattern",
+            name: "Admin Pattern Suggestion",
+            description: "Suggests adding admin/owner pattern for access control",
+            default_severity: ViolationSeverity::Info,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["access-control"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for AdminPatternRule {
-    fn id(&self) -> &str {
-        "soroban-admin-pattern"
-    }
-    
-    fn name(&self) -> &str {
-        "Admin Pattern Suggestion"
-    }
-    
-    fn description(&self) -> &str {
-        "Suggests adding admin/owner pattern for access control"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Info
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let has_admin = contract.contract_types.iter().any(|ct| {
             ct.fields.iter().any(|f| 
                 f.name.contains("admin") || 
@@ -482,10 +521,12 @@ impl SorobanRule for AdminPatternRule {
                 rule_name: self.id().to_string(),
                 description: "Consider adding an admin/owner field for access control".to_string(),
                 suggestion: "Add an 'admin: Address' field to your contract state for administrative functions".to_string(),
-                line_number: 1,
-                column_number: 0,
+                line_number: contract.span.start_line,
+                column_number: contract.span.start_column,
+                span: contract.span,
                 variable_name: contract.name.clone(),
                 severity: self.severity(),
+                fix: None,
             }]
         } else {
             Vec::new()
@@ -494,42 +535,27 @@ impl SorobanRule for AdminPatternRule {
 }
 
 /// Rule for detecting inefficient integer types
-pub struct InefficientIntegerTypesRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct InefficientIntegerTypesRule;
 
-impl Default for InefficientIntegerTypesRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for InefficientIntegerTypesRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-inefficient-integers",
+            name: "Inefficient Integer Types",
+            description: "Detects use of unnecessarily large integer types",
+            default_severity: ViolationSeverity::Info,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["types"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for InefficientIntegerTypesRule {
-    fn id(&self) -> &str {
-        "soroban-inefficient-integers"
-    }
-    
-    fn name(&self) -> &str {
-        "Inefficient Integer Types"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects use of unnecessarily large integer types"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Info
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for contract_type in &contract.contract_types {
@@ -539,10 +565,12 @@ impl SorobanRule for InefficientIntegerTypesRule {
                         rule_name: self.id().to_string(),
                         description: format!("Field '{}' uses {} which may be unnecessarily large", field.name, field.type_name),
                         suggestion: "Consider using a smaller integer type like u64 or u32 if the range permits".to_string(),
-                        line_number: field.line_number,
-                        column_number: 0,
+                        line_number: field.span.start_line,
+                        column_number: field.span.start_column,
+                        span: field.span,
                         variable_name: field.name.clone(),
                         severity: self.severity(),
+                        fix: None,
                     });
                 }
             }
@@ -552,43 +580,212 @@ impl SorobanRule for InefficientIntegerTypesRule {
     }
 }
 
-/// Rule for detecting missing error handling
-pub struct MissingErrorHandlingRule {
-    enabled: bool,
-}
+/// Rule for detecting `String` fields in contract types
+#[derive(Default)]
+pub struct StringFieldRule;
 
-impl Default for MissingErrorHandlingRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for StringFieldRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-string-field",
+            name: "String Field",
+            description: "Detects String fields in contract types that could hold a Symbol",
+            default_severity: ViolationSeverity::Info,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["strings", "storage"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        contract
+            .contract_types
+            .iter()
+            .flat_map(|contract_type| &contract_type.fields)
+            .filter(|field| field.type_name == "String")
+            .map(|field| RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!("Field '{}' uses String type", field.name),
+                suggestion: "Consider using Symbol for fixed string values to save storage costs".to_string(),
+                line_number: field.span.start_line,
+                column_number: field.span.start_column,
+                span: field.span,
+                variable_name: field.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            })
+            .collect()
     }
 }
 
-impl SorobanRule for MissingErrorHandlingRule {
-    fn id(&self) -> &str {
-        "soroban-missing-error-handling"
+/// Rule for detecting private fields in contract types
+#[derive(Default)]
+pub struct PrivateContractFieldRule;
+
+impl Rule for PrivateContractFieldRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-private-contract-field",
+            name: "Private Contract Field",
+            description: "Detects private fields in contract types, which other code cannot read or build",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["visibility"],
+        };
+        &METADATA
     }
-    
-    fn name(&self) -> &str {
-        "Missing Error Handling"
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        contract
+            .contract_types
+            .iter()
+            .flat_map(|contract_type| &contract_type.fields)
+            .filter(|field| matches!(field.visibility, FieldVisibility::Private))
+            .map(|field| RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!("Field '{}' is private but contract fields should typically be public", field.name),
+                suggestion: format!("Change '{}' to 'pub {}' to make it accessible", field.name, field.name),
+                line_number: field.span.start_line,
+                column_number: field.span.start_column,
+                span: field.span,
+                variable_name: field.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            })
+            .collect()
     }
-    
-    fn description(&self) -> &str {
-        "Detects functions that should return Result but don't"
+}
+
+/// Rule for detecting `std` vectors allocated without a capacity
+#[derive(Default)]
+pub struct VecWithoutCapacityRule;
+
+impl Rule for VecWithoutCapacityRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-vec-without-capacity",
+            name: "Vec Without Capacity",
+            description: "Detects Vec::new() in functions that never reserve capacity; soroban_sdk::Vec::new(&env) is a host object and is not flagged",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["collections"],
+        };
+        &METADATA
     }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Medium
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for function in contract.functions() {
+            let body = &function.body;
+            // `soroban_sdk::Vec::new(&env)` takes the environment and has no capacity to reserve
+            let allocation = body
+                .calls
+                .iter()
+                .filter(|call| call.path.ends_with("Vec::new") && call.args.is_empty())
+                .min_by_key(|call| call.call_span.start_byte);
+            let Some(call) = allocation.filter(|_| !body.has_call("with_capacity")) else {
+                continue;
+            };
+            let span = call.call_span;
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: "Vec::new() without capacity can cause multiple reallocations".to_string(),
+                suggestion: "Use Vec::with_capacity() to pre-allocate memory when size is known".to_string(),
+                line_number: span.start_line,
+                column_number: span.start_column,
+                span,
+                variable_name: function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
     }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
+}
+
+/// Rule for detecting setters and transfers taking an unvalidated `Address`
+#[derive(Default)]
+pub struct MissingAddressValidationRule;
+
+impl Rule for MissingAddressValidationRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-missing-address-validation",
+            name: "Missing Address Validation",
+            description: "Detects setter and transfer functions taking Address parameters that may lack validation",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["validation"],
+        };
+        &METADATA
     }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for function in contract.functions() {
+            if !(function.name.contains("set") || function.name.contains("transfer")) {
+                continue;
+            }
+            for param in function.params.iter().filter(|param| param.type_name.contains("Address")) {
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("Function '{}' takes Address parameter but may lack validation", function.name),
+                    suggestion: "Validate Address parameters to prevent invalid addresses".to_string(),
+                    line_number: param.span.start_line,
+                    column_number: param.span.start_column,
+                    span: param.span,
+                    variable_name: function.name.clone(),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
     }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+}
+
+/// Rule for detecting missing error handling
+#[derive(Default)]
+pub struct MissingErrorHandlingRule;
+
+impl Rule for MissingErrorHandlingRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-missing-error-handling",
+            name: "Missing Error Handling",
+            description: "Detects state-changing functions that don't return Result, and panic!, unwrap() and expect() in contract functions that should return a #[contracterror] variant instead",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["errors", "panics"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
@@ -605,77 +802,121 @@ impl SorobanRule for MissingErrorHandlingRule {
                         rule_name: self.id().to_string(),
                         description: format!("Function '{}' should return Result for proper error handling", function.name),
                         suggestion: "Return Result<(), Error> to properly handle operation failures and provide better error reporting".to_string(),
-                        line_number: function.line_number,
-                        column_number: 0,
+                        line_number: function.signature_span.start_line,
+                        column_number: function.signature_span.start_column,
+                        span: function.signature_span,
                         variable_name: function.name.clone(),
                         severity: self.severity(),
+                        fix: None,
                     });
                 }
             }
         }
-        
+
+        for site in contract.panic_sites() {
+            let variant = site.error_variant(contract);
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "Function '{}' can panic with {}, which aborts with an opaque host error",
+                    site.function.name,
+                    site.describe()
+                ),
+                suggestion: Self::panic_suggestion(contract, &site, &variant),
+                line_number: site.span().start_line,
+                column_number: site.span().start_column,
+                span: site.span(),
+                variable_name: site.function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
         violations
     }
 }
 
-/// Rule for detecting emergency withdrawal functions without authorization
-pub struct EmergencyWithdrawalRule {
-    enabled: bool,
-}
-
-impl Default for EmergencyWithdrawalRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl MissingErrorHandlingRule {
+    /// How to replace a panic with `variant`, adding the variant first when it is new
+    fn panic_suggestion(contract: &SorobanContract, site: &PanicSite<'_>, variant: &ErrorVariant) -> String {
+        let path = variant.path();
+        let returns_result = site.function.return_type.as_deref().is_some_and(|ty| ty.contains("Result"));
+        let replace = match (returns_result, site.kind) {
+            (true, PanicKind::Macro) => format!("return Err({}) instead of panicking", path),
+            (true, _) if site.storage_key.is_some() => format!("use .ok_or({})? instead of {}()", path, site.call.name),
+            (true, _) => format!("propagate the failure as Err({}) instead of calling {}()", path, site.call.name),
+            (false, _) => {
+                let env = site
+                    .function
+                    .params
+                    .iter()
+                    .find(|param| param.type_name.trim_start_matches('&') == "Env")
+                    .map_or("env", |param| param.name.as_str());
+                format!(
+                    "return Result<_, {}> with Err({}), or use panic_with_error!(&{}, {}) so callers get a typed error",
+                    variant.error_type, path, env, path
+                )
+            }
+        };
+        match variant.discriminant {
+            Some(discriminant) if contract.errors.is_empty() => format!(
+                "Define a #[contracterror] enum Error with `{} = {}`, then {}",
+                variant.name, discriminant, replace
+            ),
+            Some(discriminant) => format!(
+                "Add `{} = {}` to {}, then {}",
+                variant.name, discriminant, variant.error_type, replace
+            ),
+            None => {
+                let mut chars = replace.chars();
+                chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+            }
+        }
     }
 }
 
-impl SorobanRule for EmergencyWithdrawalRule {
-    fn id(&self) -> &str {
-        "soroban-emergency-withdrawal"
-    }
-    
-    fn name(&self) -> &str {
-        "Emergency Withdrawal Check"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects emergency withdrawal functions lacking proper authorization or whitelist checks"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::High
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+/// Rule for detecting emergency withdrawal functions without authorization
+#[derive(Default)]
+pub struct EmergencyWithdrawalRule;
+
+impl Rule for EmergencyWithdrawalRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-emergency-withdrawal",
+            name: "Emergency Withdrawal Check",
+            description: "Detects emergency withdrawal functions lacking proper authorization or whitelist checks",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["access-control"],
+        };
+        &METADATA
     }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
             for function in &implementation.functions {
                 let func_name = function.name.to_lowercase();
                 
-                // Identify emergency withdrawal functions
-                if func_name.contains("emergency") || func_name.contains("withdraw_all") || func_name.contains("rescue") {
-                    let source = &function.raw_definition;
-                    
-                    if !source.contains("require_auth") && !source.contains("authorize") && !source.contains("panic!") {
-                        violations.push(RuleViolation {
-                            rule_name: self.id().to_string(),
-                            description: format!("Emergency function '{}' lacks authorization check", function.name),
-                            suggestion: "Implement restrictive access control for emergency functions to prevent unauthorized fund depletion".to_string(),
-                            line_number: function.line_number,
-                            column_number: 0,
-                            variable_name: function.name.clone(),
-                            severity: self.severity(),
-                        });
-                    }
+                // Identify emergency withdrawal functions lacking any guard
+                let is_emergency = func_name.contains("emergency") || func_name.contains("withdraw_all") || func_name.contains("rescue");
+                if is_emergency && !Self::is_guarded(contract, function) {
+                    violations.push(RuleViolation {
+                        rule_name: self.id().to_string(),
+                        description: format!("Emergency function '{}' lacks authorization check", function.name),
+                        suggestion: "Implement restrictive access control for emergency functions to prevent unauthorized fund depletion".to_string(),
+                        line_number: function.signature_span.start_line,
+                        column_number: function.signature_span.start_column,
+                        span: function.signature_span,
+                        variable_name: function.name.clone(),
+                        severity: self.severity(),
+                        fix: None,
+                    });
                 }
             }
         }
@@ -684,43 +925,37 @@ impl SorobanRule for EmergencyWithdrawalRule {
     }
 }
 
-/// Rule for detecting governance voting functions without authorization
-pub struct GovernanceVotingRule {
-    enabled: bool,
-}
-
-impl Default for GovernanceVotingRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl EmergencyWithdrawalRule {
+    /// Whether the function, or a helper it calls, checks authorization or can abort
+    fn is_guarded(contract: &SorobanContract, function: &SorobanFunction) -> bool {
+        function.body.has_auth_check()
+            || function.body.has_call("panic")
+            || AuthFlow::analyze(contract, function).is_authorized()
     }
 }
 
-impl SorobanRule for GovernanceVotingRule {
-    fn id(&self) -> &str {
-        "soroban-governance-voting"
-    }
-    
-    fn name(&self) -> &str {
-        "Governance Voting Check"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects voting functions that may be missing authorization checks or are structurally insecure"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::High
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
+/// Rule for detecting governance voting functions without authorization
+#[derive(Default)]
+pub struct GovernanceVotingRule;
+
+impl Rule for GovernanceVotingRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-governance-voting",
+            name: "Governance Voting Check",
+            description: "Detects voting functions that may be missing authorization checks or are structurally insecure",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["access-control", "governance"],
+        };
+        &METADATA
     }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
@@ -729,31 +964,33 @@ impl SorobanRule for GovernanceVotingRule {
                 
                 // Identify voting functions
                 if func_name.contains("vote") || func_name.contains("propose") || func_name.contains("ballot") {
-                    let source = &function.raw_definition;
-                    
-                    // Check for authorization: require_auth() or authorize()
-                    if !source.contains("require_auth") && !source.contains("authorize") {
+                    // Check for authorization: require_auth() or authorize(), here or in a helper
+                    if !function.body.has_auth_check() && !AuthFlow::analyze(contract, function).is_authorized() {
                         violations.push(RuleViolation {
                             rule_name: self.id().to_string(),
                             description: format!("Governance function '{}' lacks explicit authorization check", function.name),
                             suggestion: "Add 'caller.require_auth()' or 'env.authorize()' to ensure only authorized users can perform governance actions".to_string(),
-                            line_number: function.line_number,
-                            column_number: 0,
+                            line_number: function.signature_span.start_line,
+                            column_number: function.signature_span.start_column,
+                            span: function.signature_span,
                             variable_name: function.name.clone(),
                             severity: self.severity(),
+                            fix: None,
                         });
                     }
                     
                     // Check for timestamp/expiration usage in proposals (heuristic)
-                    if func_name.contains("propose") && !source.contains("timestamp") && !source.contains("expiration") {
+                    if func_name.contains("propose") && !function.body.mentions("timestamp") && !function.body.mentions("expiration") {
                         violations.push(RuleViolation {
                             rule_name: self.id().to_string(),
                             description: format!("Governance function '{}' may be missing proposal expiration logic", function.name),
                             suggestion: "Proposals should have an expiration timestamp to prevent indefinite open voting".to_string(),
-                            line_number: function.line_number,
-                            column_number: 0,
+                            line_number: function.signature_span.start_line,
+                            column_number: function.signature_span.start_column,
+                            span: function.signature_span,
                             variable_name: function.name.clone(),
                             severity: ViolationSeverity::Warning,
+                            fix: None,
                         });
                     }
                 }
@@ -804,7 +1041,7 @@ impl TestContract {
 "#;
         
         let mut engine = SorobanRuleEngine::new();
-        engine.add_rule(UnusedStateVariablesRule::default());
+        engine.add_rule(UnusedStateVariablesRule);
         
         let violations = engine.analyze(source, "test.rs").unwrap();
         
@@ -837,10 +1074,10 @@ impl GovernanceContract {
 }
 "#;
         
-        let rule = GovernanceVotingRule::default();
+        let rule = GovernanceVotingRule;
         let contract = SorobanParser::parse_contract(source, "governance.rs").unwrap();
         
-        let violations = rule.apply(&contract);
+        let violations = rule.check(&RuleInput::Soroban(&contract));
         
         let vote_issue_found = violations.iter().any(|v| 
             v.rule_name == "soroban-governance-voting" && 
@@ -851,42 +1088,27 @@ impl GovernanceContract {
 }
 
 /// Rule for detecting missing claim expiration logic (#117)
-pub struct ClaimExpirationRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct ClaimExpirationRule;
 
-impl Default for ClaimExpirationRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for ClaimExpirationRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-claim-expiration",
+            name: "Claim Expiration Check",
+            description: "Detects claim-related functions that lack expiration/timeout logic",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["time"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for ClaimExpirationRule {
-    fn id(&self) -> &str {
-        "soroban-claim-expiration"
-    }
-    
-    fn name(&self) -> &str {
-        "Claim Expiration Check"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects claim-related functions that lack expiration/timeout logic"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Medium
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
@@ -894,17 +1116,19 @@ impl SorobanRule for ClaimExpirationRule {
                 let func_name = function.name.to_lowercase();
                 
                 if func_name.contains("claim") || func_name.contains("settle") || func_name.contains("redeem") {
-                    let source = &function.raw_definition;
+                    let body = &function.body;
                     
-                    if !source.contains("timestamp") && !source.contains("expiration") && !source.contains("expiry") {
+                    if !body.mentions("timestamp") && !body.mentions("expiration") && !body.mentions("expiry") {
                         violations.push(RuleViolation {
                             rule_name: self.id().to_string(),
                             description: format!("Claim function '{}' may be missing expiration logic", function.name),
                             suggestion: "Add an expiration timestamp check to ensure claims cannot be processed after a certain deadline".to_string(),
-                            line_number: function.line_number,
-                            column_number: 0,
+                            line_number: function.signature_span.start_line,
+                            column_number: function.signature_span.start_column,
+                            span: function.signature_span,
                             variable_name: function.name.clone(),
                             severity: self.severity(),
+                            fix: None,
                         });
                     }
                 }
@@ -916,42 +1140,27 @@ impl SorobanRule for ClaimExpirationRule {
 }
 
 /// Rule for detecting susceptibility to front-running (#118)
-pub struct AntiFrontRunningRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct AntiFrontRunningRule;
 
-impl Default for AntiFrontRunningRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for AntiFrontRunningRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-anti-front-running",
+            name: "Anti-Front-Running Protection",
+            description: "Detects transaction patterns vulnerable to front-running (e.g., missing nonces or slippage checks)",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["front-running"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for AntiFrontRunningRule {
-    fn id(&self) -> &str {
-        "soroban-anti-front-running"
-    }
-    
-    fn name(&self) -> &str {
-        "Anti-Front-Running Protection"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects transaction patterns vulnerable to front-running (e.g., missing nonces or slippage checks)"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::High
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
         for implementation in &contract.implementations {
@@ -960,17 +1169,19 @@ impl SorobanRule for AntiFrontRunningRule {
                 
                 // Sensitive operations: transfer, swap, liquidate
                 if func_name.contains("transfer") || func_name.contains("swap") || func_name.contains("liquidate") {
-                    let source = &function.raw_definition;
+                    let body = &function.body;
                     
-                    if !source.contains("nonce") && !source.contains("deadline") && !source.contains("min_amount") {
+                    if !body.mentions("nonce") && !body.mentions("deadline") && !body.mentions("min_amount") {
                         violations.push(RuleViolation {
                             rule_name: self.id().to_string(),
                             description: format!("Function '{}' may be vulnerable to front-running", function.name),
                             suggestion: "Implement nonces, deadlines, or minimum output checks (slippage protection) to prevent transaction reordering attacks".to_string(),
-                            line_number: function.line_number,
-                            column_number: 0,
+                            line_number: function.signature_span.start_line,
+                            column_number: function.signature_span.start_column,
+                            span: function.signature_span,
                             variable_name: function.name.clone(),
                             severity: self.severity(),
+                            fix: None,
                         });
                     }
                 }
@@ -982,62 +1193,51 @@ impl SorobanRule for AntiFrontRunningRule {
 }
 
 /// Rule for detecting insecure randomness sources (#119)
-pub struct SecureRandomnessRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct SecureRandomnessRule;
 
-impl Default for SecureRandomnessRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for SecureRandomnessRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-secure-randomness",
+            name: "Secure Randomness Check",
+            description: "Detects the use of predictable values for randomness instead of 'env.pseudo_random()'",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["randomness"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for SecureRandomnessRule {
-    fn id(&self) -> &str {
-        "soroban-secure-randomness"
-    }
-    
-    fn name(&self) -> &str {
-        "Secure Randomness Check"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects the use of predictable values for randomness instead of 'env.pseudo_random()'"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::High
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
         
-        let insecure_patterns = ["env.ledger().timestamp()", "env.ledger().sequence()", "timestamp()", "sequence()"];
-        
         for implementation in &contract.implementations {
             for function in &implementation.functions {
-                let source = &function.raw_definition;
+                let body = &function.body;
+                let insecure_source = body.calls
+                    .iter()
+                    .filter(|call| call.kind == CallKind::Method && (call.name == "timestamp" || call.name == "sequence"))
+                    .min_by_key(|call| call.span.start_byte);
                 
-                if (source.contains("random") || source.contains("seed")) && 
-                   insecure_patterns.iter().any(|p| source.contains(p)) &&
-                   !source.contains("pseudo_random") {
-                    
+                if let Some(call) = insecure_source.filter(|_| {
+                    (body.mentions("random") || body.mentions("seed")) && !body.has_call("pseudo_random")
+                }) {
+                    let span = call.span;
                     violations.push(RuleViolation {
                         rule_name: self.id().to_string(),
                         description: format!("Function '{}' uses predictable values for randomness", function.name),
                         suggestion: "Use 'env.pseudo_random()' for generating secure random values instead of ledger block properties".to_string(),
-                        line_number: function.line_number,
-                        column_number: 0,
+                        line_number: span.start_line,
+                        column_number: span.start_column,
+                        span,
                         variable_name: function.name.clone(),
                         severity: self.severity(),
+                        fix: None,
                     });
                 }
             }
@@ -1048,42 +1248,27 @@ impl SorobanRule for SecureRandomnessRule {
 }
 
 /// Rule for detecting missing version tracking in contracts (#123)
-pub struct UpgradeVersionTrackingRule {
-    enabled: bool,
-}
+#[derive(Default)]
+pub struct UpgradeVersionTrackingRule;
 
-impl Default for UpgradeVersionTrackingRule {
-    fn default() -> Self {
-        Self { enabled: true }
+impl Rule for UpgradeVersionTrackingRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-upgrade-version-tracking",
+            name: "Upgrade Version Tracking",
+            description: "Detects contracts missing version information or upgrade tracking",
+            default_severity: ViolationSeverity::Info,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["upgrades"],
+        };
+        &METADATA
     }
-}
 
-impl SorobanRule for UpgradeVersionTrackingRule {
-    fn id(&self) -> &str {
-        "soroban-upgrade-version-tracking"
-    }
-    
-    fn name(&self) -> &str {
-        "Upgrade Version Tracking"
-    }
-    
-    fn description(&self) -> &str {
-        "Detects contracts missing version information or upgrade tracking"
-    }
-    
-    fn severity(&self) -> ViolationSeverity {
-        ViolationSeverity::Info
-    }
-    
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-    
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-    
-    fn apply(&self, contract: &SorobanContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
         let mut has_version_field = false;
         let mut has_version_query = false;
         
@@ -1106,10 +1291,12 @@ impl SorobanRule for UpgradeVersionTrackingRule {
                 rule_name: self.id().to_string(),
                 description: "Contract lacks version tracking or a version query method".to_string(),
                 suggestion: "Add a 'version: u32' field to your state and a 'version()' method to track contract upgrades".to_string(),
-                line_number: 1,
-                column_number: 0,
+                line_number: contract.span.start_line,
+                column_number: contract.span.start_column,
+                span: contract.span,
                 variable_name: contract.name.clone(),
                 severity: self.severity(),
+                fix: None,
             }];
         }
         
@@ -1117,65 +1304,2532 @@ impl SorobanRule for UpgradeVersionTrackingRule {
     }
 }
 
-#[cfg(test)]
-mod issue_tests {
-    use super::*;
-    
-    #[test]
-    fn test_claim_expiration_rule() {
-        let source = r#"
-use soroban_sdk::{contract, contractimpl, Env, Address};
+/// Rule for detecting `.clone()` calls on values that are never used afterwards
+#[derive(Default)]
+pub struct RedundantCloneRule;
 
-#[contract]
-pub struct MyContract;
+impl Rule for RedundantCloneRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-redundant-clone",
+            name: "Redundant Clone",
+            description: "Detects clones of local values that are not used again, where the value could be moved instead",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["allocation"],
+        };
+        &METADATA
+    }
 
-#[contractimpl]
-impl MyContract {
-    pub fn claim_reward(env: Env, user: Address) {
-        // ❌ Missing expiration check
-        let reward = 100;
-        env.storage().instance().set(&user, &reward);
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+        
+        for implementation in &contract.implementations {
+            for function in &implementation.functions {
+                let body = &function.body;
+                
+                for call in body.calls_named("clone") {
+                    // Only plain `name.clone()`; fields and expressions may be borrowed
+                    let [name] = call.receiver_chain.as_slice() else { continue };
+                    if name == "self" || !call.args.is_empty() || call.receiver.as_deref() != Some(name.as_str()) {
+                        continue;
+                    }
+                    
+                    // A clone inside a loop runs again on the next iteration
+                    if body.loops.iter().any(|l| l.span.contains(&call.span)) {
+                        continue;
+                    }
+                    
+                    let used_later = body.local_references
+                        .iter()
+                        .any(|r| &r.name == name && r.span.start_byte >= call.span.end_byte);
+                    if used_later {
+                        continue;
+                    }
+                    
+                    // Moving an owned parameter is always fine; a local may hold a reference
+                    let applicability = match function.params.iter().find(|p| &p.name == name) {
+                        Some(param) if param.type_name.starts_with('&') => continue,
+                        Some(_) => Applicability::MachineApplicable,
+                        None => Applicability::MaybeIncorrect,
+                    };
+                    
+                    let span = call.call_span;
+                    violations.push(RuleViolation {
+                        rule_name: self.id().to_string(),
+                        description: format!("'{}' is cloned in '{}' but never used afterwards", name, function.name),
+                        suggestion: format!("Move '{}' instead of cloning it to avoid the extra host object copy", name),
+                        line_number: span.start_line,
+                        column_number: span.start_column,
+                        span,
+                        variable_name: name.clone(),
+                        severity: self.severity(),
+                        fix: Some(Fix::new(
+                            format!("Remove redundant clone of '{}'", name),
+                            applicability,
+                            vec![TextEdit::delete(span)],
+                        )),
+                    });
+                }
+            }
+        }
+        
+        violations
     }
-    
-    pub fn secure_claim(env: Env, deadline: u64) {
-        // ✅ Has expiration check
-        if env.ledger().timestamp() > deadline {
-            panic!("Expired");
+}
+
+/// Whether `source` names `path`, e.g. `DataKey::Admin`, and not just a longer
+/// path it is a prefix of
+fn names_path(source: &str, path: &str) -> bool {
+    source
+        .match_indices(path)
+        .any(|(index, _)| !source[index + path.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+/// Edits moving every `from`-tier access of the storage key `key_name` to the `to` tier
+fn retier_edits(contract: &SorobanContract, key_name: &str, from: StorageTier, to: StorageTier) -> Vec<TextEdit> {
+    contract
+        .storage_accesses()
+        .filter(|other| other.access.tier == from && other.key().is_some_and(|key| key.name == key_name))
+        .filter_map(|other| tier_span(other.access, &contract.source))
+        .map(|span| TextEdit::replace(span, to.method()))
+        .collect()
+}
+
+/// Rule for detecting per-user data and growing collections in instance storage
+///
+/// Instance storage is a single ledger entry that is loaded on every invocation,
+/// so each entry added to it makes every later call to the contract more expensive.
+#[derive(Default)]
+pub struct InstanceStorageGrowthRule;
+
+impl Rule for InstanceStorageGrowthRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-instance-unbounded-data",
+            name: "Unbounded Data in Instance Storage",
+            description: "Detects per-user entries and growing collections kept in instance storage, which is loaded on every invocation",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["storage", "storage-tier"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+        let mut flagged = HashSet::new();
+
+        for keyed in contract.storage_accesses() {
+            let access = keyed.access;
+            if access.tier != StorageTier::Instance || !matches!(access.operation, StorageOperation::Set | StorageOperation::Update) {
+                continue;
+            }
+            let Some(key) = keyed.key() else { continue };
+
+            let reason = if !key.parameters.is_empty() {
+                format!("is keyed by '{}', so it holds one entry per distinct value", key.parameters.join("', '"))
+            } else if let Some(collection) = Self::grown_collection(keyed.function, access) {
+                format!("holds '{}', a collection that '{}' keeps growing", collection, keyed.function.name)
+            } else {
+                continue;
+            };
+            if !flagged.insert(key.name.clone()) {
+                continue;
+            }
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!("Instance storage key '{}' {}", key.name, reason),
+                suggestion: "Keep per-user and growing data in persistent() storage so it is only loaded when used".to_string(),
+                line_number: access.span.start_line,
+                column_number: access.span.start_column,
+                span: access.span,
+                variable_name: key.name.clone(),
+                severity: self.severity(),
+                fix: Some(Fix::new(
+                    format!("Move '{}' to persistent storage", key.name),
+                    Applicability::MaybeIncorrect,
+                    retier_edits(contract, &key.name, StorageTier::Instance, StorageTier::Persistent),
+                )),
+            });
+        }
+
+        violations
+    }
+}
+
+impl InstanceStorageGrowthRule {
+    /// Local collection stored by `access` that the function appends to
+    fn grown_collection<'a>(function: &'a SorobanFunction, access: &StorageAccess) -> Option<&'a str> {
+        let call = function.body.calls.iter().find(|call| call.kind == CallKind::Method && call.span == access.span)?;
+        let value = call.args.get(1)?.trim_start_matches('&').trim();
+        let grows = function.body.calls.iter().any(|call| {
+            call.receiver_chain == [value]
+                && matches!(call.name.as_str(), "push_back" | "push_front" | "append" | "insert" | "set")
+        });
+        grows.then_some(value)
+    }
+}
+
+/// Words marking storage keys whose entries are only needed for a short while
+const SHORT_LIVED_MARKERS: &[&str] = &[
+    "nonce", "claim", "session", "pending", "challenge", "commitment", "ticket", "otp", "signature", "replay",
+];
+
+/// Rule for detecting short-lived entries kept in persistent storage
+///
+/// Temporary entries are cheaper to write and are deleted when their TTL runs
+/// out, instead of being archived and kept restorable like persistent entries.
+#[derive(Default)]
+pub struct PersistentShortLivedDataRule;
+
+impl Rule for PersistentShortLivedDataRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-persistent-short-lived",
+            name: "Short-Lived Data in Persistent Storage",
+            description: "Detects nonces, claims and other short-lived entries kept in persistent storage where temporary storage would do",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["storage", "storage-tier"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+        let mut flagged = HashSet::new();
+
+        for keyed in contract.storage_accesses() {
+            let access = keyed.access;
+            if access.tier != StorageTier::Persistent || !matches!(access.operation, StorageOperation::Set | StorageOperation::Update) {
+                continue;
+            }
+            let Some(key) = keyed.key() else { continue };
+            let Some(marker) = Self::short_lived_marker(&key.name) else { continue };
+            if !flagged.insert(key.name.clone()) {
+                continue;
+            }
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "Persistent storage key '{}' looks like a short-lived {} entry",
+                    key.name, marker
+                ),
+                suggestion: "Store entries that only need to outlive a few ledgers in temporary() storage".to_string(),
+                line_number: access.span.start_line,
+                column_number: access.span.start_column,
+                span: access.span,
+                variable_name: key.name.clone(),
+                severity: self.severity(),
+                fix: Some(Fix::new(
+                    format!("Move '{}' to temporary storage", key.name),
+                    Applicability::MaybeIncorrect,
+                    retier_edits(contract, &key.name, StorageTier::Persistent, StorageTier::Temporary),
+                )),
+            });
         }
+
+        violations
     }
 }
-"#;
-        let rule = ClaimExpirationRule::default();
-        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
-        let violations = rule.apply(&contract);
-        
-        // Should find one violation for claim_reward
-        assert!(violations.iter().any(|v| v.variable_name == "claim_reward"));
-        // Should NOT find violation for secure_claim
-        assert!(!violations.iter().any(|v| v.variable_name == "secure_claim"));
+
+impl PersistentShortLivedDataRule {
+    /// The short-lived marker among the words of a key name, e.g. `nonce` in `DataKey::UsedNonces`
+    fn short_lived_marker(key_name: &str) -> Option<&'static str> {
+        let mut words = Vec::new();
+        for part in key_name.split(|c: char| !c.is_alphanumeric()) {
+            let mut word = String::new();
+            for c in part.chars() {
+                if c.is_uppercase() && !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                word.extend(c.to_lowercase());
+            }
+            words.push(word);
+        }
+
+        SHORT_LIVED_MARKERS.iter().copied().find(|marker| {
+            words.iter().any(|word| {
+                word.strip_prefix(marker)
+                    .is_some_and(|suffix| matches!(suffix, "" | "s" | "ed"))
+            })
+        })
     }
+}
 
-    #[test]
-    fn test_secure_randomness_rule() {
-        let source = r#"
-use soroban_sdk::{contract, contractimpl, Env};
+/// Rule for detecting storage keys used in a different tier than they are written to
+///
+/// Each tier is a separate keyspace, so reading a key from a tier it was never
+/// written to always finds nothing.
+#[derive(Default)]
+pub struct StorageTierMismatchRule;
 
-#[contract]
-pub struct MyContract;
+impl Rule for StorageTierMismatchRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-storage-tier-mismatch",
+            name: "Storage Tier Mismatch",
+            description: "Detects keys read from one storage tier but only ever written to another, so the read never finds the value",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["storage", "storage-tier"],
+        };
+        &METADATA
+    }
 
-#[contractimpl]
-impl MyContract {
-    pub fn roll_dice(env: Env) -> u32 {
-        // ❌ Insecure randomness
-        let seed = env.ledger().timestamp();
-        (seed % 6) + 1
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+
+        let mut written: HashMap<String, Vec<StorageTier>> = HashMap::new();
+        for keyed in contract.storage_accesses() {
+            if !matches!(keyed.access.operation, StorageOperation::Set | StorageOperation::Update) {
+                continue;
+            }
+            if let Some(key) = keyed.key() {
+                let tiers = written.entry(key.name).or_default();
+                if !tiers.contains(&keyed.access.tier) {
+                    tiers.push(keyed.access.tier);
+                }
+            }
+        }
+
+        let mut violations = Vec::new();
+        for keyed in contract.storage_accesses() {
+            let access = keyed.access;
+            if matches!(access.operation, StorageOperation::Set | StorageOperation::Update) {
+                continue;
+            }
+            let Some(key) = keyed.key() else { continue };
+            // Keys never written here may be set up by another contract or a migration
+            let Some(tiers) = written.get(&key.name) else { continue };
+            if tiers.contains(&access.tier) {
+                continue;
+            }
+
+            let written_in = tiers.iter().map(|tier| tier.method()).collect::<Vec<_>>().join(" and ");
+            let fix = match tiers.as_slice() {
+                [tier] => tier_span(access, &contract.source).map(|span| {
+                    Fix::new(
+                        format!("Use {} storage", tier.method()),
+                        Applicability::MaybeIncorrect,
+                        vec![TextEdit::replace(span, tier.method())],
+                    )
+                }),
+                _ => None,
+            };
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' uses key '{}' in {} storage, but the key is only written to {} storage",
+                    keyed.function.name,
+                    key.name,
+                    access.tier.method(),
+                    written_in
+                ),
+                suggestion: format!("Access '{}' through the same storage tier it is written to", key.name),
+                line_number: access.span.start_line,
+                column_number: access.span.start_column,
+                span: access.span,
+                variable_name: key.name.clone(),
+                severity: self.severity(),
+                fix,
+            });
+        }
+
+        violations
     }
 }
-"#;
-        let rule = SecureRandomnessRule::default();
-        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
-        let violations = rule.apply(&contract);
-        assert!(!violations.is_empty());
+
+/// Rule for detecting archivable entries whose TTL is never extended
+///
+/// Persistent entries and the contract instance are archived when their TTL runs
+/// out, and reads of archived entries fail until someone restores them.
+#[derive(Default)]
+pub struct MissingTtlExtensionRule;
+
+impl Rule for MissingTtlExtensionRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-missing-ttl-extension",
+            name: "Missing TTL Extension",
+            description: "Detects persistent keys and instance storage that are written but never have their TTL extended, so they can be archived and then fail reads",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["storage", "ttl"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for entry in TtlCoverage::analyze(contract).entries {
+            if !entry.is_uncovered() {
+                continue;
+            }
+            let write = entry.writes[0].access;
+            let (description, suggestion, variable_name) = match &entry.key {
+                // Entries only written here may be read by other contracts through
+                // events or off-chain, and do not fail anything when archived
+                Some(_) if entry.reads.is_empty() => continue,
+                Some(key) => (
+                    format!(
+                        "Persistent key '{}' is written and read, but its TTL is never extended, so reads fail once it is archived",
+                        key
+                    ),
+                    format!(
+                        "Call env.storage().persistent().extend_ttl(&key, threshold, extend_to) for '{}' when it is written or read",
+                        key
+                    ),
+                    key.clone(),
+                ),
+                None => (
+                    "Instance storage is written, but the instance TTL is never extended, so the whole contract becomes unusable once it is archived".to_string(),
+                    "Call env.storage().instance().extend_ttl(threshold, extend_to) from the contract's entry points".to_string(),
+                    "instance".to_string(),
+                ),
+            };
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description,
+                suggestion,
+                line_number: write.span.start_line,
+                column_number: write.span.start_column,
+                span: write.span,
+                variable_name,
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting TTL extensions that cost more than they need to
+///
+/// Extending a TTL is charged for the rent of the whole entry, so it should run
+/// once per invocation and only when the TTL has actually dropped.
+pub struct WastefulTtlExtensionRule {
+    /// Threshold, as a percentage of the extended TTL, above which the entry is
+    /// extended on almost every call
+    max_threshold_percent: u64,
+}
+
+impl Default for WastefulTtlExtensionRule {
+    fn default() -> Self {
+        Self { max_threshold_percent: 90 }
+    }
+}
+
+impl Rule for WastefulTtlExtensionRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-wasteful-ttl-extension",
+            name: "Wasteful TTL Extension",
+            description: "Detects extend_ttl calls made inside loops or with a threshold so close to the extended TTL that every call pays for an extension",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["storage", "ttl"],
+        };
+        &METADATA
+    }
+
+    fn set_threshold(&mut self, name: &str, value: u64) -> bool {
+        match name {
+            "max_threshold_percent" => {
+                self.max_threshold_percent = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for keyed in contract.storage_accesses() {
+            let access = keyed.access;
+            if access.operation != StorageOperation::ExtendTtl {
+                continue;
+            }
+
+            let mut reasons = Vec::new();
+            if let Some(pattern) = Self::enclosing_loop(keyed.function, access) {
+                reasons.push(match pattern {
+                    Some(pattern) => format!("inside a loop, though its key does not depend on '{}'", pattern),
+                    None => "inside a loop".to_string(),
+                });
+            }
+            if let Some((threshold, extend_to)) = self.excessive_threshold(contract, access) {
+                reasons.push(format!(
+                    "with threshold {} at {}% or more of the extended TTL {}, so nearly every call extends it",
+                    threshold, self.max_threshold_percent, extend_to
+                ));
+            }
+            if reasons.is_empty() {
+                continue;
+            }
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' extends the TTL of {} storage {}",
+                    keyed.function.name,
+                    access.tier.method(),
+                    reasons.join(" and ")
+                ),
+                suggestion: "Extend each entry once per invocation, outside loops, with a threshold well below the extended TTL".to_string(),
+                line_number: access.span.start_line,
+                column_number: access.span.start_column,
+                span: access.span,
+                variable_name: access.key.clone().unwrap_or_else(|| access.tier.method().to_string()),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+impl WastefulTtlExtensionRule {
+    /// The pattern of the loop around `access`, when the extension repeats on each
+    /// iteration for the same entry; `Some(None)` for loops without a pattern
+    fn enclosing_loop(function: &SorobanFunction, access: &StorageAccess) -> Option<Option<String>> {
+        let enclosing = function.body.loops.iter().rev().find(|l| l.span.contains(&access.span))?;
+        let Some(pattern) = enclosing.pattern.clone() else {
+            return Some(None);
+        };
+        // Extending a different key on each iteration is not repeated work
+        if access.tier != StorageTier::Instance {
+            let key = StorageKey::resolve(function, access)?;
+            let words = |text: &str| -> Vec<String> {
+                text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .filter(|word| !word.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            };
+            let key_words = words(&key.expression);
+            if words(&pattern).iter().any(|name| key_words.contains(name)) {
+                return None;
+            }
+        }
+        Some(Some(pattern))
+    }
+
+    /// Evaluated threshold and extended TTL, when the threshold is too close to the extended TTL
+    fn excessive_threshold(&self, contract: &SorobanContract, access: &StorageAccess) -> Option<(u128, u128)> {
+        let skip = if access.tier == StorageTier::Instance { 0 } else { 1 };
+        let threshold = contract.evaluate(access.args.get(skip)?)?;
+        let extend_to = contract.evaluate(access.args.get(skip + 1)?)?;
+        (threshold.checked_mul(100)? >= extend_to.checked_mul(u128::from(self.max_threshold_percent))?)
+            .then_some((threshold, extend_to))
+    }
+}
+
+/// Rule for detecting contract error variants sharing a code
+///
+/// Callers only see the `u32` code of a contract error, so two variants with the
+/// same code cannot be told apart.
+#[derive(Default)]
+pub struct DuplicateErrorCodeRule;
+
+impl Rule for DuplicateErrorCodeRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-duplicate-error-code",
+            name: "Duplicate Error Code",
+            description: "Detects #[contracterror] variants that share an error code, which callers cannot tell apart",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["errors"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+        let mut codes: HashMap<u128, String> = HashMap::new();
+
+        for error in &contract.errors {
+            // Variants without a discriminant continue from the previous one, as in Rust
+            let mut next = Some(0u128);
+            for variant in &error.variants {
+                let code = match &variant.discriminant {
+                    Some(discriminant) => contract.evaluate(discriminant),
+                    None => next,
+                };
+                next = code.and_then(|code| code.checked_add(1));
+                let Some(code) = code else { continue };
+
+                let path = format!("{}::{}", error.name, variant.name);
+                let Some(first) = codes.get(&code) else {
+                    codes.insert(code, path);
+                    continue;
+                };
+
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("Error '{}' has code {}, already used by '{}'", path, code, first),
+                    suggestion: "Give every contract error a distinct code".to_string(),
+                    line_number: variant.span.start_line,
+                    column_number: variant.span.start_column,
+                    span: variant.span,
+                    variable_name: path,
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting contract error variants that are never returned
+#[derive(Default)]
+pub struct UnusedErrorVariantRule;
+
+impl Rule for UnusedErrorVariantRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-unused-error-variant",
+            name: "Unused Error Variant",
+            description: "Detects #[contracterror] variants the contract never uses",
+            default_severity: ViolationSeverity::Info,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["errors"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for error in &contract.errors {
+            for variant in &error.variants {
+                let path = format!("{}::{}", error.name, variant.name);
+                if names_path(&contract.source, &path) {
+                    continue;
+                }
+
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("Error '{}' is declared but never used", path),
+                    suggestion: format!(
+                        "Remove '{}' or return it where it applies; its code stays reserved either way",
+                        variant.name
+                    ),
+                    line_number: variant.span.start_line,
+                    column_number: variant.span.start_column,
+                    span: variant.span,
+                    variable_name: path,
+                    severity: self.severity(),
+                    fix: Some(Fix::new(
+                        format!("Remove variant '{}'", variant.name),
+                        Applicability::MaybeIncorrect,
+                        vec![TextEdit::delete(UnusedStateVariablesRule::field_with_comma(
+                            variant.span,
+                            &contract.source,
+                        ))],
+                    )),
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting storage keys that are read but never written
+///
+/// Only the contract itself can write its storage, so a key enum variant no code
+/// path writes is always missing, unless an earlier version of the contract wrote it.
+#[derive(Default)]
+pub struct StorageKeyNeverWrittenRule;
+
+impl Rule for StorageKeyNeverWrittenRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-storage-key-never-written",
+            name: "Storage Key Never Written",
+            description: "Detects #[contracttype] storage keys the contract reads but never writes, so the reads never find a value",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["storage"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let inventory = StorageInventory::build(contract);
+        let written: HashSet<&str> = inventory
+            .keys
+            .iter()
+            .filter(|key| !key.writers.is_empty())
+            .map(|key| key.key.as_str())
+            .collect();
+
+        let mut violations = Vec::new();
+        let mut flagged = HashSet::new();
+        for keyed in contract.storage_accesses() {
+            let access = keyed.access;
+            if !access.operation.is_read() {
+                continue;
+            }
+            let Some(key) = keyed.key() else { continue };
+            let is_variant = inventory
+                .keys
+                .iter()
+                .any(|entry| entry.key == key.name && entry.kind == KeyKind::Variant);
+            if !is_variant || written.contains(key.name.as_str()) || !flagged.insert(key.name.clone()) {
+                continue;
+            }
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' reads storage key '{}', which the contract never writes",
+                    keyed.function.name, key.name
+                ),
+                suggestion: format!(
+                    "Write '{}' where it is initialized, or drop the read if an earlier contract version set it and it is no longer needed",
+                    key.name
+                ),
+                line_number: access.span.start_line,
+                column_number: access.span.start_column,
+                span: access.span,
+                variable_name: key.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting entry points that change state on behalf of an address
+/// without authorizing it
+///
+/// Checks are followed into private helpers, so `Self::check_admin_auth(&env)`
+/// counts as authorizing the admin.
+#[derive(Default)]
+pub struct MissingAuthRule;
+
+impl Rule for MissingAuthRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-missing-auth",
+            name: "Missing Authorization",
+            description: "Detects entry points that move the contract's own tokens without any authorization, or write per-address or admin-guarded state without require_auth() on the address involved",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["access-control", "auth"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let flows = AuthFlow::analyze_contract(contract);
+
+        // Keys some entry point only writes after authorizing someone
+        let guarded: HashSet<&str> = flows
+            .iter()
+            .filter(|flow| flow.is_authorized())
+            .flat_map(|flow| &flow.effects)
+            .filter_map(|effect| match &effect.kind {
+                EffectKind::StorageWrite { key: Some(key), .. } => Some(key.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut violations = Vec::new();
+        for flow in &flows {
+            let function = flow.function;
+            if function.is_constructor {
+                continue;
+            }
+            let Some((effect, reason)) = flow
+                .effects
+                .iter()
+                .find_map(|effect| Self::missing_auth(flow, effect, &guarded).map(|reason| (effect, reason)))
+            else {
+                continue;
+            };
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!("'{}' {} {}", function.name, effect.describe(), reason),
+                suggestion: "Call require_auth() on the address whose funds or state change before changing them".to_string(),
+                line_number: effect.span.start_line,
+                column_number: effect.span.start_column,
+                span: effect.span,
+                variable_name: function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+impl MissingAuthRule {
+    /// Why `effect` needs an authorization check `flow` lacks
+    fn missing_auth(flow: &AuthFlow<'_>, effect: &Effect, guarded: &HashSet<&str>) -> Option<String> {
+        let function = flow.function;
+        match &effect.kind {
+            // Transfers out of other addresses are left to `TransferFromUnauthenticatedRule`
+            EffectKind::TokenTransfer { from, .. } => (from.is_current_contract() && !flow.is_authorized())
+                .then(|| "without any authorization".to_string()),
+            EffectKind::StorageWrite { key: Some(key), .. } => {
+                if flow.authorizes_stored() {
+                    return None;
+                }
+                // Keys built from an address belong to that address
+                let owner = function.body.storage_accesses.iter().find_map(|access| {
+                    let resolved = StorageKey::resolve(function, access)?;
+                    (resolved.name == *key && access.operation.is_write()).then_some(resolved)
+                })
+                .and_then(|resolved| {
+                    resolved.parameters.into_iter().find(|name| {
+                        function.params.iter().any(|param| param.name == *name && param.type_name.ends_with("Address"))
+                    })
+                });
+                if let Some(owner) = owner {
+                    let subject = AuthSubject::Parameter(owner.clone());
+                    return (!flow.authorizes(&subject)).then(|| format!("owned by '{}' without '{}.require_auth()'", owner, owner));
+                }
+
+                // One-time initialization checks the key is not set yet instead
+                let initializes = function.body.storage_accesses.iter().any(|access| {
+                    access.operation == StorageOperation::Has
+                        && StorageKey::resolve(function, access).is_some_and(|resolved| resolved.name == *key)
+                });
+                (guarded.contains(key.as_str()) && !flow.is_authorized() && !initializes)
+                    .then(|| "without authorization, though other entry points authorize before writing it".to_string())
+            }
+            EffectKind::StorageWrite { key: None, .. } => None,
+        }
+    }
+}
+
+/// Rule for detecting authorization checks made after the state they guard changes
+///
+/// A failed check still reverts the invocation, but the writes before it are
+/// charged for and the function reads as unguarded to reviewers.
+#[derive(Default)]
+pub struct AuthAfterEffectRule;
+
+impl Rule for AuthAfterEffectRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-auth-after-effect",
+            name: "Authorization After State Change",
+            description: "Detects entry points that write storage or move tokens before their first require_auth() check",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["access-control", "auth"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for flow in AuthFlow::analyze_contract(contract) {
+            let Some(effect) = flow.effects_before_auth().next() else { continue };
+            let check = &flow.checks[0];
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' {} before authorizing {} on line {}",
+                    flow.function.name,
+                    effect.describe(),
+                    check.subject.describe(),
+                    check.span.start_line
+                ),
+                suggestion: "Check authorization first, before any storage write or token transfer".to_string(),
+                line_number: effect.span.start_line,
+                column_number: effect.span.start_column,
+                span: effect.span,
+                variable_name: flow.function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting fallible cross-contract calls whose result is thrown away
+///
+/// `try_` client methods and `env.try_invoke_contract` return the callee's
+/// failure instead of trapping, so discarding the result silently ignores it.
+#[derive(Default)]
+pub struct UncheckedExternalCallRule;
+
+impl Rule for UncheckedExternalCallRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-unchecked-external-call",
+            name: "Unchecked External Call",
+            description: "Detects try_ client calls and try_invoke_contract calls whose Result is discarded",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["external-call", "error-handling"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+
+        contract
+            .external_calls()
+            .filter(|external| external.is_fallible() && external.is_result_discarded(&contract.source))
+            .map(|external| RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' discards the result of '{}', ignoring a failed call into another contract",
+                    external.function.name, external.call.name
+                ),
+                suggestion: "Match on the returned Result and handle or propagate the error, or call the non-try method to trap on failure".to_string(),
+                line_number: external.span().start_line,
+                column_number: external.span().start_column,
+                span: external.span(),
+                variable_name: external.function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            })
+            .collect()
+    }
+}
+
+/// Rule for detecting storage writes made after a call into another contract
+///
+/// Soroban rejects re-entrant calls into the same contract, but the callee still
+/// runs against state the caller has not finished updating and can call other
+/// contracts that read it. Writing state before making external calls
+/// (checks-effects-interactions) avoids both.
+#[derive(Default)]
+pub struct StateWriteAfterExternalCallRule;
+
+impl Rule for StateWriteAfterExternalCallRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-state-write-after-external-call",
+            name: "State Write After External Call",
+            description: "Detects entry points that write storage after calling into another contract",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["external-call", "reentrancy", "storage"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let graph = CallGraph::build(contract);
+        let mut violations = Vec::new();
+
+        for function in graph.entry_points() {
+            let own: Vec<_> = ExternalCall::find(function)
+                .into_iter()
+                .filter(|external| !external.is_read_only())
+                .collect();
+            // Calls into other contracts, made directly or through a helper
+            let Some(external) = function
+                .body
+                .calls
+                .iter()
+                .filter(|call| {
+                    own.iter().any(|external| std::ptr::eq(external.call, *call))
+                        || graph.resolve(function, call).is_some_and(|helper| graph.reaches_external_call(helper))
+                })
+                .min_by_key(|call| call.span.end_byte)
+            else {
+                continue;
+            };
+
+            let writes = function
+                .body
+                .storage_accesses
+                .iter()
+                .filter(|access| access.operation.is_write())
+                .map(|access| access.span)
+                .chain(
+                    function
+                        .body
+                        .calls
+                        .iter()
+                        .filter(|call| {
+                            graph.resolve(function, call).is_some_and(|helper| graph.reaches_storage_write(helper))
+                        })
+                        .map(|call| call.span),
+                );
+            let Some(write) = writes
+                .filter(|span| span.start_byte >= external.span.end_byte)
+                .min_by_key(|span| span.start_byte)
+            else {
+                continue;
+            };
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' writes storage after calling '{}' on line {}",
+                    function.name, external.name, external.span.start_line
+                ),
+                suggestion: "Update storage before calling into other contracts (checks-effects-interactions)".to_string(),
+                line_number: write.start_line,
+                column_number: write.start_column,
+                span: write,
+                variable_name: function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting token transfers out of an address the entry point never
+/// authorizes
+///
+/// Transfers out of the contract's own balance are covered by
+/// `MissingAuthRule` instead.
+#[derive(Default)]
+pub struct TransferFromUnauthenticatedRule;
+
+impl Rule for TransferFromUnauthenticatedRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-transfer-from-unauthenticated",
+            name: "Transfer From Unauthenticated Address",
+            description: "Detects token transfers and burns whose source address is not the one the entry point authorizes with require_auth()",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Soroban],
+            tags: &["access-control", "auth", "external-call"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for flow in AuthFlow::analyze_contract(contract) {
+            for effect in &flow.effects {
+                let EffectKind::TokenTransfer { from, .. } = &effect.kind else { continue };
+                if from.is_current_contract() || flow.authorizes(from) {
+                    continue;
+                }
+
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("'{}' {} without authorizing it", flow.function.name, effect.describe()),
+                    suggestion: "Call require_auth() on the address funds move out of, or transfer from the authenticated caller".to_string(),
+                    line_number: effect.span.start_line,
+                    column_number: effect.span.start_column,
+                    span: effect.span,
+                    variable_name: flow.function.name.clone(),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting calls into other contracts made on every loop iteration
+///
+/// Each cross-contract call pays for a new frame, argument conversion and the
+/// callee's own footprint.
+#[derive(Default)]
+pub struct ExternalCallInLoopRule;
+
+impl Rule for ExternalCallInLoopRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-external-call-in-loop",
+            name: "External Call In Loop",
+            description: "Detects loops that call into another contract on each iteration, directly or through a helper",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["external-call", "loops"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let graph = CallGraph::build(contract);
+        let mut violations = Vec::new();
+
+        for function in contract.all_functions() {
+            let own = ExternalCall::find(function);
+            let direct = |call: &Call| own.iter().any(|external| std::ptr::eq(external.call, call));
+            let reaches = |f: &SorobanFunction| ExternalCall::find(f).iter().any(|external| !external.is_read_only());
+            for (call, via) in graph.calls_in_loops(function, direct, reaches) {
+                let what = match via {
+                    Some(helper) => format!("calls into another contract through '{}'", helper.name),
+                    None => format!("calls '{}' on another contract", call.name),
+                };
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("'{}' {} on every loop iteration", function.name, what),
+                    suggestion: "Batch the work into a single call that takes a Vec, or move the call out of the loop".to_string(),
+                    line_number: call.span.start_line,
+                    column_number: call.span.start_column,
+                    span: call.span,
+                    variable_name: function.name.clone(),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting privileged entry points that change state without
+/// publishing an event
+///
+/// An entry point counts as privileged when it authorizes an address loaded from
+/// storage, such as the admin, or its name marks it as an admin operation.
+#[derive(Default)]
+pub struct AdminMissingEventRule;
+
+impl AdminMissingEventRule {
+    /// Name fragments of admin operations
+    const ADMIN_NAMES: &'static [&'static str] = &["admin", "owner", "pause", "emergency", "upgrade", "config", "fee", "role"];
+}
+
+impl Rule for AdminMissingEventRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-admin-missing-event",
+            name: "Admin Action Without Event",
+            description: "Detects admin entry points that change contract state without publishing an event",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["events", "access-control"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let graph = CallGraph::build(contract);
+        let mut violations = Vec::new();
+
+        for flow in AuthFlow::analyze_contract(contract) {
+            let function = flow.function;
+            let admin = flow.authorizes_stored() || Self::ADMIN_NAMES.iter().any(|name| function.name.contains(name));
+            if function.is_constructor || !admin {
+                continue;
+            }
+            let changes_state =
+                graph.reaches_storage_write(function) || graph.reaches(function, |f| f.body.assigns_self_field());
+            if !changes_state || graph.reaches(function, publishes_event) {
+                continue;
+            }
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!("Admin function '{}' changes contract state without publishing an event", function.name),
+                suggestion: "Publish an event with env.events().publish(..) or a #[contractevent] type so indexers and monitoring see the change".to_string(),
+                line_number: function.signature_span.start_line,
+                column_number: function.signature_span.start_column,
+                span: function.signature_span,
+                variable_name: function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting events published on every loop iteration
+///
+/// Every event adds to the transaction's contract events size, which is
+/// charged per byte; one event summarizing the batch is usually enough.
+#[derive(Default)]
+pub struct EventInLoopRule;
+
+impl Rule for EventInLoopRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-event-in-loop",
+            name: "Event Published In Loop",
+            description: "Detects loops that publish an event on each iteration, directly or through a helper",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["events", "loops"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let graph = CallGraph::build(contract);
+        let mut violations = Vec::new();
+
+        for function in contract.all_functions() {
+            let emissions = EventEmission::find(contract, function);
+            let direct = |call: &Call| emissions.iter().any(|emission| std::ptr::eq(emission.call, call));
+            for (call, via) in graph.calls_in_loops(function, direct, publishes_event) {
+                let what = match via {
+                    Some(helper) => format!("publishes an event through '{}'", helper.name),
+                    None => "publishes an event".to_string(),
+                };
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("'{}' {} on every loop iteration", function.name, what),
+                    suggestion: "Publish one event for the whole batch after the loop".to_string(),
+                    line_number: call.span.start_line,
+                    column_number: call.span.start_column,
+                    span: call.span,
+                    variable_name: function.name.clone(),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting events carrying values that grow with their contents
+///
+/// `String`, `Vec`, `Bytes` and `Map` values make the event size, and the fee
+/// charged for it, depend on input; as topics they also bloat every index entry.
+#[derive(Default)]
+pub struct OversizedEventPayloadRule;
+
+impl Rule for OversizedEventPayloadRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-oversized-event-payload",
+            name: "Oversized Event Payload",
+            description: "Detects events whose topics or data include String, Vec, Bytes or Map values",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["events"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for emission in contract.event_emissions() {
+            let unbounded = emission.unbounded_values();
+            if unbounded.is_empty() {
+                continue;
+            }
+            let values: Vec<String> =
+                unbounded.iter().map(|(value, type_name)| format!("'{}' ({})", value, type_name)).collect();
+            let in_topics = unbounded.iter().any(|(value, _)| emission.topics.contains(value));
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "Event published in '{}' carries unbounded values{}: {}",
+                    emission.function.name,
+                    if in_topics { ", some as topics" } else { "" },
+                    values.join(", ")
+                ),
+                suggestion: "Publish identifiers, counts or hashes instead of whole strings and collections".to_string(),
+                line_number: emission.span().start_line,
+                column_number: emission.span().start_column,
+                span: emission.span(),
+                variable_name: emission.function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        for event in &contract.events {
+            for field in event.fields.iter().filter(|field| is_unbounded(&field.type_name)) {
+                let role = if event.topic_fields.contains(&field.name) { "topic" } else { "field" };
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!("Event '{}' has unbounded {} '{}' of type {}", event.name, role, field.name, field.type_name),
+                    suggestion: "Publish identifiers, counts or hashes instead of whole strings and collections".to_string(),
+                    line_number: field.span.start_line,
+                    column_number: field.span.start_column,
+                    span: field.span,
+                    variable_name: format!("{}::{}", event.name, field.name),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting the same event published with different topic counts
+///
+/// Indexers match events on their topics, so an event whose topic list changes
+/// shape between call sites is missed by filters written for one of them.
+#[derive(Default)]
+pub struct InconsistentEventTopicsRule;
+
+impl Rule for InconsistentEventTopicsRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-inconsistent-event-topics",
+            name: "Inconsistent Event Topics",
+            description: "Detects events with the same name topic published with a different number of topics",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::BestPractice,
+            languages: &[Language::Soroban],
+            tags: &["events"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut first: HashMap<String, EventEmission<'_>> = HashMap::new();
+        let mut violations = Vec::new();
+
+        // `#[contractevent]` types fix their topics in the type definition
+        for emission in contract.event_emissions().filter(|emission| emission.event_type.is_none()) {
+            let Some(name) = emission.name.clone() else { continue };
+            let Some(shape) = first.get(&name) else {
+                first.insert(name, emission);
+                continue;
+            };
+            if shape.topics.len() == emission.topics.len() {
+                continue;
+            }
+
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "Event '{}' is published with {} topics in '{}' but {} topics in '{}' on line {}",
+                    name,
+                    emission.topics.len(),
+                    emission.function.name,
+                    shape.topics.len(),
+                    shape.function.name,
+                    shape.span().start_line
+                ),
+                suggestion: "Publish every occurrence of an event with the same topics, moving optional values into the data".to_string(),
+                line_number: emission.span().start_line,
+                column_number: emission.span().start_column,
+                span: emission.span(),
+                variable_name: name,
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting loops that read a `soroban_sdk::Vec` by index
+///
+/// Each `get(i)` on a host `Vec` is a separate host call with its own bounds
+/// check; iterating hands out the elements in one pass.
+#[derive(Default)]
+pub struct VecIndexInLoopRule;
+
+impl Rule for VecIndexInLoopRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-vec-get-in-loop",
+            name: "Vec Indexed In Loop",
+            description: "Detects loops that read a soroban_sdk::Vec with get(i) on each iteration instead of iterating over it",
+            default_severity: ViolationSeverity::Medium,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["collections", "loops"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for function in contract.all_functions() {
+            for lp in &function.body.loops {
+                // The index is the loop variable, or a counter in a `while` condition
+                let indexes = |arg: &str| {
+                    let index = root_name(arg);
+                    match (&lp.pattern, &lp.header) {
+                        (Some(pattern), _) => pattern.trim_start_matches("mut ") == index,
+                        (None, Some(header)) => !index.is_empty() && header.split(|c: char| !(c.is_alphanumeric() || c == '_')).any(|word| word == index),
+                        (None, None) => false,
+                    }
+                };
+                let indexed = function.body.calls.iter().find(|call| {
+                    call.kind == CallKind::Method
+                        && matches!(call.name.as_str(), "get" | "get_unchecked")
+                        && lp.span.contains(&call.span)
+                        && call.args.len() == 1
+                        && indexes(&call.args[0])
+                        && call.receiver.as_deref().is_some_and(|receiver| {
+                            value_type(function, receiver, call.span).is_some_and(|type_name| base_type(&type_name) == "Vec")
+                        })
+                });
+                let Some(call) = indexed else { continue };
+                let receiver = call.receiver.as_deref().unwrap_or_default().trim_start_matches('&');
+
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!(
+                        "'{}' reads '{}' with {}({}) on every loop iteration, a separate host call each time",
+                        function.name, receiver, call.name, call.args[0]
+                    ),
+                    suggestion: format!("Iterate with `for item in {}.iter()` instead of indexing", receiver),
+                    line_number: call.span.start_line,
+                    column_number: call.span.start_column,
+                    span: call.span,
+                    variable_name: receiver.to_string(),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting a whole `soroban_sdk::Map` stored as one storage entry
+///
+/// Reading or writing any entry of the map loads and rewrites all of them, and
+/// the entry's size, and with it the fee, grows with every key added.
+#[derive(Default)]
+pub struct StoredMapRule;
+
+impl Rule for StoredMapRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-map-in-storage",
+            name: "Map Stored As One Entry",
+            description: "Detects a soroban_sdk::Map written to a single storage entry where a keyed entry per element would be cheaper",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["collections", "storage"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for function in contract.all_functions() {
+            for access in &function.body.storage_accesses {
+                if access.operation != StorageOperation::Set {
+                    continue;
+                }
+                let Some(value) = access.args.get(1) else { continue };
+                let is_map = value_type(function, value, access.span).is_some_and(|type_name| base_type(&type_name) == "Map");
+                if !is_map {
+                    continue;
+                }
+                let key = StorageKey::resolve(function, access).map_or_else(|| "?".to_string(), |key| key.name);
+
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!(
+                        "'{}' stores the Map '{}' as a single {} entry under '{}'; every access loads and rewrites all of its entries",
+                        function.name,
+                        value.trim_start_matches('&'),
+                        access.tier.method(),
+                        key
+                    ),
+                    suggestion: "Store each element under its own key, e.g. DataKey::Entry(id), so an access touches only the entry it needs".to_string(),
+                    line_number: access.span.start_line,
+                    column_number: access.span.start_column,
+                    span: access.span,
+                    variable_name: key,
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting values converted from `Bytes` to `String` and back, or
+/// the other way around, within one function
+#[derive(Default)]
+pub struct BytesStringRoundTripRule;
+
+impl BytesStringRoundTripRule {
+    /// The type a call converts into, `String` or `Bytes`, if it converts between the two
+    fn conversion(function: &SorobanFunction, call: &Call) -> Option<&'static str> {
+        let typed = |expression: &str, expected: &str| {
+            value_type(function, root_name(expression), call.span).is_some_and(|type_name| base_type(&type_name) == expected)
+        };
+        let any_arg = |expected: &str| call.args.iter().any(|arg| typed(arg, expected));
+        let receiver = |expected: &str| call.receiver.as_deref().is_some_and(|receiver| typed(receiver, expected));
+
+        match call.kind {
+            CallKind::Function if call.path.ends_with("String::from_bytes") => Some("String"),
+            CallKind::Function if call.path.ends_with("String::from_slice") || call.path.ends_with("String::from_str") => {
+                any_arg("Bytes").then_some("String")
+            }
+            CallKind::Function if call.path.ends_with("Bytes::from_slice") || call.path.ends_with("Bytes::from") => {
+                any_arg("String").then_some("Bytes")
+            }
+            CallKind::Method if call.name == "to_string" && receiver("Bytes") => Some("String"),
+            CallKind::Method if matches!(call.name.as_str(), "to_bytes" | "to_xdr" | "copy_into_slice") && receiver("String") => Some("Bytes"),
+            _ => None,
+        }
+    }
+}
+
+impl Rule for BytesStringRoundTripRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-bytes-string-roundtrip",
+            name: "Bytes/String Round Trip",
+            description: "Detects functions converting soroban_sdk::Bytes to String and back, copying the value through the host each way",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["collections", "strings"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for function in contract.all_functions() {
+            let mut conversions: Vec<(&Call, &str)> = function
+                .body
+                .calls
+                .iter()
+                .filter_map(|call| Some((call, Self::conversion(function, call)?)))
+                .collect();
+            conversions.sort_by_key(|(call, _)| call.span.start_byte);
+            let Some((first, into)) = conversions.first() else { continue };
+            let Some((back, _)) = conversions.iter().find(|(_, other)| other != into) else { continue };
+
+            let from = if *into == "String" { "Bytes" } else { "String" };
+            violations.push(RuleViolation {
+                rule_name: self.id().to_string(),
+                description: format!(
+                    "'{}' converts {} to {} on line {} and back on line {}, copying the value through the host each way",
+                    function.name, from, into, first.span.start_line, back.span.start_line
+                ),
+                suggestion: "Keep the value in one type, Bytes for raw data or String for text, and convert it at most once".to_string(),
+                line_number: back.span.start_line,
+                column_number: back.span.start_column,
+                span: back.span,
+                variable_name: function.name.clone(),
+                severity: self.severity(),
+                fix: None,
+            });
+        }
+
+        violations
+    }
+}
+
+/// Rule for detecting a `Vec<Address>` built in a loop and passed to another
+/// contract
+///
+/// The vector's size, and with it the cost of the call and of the callee's
+/// work, grows with the number of addresses collected.
+#[derive(Default)]
+pub struct AddressVecAcrossContractsRule;
+
+impl Rule for AddressVecAcrossContractsRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "soroban-address-vec-external-call",
+            name: "Address Vec Passed To Contract",
+            description: "Detects a Vec<Address> grown in a loop and then passed across a contract boundary",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Soroban],
+            tags: &["collections", "external-call"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.soroban() else {
+            return Vec::new();
+        };
+        let mut violations = Vec::new();
+
+        for function in contract.all_functions() {
+            let body = &function.body;
+            for external in ExternalCall::find(function) {
+                for arg in &external.call.args {
+                    let name = root_name(arg);
+                    let pushes: Vec<&Call> = body
+                        .calls
+                        .iter()
+                        .filter(|call| {
+                            call.kind == CallKind::Method
+                                && matches!(call.name.as_str(), "push_back" | "push_front" | "append" | "insert")
+                                && call.receiver.as_deref().is_some_and(|receiver| root_name(receiver) == name)
+                                && call.span.end_byte <= external.span().start_byte
+                                && body.loops.iter().any(|lp| lp.span.contains(&call.span))
+                        })
+                        .collect();
+                    if name.is_empty() || pushes.is_empty() {
+                        continue;
+                    }
+
+                    let declared = value_type(function, name, external.span()).unwrap_or_default();
+                    let holds_addresses = declared.replace(' ', "").contains("Vec<Address>")
+                        || (base_type(&declared) == "Vec"
+                            && pushes.iter().any(|push| {
+                                push.args.iter().any(|value| {
+                                    value_type(function, root_name(value), push.span).is_some_and(|type_name| base_type(&type_name) == "Address")
+                                })
+                            }));
+                    if !holds_addresses {
+                        continue;
+                    }
+
+                    let span = external.span();
+                    violations.push(RuleViolation {
+                        rule_name: self.id().to_string(),
+                        description: format!(
+                            "'{}' builds the Vec<Address> '{}' in a loop and passes it to '{}' on another contract; the call's cost grows with every address",
+                            function.name, name, external.call.name
+                        ),
+                        suggestion: "Pass a bounded page of addresses per call, or let the callee read them from its own storage by key".to_string(),
+                        line_number: span.start_line,
+                        column_number: span.start_column,
+                        span,
+                        variable_name: name.to_string(),
+                        severity: self.severity(),
+                        fix: None,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod issue_tests {
+    use super::*;
+    
+    #[test]
+    fn test_claim_expiration_rule() {
+        let source = r#"
+use soroban_sdk::{contract, contractimpl, Env, Address};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn claim_reward(env: Env, user: Address) {
+        // ❌ Missing expiration check
+        let reward = 100;
+        env.storage().instance().set(&user, &reward);
+    }
+    
+    pub fn secure_claim(env: Env, deadline: u64) {
+        // ✅ Has expiration check
+        if env.ledger().timestamp() > deadline {
+            panic!("Expired");
+        }
+    }
+}
+"#;
+        let rule = ClaimExpirationRule;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+        let violations = rule.check(&RuleInput::Soroban(&contract));
+        
+        // Should find one violation for claim_reward
+        assert!(violations.iter().any(|v| v.variable_name == "claim_reward"));
+        // Should NOT find violation for secure_claim
+        assert!(!violations.iter().any(|v| v.variable_name == "secure_claim"));
+    }
+
+    #[test]
+    fn test_secure_randomness_rule() {
+        let source = r#"
+use soroban_sdk::{contract, contractimpl, Env};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn roll_dice(env: Env) -> u32 {
+        // ❌ Insecure randomness
+        let seed = env.ledger().timestamp();
+        (seed % 6) + 1
+    }
+}
+"#;
+        let rule = SecureRandomnessRule;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+        let violations = rule.check(&RuleInput::Soroban(&contract));
+        assert!(!violations.is_empty());
+    }
+
+    #[test]
+    fn test_violation_spans_point_at_trigger() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Env, String};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn label(env: Env, id: u32) -> String {
+        let text = format!("item-{}", id);
+        String::from_str(&env, &text)
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+
+        let violations = ExpensiveStringOperationsRule.check(&RuleInput::Soroban(&contract));
+        assert_eq!(violations.len(), 1);
+        let span = violations[0].span;
+        assert_eq!((span.start_line, span.start_column), (9, 20));
+        assert_eq!(span.snippet(source), "format!(\"item-{}\", id)");
+        assert_eq!(violations[0].line_number, 9);
+        assert_eq!(violations[0].column_number, 20);
+
+        // Contract-wide findings point at the contract declaration
+        let violations = UpgradeVersionTrackingRule.check(&RuleInput::Soroban(&contract));
+        assert_eq!(violations[0].span.start_line, 4);
+        assert_eq!(violations[0].span.snippet(source), "pub struct MyContract;");
+    }
+
+    #[test]
+    fn test_rules_ignore_comments_strings_and_identifiers() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, symbol_short, Env, Map, Symbol};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn format_date(env: Env, days: Map<u32, u32>) -> Symbol {
+        // for each day: .to_string() then loop { .get( .set( }
+        let note = "while String::from( .get(";
+        let first = days.get(1);
+        let second = days.get(2);
+        let third = days.get(3);
+        let fourth = days.get(4);
+        symbol_short!("date")
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+
+        assert!(UnboundedLoopRule.check(&RuleInput::Soroban(&contract)).is_empty());
+        assert!(ExpensiveStringOperationsRule.check(&RuleInput::Soroban(&contract)).is_empty());
+        // Map lookups are not ledger storage accesses
+        assert!(InefficientStorageAccessRule.check(&RuleInput::Soroban(&contract)).is_empty());
+    }
+
+    #[test]
+    fn test_storage_and_loop_rules_use_body_model() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Env, Symbol};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn churn(env: Env, key: Symbol) {
+        let a: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        let b: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &(a + b));
+        env.storage().persistent().set(&key, &a);
+        for i in 0..a {
+            env.storage().temporary().remove(&i);
+        }
+        while env.storage().instance().has(&key) {
+            env.storage().instance().remove(&key);
+        }
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+
+        let violations = InefficientStorageAccessRule.check(&RuleInput::Soroban(&contract));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].description.contains("reads instance key 'key' again on line 10"));
+        assert!(violations[0].fix.is_some());
+
+        // The for loop runs up to a stored value; the while loop compares no counter
+        let violations = UnboundedLoopRule.check(&RuleInput::Soroban(&contract));
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].line_number, 13);
+        assert!(violations[0].description.contains("loaded from storage under 'key'"));
+        assert!(matches!(violations[0].severity, ViolationSeverity::High));
+        assert_eq!(violations[1].line_number, 16);
+        assert_eq!(violations[1].span.snippet(source), "while env.storage().instance().has(&key)");
+    }
+
+    #[test]
+    fn test_reads_of_distinct_keys_are_not_redundant() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Env};
+
+#[contract]
+pub struct Pool;
+
+#[contractimpl]
+impl Pool {
+    pub fn quote(env: Env) -> i128 {
+        let reserve_a: i128 = env.storage().instance().get(&DataKey::ReserveA).unwrap();
+        let reserve_b: i128 = env.storage().instance().get(&DataKey::ReserveB).unwrap();
+        let fee: i128 = env.storage().instance().get(&DataKey::Fee).unwrap();
+        let fee_cap: i128 = env.storage().persistent().get(&DataKey::Fee).unwrap();
+        reserve_a * reserve_b / fee.max(fee_cap)
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "pool.rs").unwrap();
+        assert!(InefficientStorageAccessRule.check(&RuleInput::Soroban(&contract)).is_empty());
+    }
+
+    #[test]
+    fn test_inline_suppressions() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Env};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn drain(env: Env) {
+        // gasguard-ignore: soroban-unbounded-loop
+        loop {
+            env.events().publish((1,), 2);
+        }
+    }
+
+    // gasguard-ignore: soroban-expensive-strings
+    pub fn quiet(env: Env) {}
+}
+"#;
+        let mut engine = SorobanRuleEngine::new();
+        engine.add_rule(UnboundedLoopRule);
+        let report = engine.analyze_with_suppressions(source, "test.rs").unwrap();
+
+        assert_eq!(report.suppressed_count(), 1);
+        assert_eq!(report.suppressed[0].rule_name, "soroban-unbounded-loop");
+        let unused: Vec<_> = report.violations
+            .iter()
+            .filter(|v| v.rule_name == crate::UNUSED_SUPPRESSION_RULE)
+            .collect();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].line_number, 15);
+        assert!(!report.violations.iter().any(|v| v.rule_name == "soroban-unbounded-loop"));
+    }
+
+    #[test]
+    fn test_redundant_clone_rule() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Env, Vec};
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn duplicate(env: Env, data: Vec<i32>, keep: Vec<i32>) -> Vec<i32> {
+        let copy = data.clone();
+        let other = keep.clone();
+        env.storage().instance().set(&1, &keep);
+        copy
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+        let violations = RedundantCloneRule.check(&RuleInput::Soroban(&contract));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].variable_name, "data");
+        let fix = violations[0].fix.as_ref().unwrap();
+        assert!(fix.is_machine_applicable());
+        let fixed = crate::apply_edits(source, &fix.edits).unwrap();
+        assert!(fixed.contains("let copy = data;"));
+    }
+
+    #[test]
+    fn test_unused_field_fix_removes_declaration() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, contracttype};
+
+#[contracttype]
+pub struct Config {
+    pub owner: u32,
+    pub legacy_flag: bool,
+}
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn owner(config: Config) -> u32 {
+        config.owner
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+        let violations = UnusedStateVariablesRule.check(&RuleInput::Soroban(&contract));
+
+        assert_eq!(violations.len(), 1);
+        let fix = violations[0].fix.as_ref().unwrap();
+        assert!(!fix.is_machine_applicable());
+        let fixed = crate::apply_edits(source, &fix.edits).unwrap();
+        assert!(fixed.contains("pub struct Config {\n    pub owner: u32,\n}"));
+    }
+
+    #[test]
+    fn test_unused_storage_key_variant() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, contracttype};
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Balance(Address),
+    BalanceLimit,
+}
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn balance(env: Env, id: Address) -> i128 {
+        let _admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        env.storage().persistent().get(&DataKey::Balance(id)).unwrap_or(0)
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+        let violations = UnusedStateVariablesRule.check(&RuleInput::Soroban(&contract));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].variable_name, "DataKey::BalanceLimit");
+        let fixed = crate::apply_edits(source, &violations[0].fix.as_ref().unwrap().edits).unwrap();
+        assert!(fixed.contains("    Balance(Address),\n}"));
+    }
+
+    #[test]
+    fn test_storage_tier_rules() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Vec};
+
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn deposit(env: Env, user: Address, amount: i128) {
+        env.storage().instance().set(&DataKey::Balance(user.clone()), &amount);
+        let mut users: Vec<Address> = env.storage().instance().get(&DataKey::Users).unwrap();
+        users.push_back(user);
+        env.storage().instance().set(&DataKey::Users, &users);
+    }
+
+    pub fn claim(env: Env, user: Address, nonce: u64) {
+        let key = DataKey::UsedNonce(nonce);
+        env.storage().persistent().set(&key, &true);
+        let balance: i128 = env.storage().persistent().get(&DataKey::Balance(user)).unwrap_or(0);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "vault.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+
+        let growth = InstanceStorageGrowthRule.check(&input);
+        let keys: Vec<_> = growth.iter().map(|v| v.variable_name.as_str()).collect();
+        assert_eq!(keys, vec!["DataKey::Balance", "DataKey::Users"]);
+        let fixed = crate::apply_edits(source, &growth[0].fix.as_ref().unwrap().edits).unwrap();
+        assert!(fixed.contains("env.storage().persistent().set(&DataKey::Balance(user.clone()), &amount);"));
+
+        let short_lived = PersistentShortLivedDataRule.check(&input);
+        assert_eq!(short_lived.len(), 1);
+        assert_eq!(short_lived[0].variable_name, "DataKey::UsedNonce");
+        assert_eq!(short_lived[0].line_number, 17);
+
+        let mismatch = StorageTierMismatchRule.check(&input);
+        assert_eq!(mismatch.len(), 1);
+        assert_eq!(mismatch[0].line_number, 18);
+        assert!(mismatch[0].description.contains("only written to instance storage"));
+        let fixed = crate::apply_edits(source, &mismatch[0].fix.as_ref().unwrap().edits).unwrap();
+        assert!(fixed.contains("env.storage().instance().get(&DataKey::Balance(user))"));
+    }
+
+    #[test]
+    fn test_tier_mismatch_tells_constant_symbol_keys_apart() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Address, Env, Symbol};
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn init(env: Env, admin: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "ADMIN"), &admin);
+        env.storage().persistent().set(&Symbol::new(&env, "COUNT"), &0u32);
+    }
+
+    pub fn admin(env: Env) -> Address {
+        env.storage().persistent().get(&Symbol::new(&env, "ADMIN")).unwrap()
+    }
+
+    pub fn count(env: Env) -> u32 {
+        env.storage().instance().get(&Symbol::new(&env, "COUNT")).unwrap()
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let mismatch = StorageTierMismatchRule.check(&RuleInput::Soroban(&contract));
+
+        let keys: Vec<_> = mismatch.iter().map(|v| (v.variable_name.as_str(), v.line_number)).collect();
+        assert_eq!(keys, vec![("Symbol::new(&env, \"ADMIN\")", 14), ("Symbol::new(&env, \"COUNT\")", 18)]);
+        assert!(mismatch[0].description.contains("only written to instance storage"));
+    }
+
+    #[test]
+    fn test_ttl_rules() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Address, Env, Vec};
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+const BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn register(env: Env, user: Address, name: Symbol) {
+        env.storage().persistent().set(&DataKey::Name(user.clone()), &name);
+        env.storage().persistent().set(&DataKey::Owner(name), &user);
+        env.storage().persistent().extend_ttl(&DataKey::Owner(name), DAY_IN_LEDGERS, BUMP_AMOUNT);
+    }
+
+    pub fn name(env: Env, user: Address) -> Symbol {
+        env.storage().persistent().extend_ttl(&DataKey::Owner(user.clone()), BUMP_AMOUNT - 1, BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Name(user)).unwrap()
+    }
+
+    pub fn touch_all(env: Env, users: Vec<Address>) {
+        for user in users.iter() {
+            env.storage().persistent().extend_ttl(&DataKey::Owner(user), DAY_IN_LEDGERS, BUMP_AMOUNT);
+            env.storage().instance().extend_ttl(DAY_IN_LEDGERS, BUMP_AMOUNT);
+        }
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+
+        let missing = MissingTtlExtensionRule.check(&input);
+        let keys: Vec<_> = missing.iter().map(|v| v.variable_name.as_str()).collect();
+        assert_eq!(keys, vec!["DataKey::Name"]);
+        assert_eq!(missing[0].line_number, 12);
+
+        let mut rule = WastefulTtlExtensionRule::default();
+        let wasteful = rule.check(&input);
+        let lines: Vec<_> = wasteful.iter().map(|v| v.line_number).collect();
+        assert_eq!(lines, vec![18, 25]);
+        assert!(wasteful[0].description.contains("threshold 518399"));
+        assert!(wasteful[1].description.contains("inside a loop"));
+
+        assert!(rule.set_threshold("max_threshold_percent", 100));
+        assert_eq!(rule.check(&input).len(), 1);
+    }
+
+    #[test]
+    fn test_ttl_extension_covers_only_its_own_symbol_key() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Env, Symbol};
+
+#[contract]
+pub struct Counter;
+
+#[contractimpl]
+impl Counter {
+    pub fn set(env: Env, limit: u32, count: u32) {
+        env.storage().persistent().set(&Symbol::new(&env, "LIMIT"), &limit);
+        env.storage().persistent().extend_ttl(&Symbol::new(&env, "LIMIT"), 100, 1000);
+        env.storage().persistent().set(&Symbol::new(&env, "COUNT"), &count);
+    }
+
+    pub fn get(env: Env) -> (u32, u32) {
+        let limit = env.storage().persistent().get(&Symbol::new(&env, "LIMIT")).unwrap();
+        let count = env.storage().persistent().get(&Symbol::new(&env, "COUNT")).unwrap();
+        (limit, count)
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "counter.rs").unwrap();
+        let violations = MissingTtlExtensionRule.check(&RuleInput::Soroban(&contract));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].variable_name, "Symbol::new(&env, \"COUNT\")");
+        assert_eq!(violations[0].line_number, 11);
+        assert!(violations[0].description.contains("'Symbol::new(&env, \"COUNT\")'"));
+    }
+
+    #[test]
+    fn test_error_and_key_rules() {
+        let source = r#"use soroban_sdk::{contract, contracterror, contractimpl, contracttype};
+
+const BASE: u32 = 10;
+
+#[contracterror]
+pub enum Error {
+    NotFound = 1,
+    Unauthorized = 2,
+    Paused = 3,
+}
+
+#[contracterror]
+pub enum AdminError {
+    NotAdmin = BASE,
+    Expired = 2,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Paused,
+}
+
+#[contract]
+pub struct MyContract;
+
+#[contractimpl]
+impl MyContract {
+    pub fn admin(env: Env) -> Result<Address, Error> {
+        if env.storage().instance().has(&DataKey::Paused) {
+            return Err(Error::Unauthorized);
+        }
+        env.storage().instance().get(&DataKey::Admin).ok_or(Error::NotFound)
+    }
+
+    pub fn set_admin(env: Env, admin: Address) -> Result<(), AdminError> {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        Err(AdminError::NotAdmin)
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+
+        let duplicates = DuplicateErrorCodeRule.check(&input);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].variable_name, "AdminError::Expired");
+        assert!(duplicates[0].description.contains("already used by 'Error::Unauthorized'"));
+
+        let unused = UnusedErrorVariantRule.check(&input);
+        let names: Vec<_> = unused.iter().map(|v| v.variable_name.as_str()).collect();
+        assert_eq!(names, vec!["Error::Paused", "AdminError::Expired"]);
+
+        let never_written = StorageKeyNeverWrittenRule.check(&input);
+        assert_eq!(never_written.len(), 1);
+        assert_eq!(never_written[0].variable_name, "DataKey::Paused");
+        assert_eq!(never_written[0].line_number, 30);
+    }
+
+    #[test]
+    fn test_auth_rules() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, token, Address, Env};
+
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("already initialized");
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::check_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn take_admin(env: Env, new_admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn deposit(env: Env, from: Address, token: Address, amount: i128) {
+        let client = token::Client::new(&env, &token);
+        client.transfer(&from, &env.current_contract_address(), &amount);
+        env.storage().persistent().set(&DataKey::Balance(from.clone()), &amount);
+    }
+
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        env.storage().persistent().set(&DataKey::Balance(to.clone()), &0);
+        to.require_auth();
+    }
+
+    fn check_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "vault.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+
+        let missing = MissingAuthRule.check(&input);
+        let found: Vec<_> = missing.iter().map(|v| (v.variable_name.as_str(), v.line_number)).collect();
+        assert_eq!(found, vec![("take_admin", 21), ("deposit", 27)]);
+        assert!(missing[1].description.contains("without 'from.require_auth()'"));
+
+        let late = AuthAfterEffectRule.check(&input);
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].variable_name, "withdraw");
+        assert!(late[0].description.contains("before authorizing parameter 'to' on line 32"));
+    }
+
+    #[test]
+    fn test_external_call_rules() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, token, Address, Env, Vec};
+
+#[contract]
+pub struct Router;
+
+#[contractimpl]
+impl Router {
+    pub fn swap(env: Env, user: Address, pool: Address, token: Address, amount: i128) {
+        user.require_auth();
+        let client = token::Client::new(&env, &token);
+        client.transfer(&user, &pool, &amount);
+        env.storage().persistent().set(&DataKey::Swapped(user.clone()), &amount);
+    }
+
+    pub fn sweep(env: Env, from: Address, to: Address, token: Address) {
+        let client = token::Client::new(&env, &token);
+        let balance = client.balance(&from);
+        client.transfer(&from, &to, &balance);
+    }
+
+    pub fn sync(env: Env, pools: Vec<Address>) {
+        for pool in pools.iter() {
+            PoolClient::new(&env, &pool).try_sync();
+        }
+    }
+
+    pub fn sum(env: Env, token: Address, who: Address) -> u32 {
+        let client = token::Client::new(&env, &token);
+        let mut total = 0;
+        for _ in 0..client.balance(&who) as u32 {
+            total += 1;
+        }
+        total
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "router.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+        let found = |violations: Vec<RuleViolation>| -> Vec<(String, usize)> {
+            violations.into_iter().map(|v| (v.variable_name, v.line_number)).collect()
+        };
+
+        assert_eq!(found(UncheckedExternalCallRule.check(&input)), vec![("sync".to_string(), 23)]);
+        assert_eq!(found(StateWriteAfterExternalCallRule.check(&input)), vec![("swap".to_string(), 12)]);
+        assert_eq!(found(TransferFromUnauthenticatedRule.check(&input)), vec![("sweep".to_string(), 18)]);
+        assert_eq!(found(ExternalCallInLoopRule.check(&input)), vec![("sync".to_string(), 23)]);
+        assert!(MissingAuthRule.check(&input).iter().all(|v| v.variable_name != "sweep"));
+    }
+
+    #[test]
+    fn test_event_rules() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, String, Vec};
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn update_admin(env: Env, new_admin: Address) {
+        Self::check_admin(&env);
+        env.storage().instance().set(&symbol_short!("admin"), &new_admin);
+    }
+
+    pub fn set_fee(env: Env, fee: u32) {
+        Self::check_admin(&env);
+        env.storage().instance().set(&symbol_short!("fee"), &fee);
+        env.events().publish((symbol_short!("fee"), fee), ());
+    }
+
+    pub fn batch(env: Env, keys: Vec<Symbol>, note: String) {
+        for key in keys.iter() {
+            Self::touch(&env, key);
+        }
+        env.events().publish((symbol_short!("batch"),), (keys, note));
+    }
+
+    fn touch(env: &Env, key: Symbol) {
+        env.events().publish((symbol_short!("fee"),), key);
+    }
+
+    fn check_admin(env: &Env) {
+        let admin: Address = env.storage().instance().get(&symbol_short!("admin")).unwrap();
+        admin.require_auth();
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+        let found = |violations: Vec<RuleViolation>| -> Vec<(String, usize)> {
+            violations.into_iter().map(|v| (v.variable_name, v.line_number)).collect()
+        };
+
+        assert_eq!(found(AdminMissingEventRule.check(&input)), vec![("update_admin".to_string(), 8)]);
+        assert_eq!(found(EventInLoopRule.check(&input)), vec![("batch".to_string(), 21)]);
+
+        let oversized = OversizedEventPayloadRule.check(&input);
+        assert_eq!(found(oversized.clone()), vec![("batch".to_string(), 23)]);
+        assert!(oversized[0].description.contains("'keys' (Vec<Symbol>), 'note' (String)"));
+
+        let inconsistent = InconsistentEventTopicsRule.check(&input);
+        assert_eq!(found(inconsistent.clone()), vec![("fee".to_string(), 27)]);
+        assert!(inconsistent[0].description.contains("1 topics in 'touch' but 2 topics in 'set_fee' on line 16"));
+    }
+
+    #[test]
+    fn test_missing_error_handling_audits_panics() {
+        let source = r#"use soroban_sdk::{contract, contracterror, contractimpl, Address, Env};
+
+#[contracterror]
+pub enum Error {
+    NotInitialized = 1,
+}
+
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    pub fn admin(env: Env) -> Result<Address, Error> {
+        Ok(env.storage().instance().get(&DataKey::Admin).unwrap())
+    }
+
+    pub fn pause(env: Env) {
+        panic!("paused");
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "vault.rs").unwrap();
+        let violations = MissingErrorHandlingRule.check(&RuleInput::Soroban(&contract));
+        assert_eq!(violations.len(), 2);
+
+        assert_eq!(violations[0].line_number, 14);
+        assert!(violations[0].description.contains("unwrap() on storage key 'DataKey::Admin'"));
+        assert_eq!(violations[0].suggestion, "Add `AdminNotFound = 2` to Error, then use .ok_or(Error::AdminNotFound)? instead of unwrap()");
+
+        assert_eq!(violations[1].line_number, 18);
+        assert!(violations[1].suggestion.contains("panic_with_error!(&env, Error::Paused)"));
+    }
+
+    #[test]
+    fn test_host_collection_rules() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, Address, Bytes, Env, Map, String, Vec};
+
+#[contract]
+pub struct Airdrop;
+
+#[contractimpl]
+impl Airdrop {
+    pub fn total(env: Env, amounts: Vec<i128>) -> i128 {
+        let mut sum = 0;
+        for i in 0..amounts.len() {
+            sum += amounts.get(i).unwrap();
+        }
+        sum
+    }
+
+    pub fn set_weights(env: Env, weights: Map<Address, u32>) {
+        env.storage().persistent().set(&DataKey::Weights, &weights);
+    }
+
+    pub fn rename(env: Env, raw: Bytes) -> Bytes {
+        let name = String::from_bytes(&env, &raw);
+        name.to_bytes()
+    }
+
+    pub fn notify(env: Env, registry: Address, members: Vec<Address>) {
+        let mut eligible = Vec::new(&env);
+        for member in members.iter() {
+            let user: Address = member;
+            eligible.push_back(user);
+        }
+        RegistryClient::new(&env, &registry).register_all(&eligible);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "airdrop.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+        let found = |violations: Vec<RuleViolation>| -> Vec<(String, usize)> {
+            violations.into_iter().map(|v| (v.variable_name, v.line_number)).collect()
+        };
+
+        assert_eq!(found(VecIndexInLoopRule.check(&input)), vec![("amounts".to_string(), 11)]);
+        assert_eq!(found(StoredMapRule.check(&input)), vec![("DataKey::Weights".to_string(), 17)]);
+        assert_eq!(found(BytesStringRoundTripRule.check(&input)), vec![("rename".to_string(), 22)]);
+        assert_eq!(found(AddressVecAcrossContractsRule.check(&input)), vec![("eligible".to_string(), 31)]);
+        // `Vec::new(&env)` is a host vector with no capacity to reserve
+        assert!(VecWithoutCapacityRule.check(&input).is_empty());
+    }
+
+    #[test]
+    fn test_every_finding_belongs_to_a_registered_rule() {
+        let source = r#"use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String};
+
+#[contracttype]
+pub struct Config {
+    owner: Address,
+    pub label: String,
+    pub supply: i128,
+}
+
+#[contract]
+pub struct Registry;
+
+#[contractimpl]
+impl Registry {
+    pub fn set_owner(env: Env, owner: Address) {
+        let mut history = Vec::new();
+        history.push(owner.clone());
+        let config = Config { owner, label: String::from_str(&env, "main"), supply: 0 };
+        env.storage().instance().set(&DataKey::Config, &config);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "registry.rs").unwrap();
+        let input = RuleInput::Soroban(&contract);
+        let found = |violations: Vec<RuleViolation>| -> Vec<(String, usize)> {
+            violations.into_iter().map(|v| (v.variable_name, v.line_number)).collect()
+        };
+
+        assert_eq!(found(PrivateContractFieldRule.check(&input)), vec![("owner".to_string(), 5)]);
+        assert_eq!(found(StringFieldRule.check(&input)), vec![("label".to_string(), 6)]);
+        assert_eq!(found(VecWithoutCapacityRule.check(&input)), vec![("set_owner".to_string(), 16)]);
+        assert_eq!(found(MissingAddressValidationRule.check(&input)), vec![("set_owner".to_string(), 15)]);
+
+        let engine = SorobanRuleEngine::with_default_rules();
+        let ids: Vec<_> = engine.get_rules().iter().map(|rule| rule.id()).collect();
+        let violations = engine.analyze(source, "registry.rs").unwrap();
+        assert!(violations.iter().all(|v| ids.contains(&v.rule_name.as_str())));
+        // The missing constructor and the large integer are each reported by one rule
+        let constructor: Vec<_> = violations.iter().filter(|v| v.description.contains("constructor")).collect();
+        assert_eq!(constructor.len(), 1);
+        assert_eq!(constructor[0].rule_name, "soroban-missing-constructor");
+        assert_eq!(violations.iter().filter(|v| v.description.contains("uses i128")).count(), 1);
     }
 
     #[test]
@@ -1191,9 +3845,9 @@ impl MyContract {
     pub fn hello() {}
 }
 "#;
-        let rule = UpgradeVersionTrackingRule::default();
+        let rule = UpgradeVersionTrackingRule;
         let contract = SorobanParser::parse_contract(source, "test.rs").unwrap();
-        let violations = rule.apply(&contract);
+        let violations = rule.check(&RuleInput::Soroban(&contract));
         
         // Should find a violation because version is missing
         assert!(violations.iter().any(|v| v.rule_name == "soroban-upgrade-version-tracking"));
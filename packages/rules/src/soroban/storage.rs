@@ -0,0 +1,269 @@
+//! Storage key resolution
+//!
+//! Storage accesses record the key argument as written, which is often a local
+//! such as `key` bound a few lines earlier. This module resolves those bindings
+//! and names each key after the `DataKey` variant or expression it is built
+//! from, so accesses in different functions can be matched up.
+
+use super::events::tuple_elements;
+use super::{SorobanContract, SorobanFunction, SorobanParser, StatementKind, StorageAccess};
+use crate::SourceSpan;
+
+/// The key of a storage access, resolved through local `let` bindings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageKey {
+    /// Stable name of the key: the variant path of an enum key such as
+    /// `DataKey::Balance`, the callee of a key built from runtime values, or
+    /// the whole key expression otherwise
+    pub name: String,
+    /// Key expression after resolving a local binding
+    pub expression: String,
+    /// Function parameters the key is built from
+    pub parameters: Vec<String>,
+}
+
+impl StorageKey {
+    /// Resolve the key of `access`, made in `function`
+    pub fn resolve(function: &SorobanFunction, access: &StorageAccess) -> Option<Self> {
+        Some(Self::resolve_expression(function, access.key.as_deref()?, access.span))
+    }
+
+    /// Resolve a key expression used in `function` at `at`, e.g. an argument
+    /// passed on to a helper
+    pub fn resolve_expression(function: &SorobanFunction, key: &str, at: SourceSpan) -> Self {
+        let key = key.trim_start_matches('&').trim();
+        let expression = Self::binding(function, key, at)
+            .unwrap_or(key)
+            .trim_start_matches('&')
+            .trim()
+            .to_string();
+
+        let code = without_strings(&expression);
+        let parameters = function
+            .params
+            .iter()
+            .filter(|param| !param.type_name.trim_start_matches('&').ends_with("Env"))
+            .filter(|param| identifiers(&code).any(|ident| ident == param.name))
+            .map(|param| param.name.clone())
+            .collect();
+
+        Self {
+            name: Self::name_of(function, &expression),
+            expression,
+            parameters,
+        }
+    }
+
+    /// Whether the key carries data, as in `DataKey::Balance(user)`, so it names
+    /// one entry per distinct value rather than a single entry
+    pub fn is_parameterized(&self) -> bool {
+        let text = SorobanParser::collapse_whitespace(self.expression.trim_end_matches(".clone()"));
+        self.name != text || !self.parameters.is_empty()
+    }
+
+    /// Initializer of the last `let key = ...` before `before`, when `key` is a local
+    fn binding<'a>(function: &'a SorobanFunction, key: &str, before: SourceSpan) -> Option<&'a str> {
+        if identifiers(key).count() != 1 || !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        function
+            .body
+            .statements
+            .iter()
+            .rev()
+            .filter(|statement| statement.span.end_byte <= before.start_byte)
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Let { pattern, init: Some(init), .. }
+                    if pattern.trim_start_matches("mut ") == key =>
+                {
+                    Some(init.as_str())
+                }
+                _ => None,
+            })
+            .next()
+    }
+
+    /// `DataKey::Balance(user)` is named `DataKey::Balance`, and so is a call
+    /// such as `Symbol::new(&env, &name)` built from runtime values; other keys,
+    /// such as `Symbol::new(&env, "ADMIN")`, by their text
+    fn name_of(function: &SorobanFunction, expression: &str) -> String {
+        let expression = expression.trim_end_matches(".clone()");
+        let text = SorobanParser::collapse_whitespace(expression);
+        let Some((path, args)) = expression.split_once('(') else {
+            return text;
+        };
+        if path.is_empty() || !expression.ends_with(')') || !path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') {
+            return text;
+        }
+        // `Foo(a).bar(b)` is not a single call
+        if closing_paren(expression, path.len()) != Some(expression.len() - 1) {
+            return text;
+        }
+        let args = tuple_elements(&format!("({},)", &args[..args.len() - 1]));
+
+        // Tuple variants of key enums are `CamelCase`; constructors and other
+        // functions name one entry only when every argument is a constant
+        let variant = path.rsplit("::").next().is_some_and(|last| last.starts_with(|c: char| c.is_ascii_uppercase()));
+        if variant || !args.iter().all(|arg| is_constant(function, arg)) {
+            path.to_string()
+        } else {
+            text
+        }
+    }
+}
+
+/// Index of the parenthesis closing the one at `open`, skipping string literals
+fn closing_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    for (index, c) in text[open..].char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether a call argument has the same value on every call: a literal, a
+/// `SCREAMING_CASE` constant, or the environment
+fn is_constant(function: &SorobanFunction, arg: &str) -> bool {
+    let arg = arg.trim().trim_start_matches('&').trim();
+    if arg.is_empty() || arg.starts_with('"') || arg.starts_with(|c: char| c.is_ascii_digit()) || arg == "true" || arg == "false" {
+        return true;
+    }
+    if arg.starts_with("symbol_short!(") {
+        return true;
+    }
+    let constant = arg.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == ':')
+        && arg.contains(|c: char| c.is_ascii_uppercase());
+    let env = function
+        .params
+        .iter()
+        .any(|param| param.name == arg && param.type_name.trim_start_matches('&').ends_with("Env"));
+    constant || env
+}
+
+/// A storage access together with the function making it
+#[derive(Debug, Clone, Copy)]
+pub struct KeyedAccess<'a> {
+    pub function: &'a SorobanFunction,
+    pub access: &'a StorageAccess,
+}
+
+impl<'a> KeyedAccess<'a> {
+    pub fn key(&self) -> Option<StorageKey> {
+        StorageKey::resolve(self.function, self.access)
+    }
+}
+
+impl SorobanContract {
+    /// Every contract function, across all `#[contractimpl]` blocks
+    pub fn functions(&self) -> impl Iterator<Item = &SorobanFunction> {
+        self.implementations.iter().flat_map(|implementation| &implementation.functions)
+    }
+
+    /// Every function in the file: contract functions, then methods of other
+    /// impl blocks, then free functions
+    pub fn all_functions(&self) -> impl Iterator<Item = &SorobanFunction> {
+        self.functions()
+            .chain(self.helper_impls.iter().flat_map(|implementation| &implementation.functions))
+            .chain(&self.free_functions)
+    }
+
+    /// Every storage access of every function, contract functions first
+    pub fn storage_accesses(&self) -> impl Iterator<Item = KeyedAccess<'_>> {
+        self.all_functions().flat_map(|function| {
+            function
+                .body
+                .storage_accesses
+                .iter()
+                .map(move |access| KeyedAccess { function, access })
+        })
+    }
+}
+
+/// Location of the tier method, e.g. `persistent` in `env.storage().persistent().get(..)`
+pub fn tier_span(access: &StorageAccess, source: &str) -> Option<SourceSpan> {
+    let snippet = access.span.snippet(source);
+    let storage = snippet.find("storage")?;
+    let method = access.tier.method();
+    let offset = storage + snippet[storage..].find(&format!("{}(", method))?;
+    let start = access.span.start_byte + offset;
+    Some(SourceSpan::from_byte_range(source, start, start + method.len()))
+}
+
+/// Blank out string literals, so `symbol_short!("admin")` does not mention `admin`
+fn without_strings(text: &str) -> String {
+    let mut in_string = false;
+    let mut escaped = false;
+    text.chars()
+        .map(|c| {
+            let blank = in_string;
+            if c == '"' && !escaped {
+                in_string = !in_string;
+            }
+            escaped = in_string && !escaped && c == '\\';
+            if blank {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Identifiers in a snippet of Rust code
+pub(crate) fn identifiers(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| word.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_keys() {
+        let source = r#"
+#[contract]
+pub struct Token;
+
+#[contractimpl]
+impl Token {
+    pub fn transfer(env: Env, from: Address, amount: i128) {
+        let key = DataKey::Balance(from.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance - amount));
+        env.storage().instance().set(&symbol_short!("total"), &amount);
+        env.storage().instance().set(&Symbol::new(&env, "ADMIN"), &from);
+        env.storage().instance().set(&Symbol::new(&env, "COUNT"), &amount);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "token.rs").unwrap();
+        let keys: Vec<_> = contract.storage_accesses().filter_map(|a| a.key()).collect();
+
+        assert_eq!(keys.len(), 5);
+        assert_eq!(keys[0].name, "DataKey::Balance");
+        assert_eq!(keys[0].expression, "DataKey::Balance(from.clone())");
+        assert_eq!(keys[0].parameters, vec!["from"]);
+        assert!(keys[0].is_parameterized());
+        assert_eq!(keys[2].name, "symbol_short!(\"total\")");
+        assert!(!keys[2].is_parameterized());
+        assert_eq!(keys[3].name, "Symbol::new(&env, \"ADMIN\")");
+        assert_eq!(keys[4].name, "Symbol::new(&env, \"COUNT\")");
+
+        let access = contract.storage_accesses().next().unwrap().access;
+        let span = tier_span(access, source).unwrap();
+        assert_eq!(span.snippet(source), "persistent");
+    }
+}
@@ -88,7 +88,7 @@ pub struct Token {
     }
     
     #[test]
-    fn test_soroban_analyzer_basic_checks() {
+    fn test_unused_state_variables_basic_checks() {
         let contract = SorobanContract {
             name: "TestContract".to_string(),
             contract_types: vec![SorobanStruct {
@@ -123,22 +123,21 @@ pub struct TestContract {
             file_path: "test.rs".to_string(),
         };
         
-        let violations = SorobanAnalyzer::analyze_contract(&contract);
+        let violations = UnusedStateVariablesRule.check(&gasguard_rules::RuleInput::Soroban(&contract));
         
         // Should detect unused variable
         let unused_found = violations.iter().any(|v| 
-            v.rule_name == "unused-state-variable" && v.variable_name == "unused_var"
+            v.rule_name == "soroban-unused-state-variables" && v.variable_name == "unused_var"
         );
         assert!(unused_found);
     }
     
     #[test]
     fn test_soroban_rule_engine_unused_variables_rule() {
-        let rule = UnusedStateVariablesRule::default();
-        assert_eq!(rule.id(), "soroban-unused-state-variables");
-        assert_eq!(rule.name(), "Unused State Variables");
+        let rule = UnusedStateVariablesRule;
+        assert_eq!(rule.metadata().id, "soroban-unused-state-variables");
+        assert_eq!(rule.metadata().name, "Unused State Variables");
         assert_eq!(rule.severity(), crate::ViolationSeverity::Warning);
-        assert!(rule.is_enabled());
         
         let contract = SorobanContract {
             name: "Test".to_string(),
@@ -158,7 +157,7 @@ pub struct TestContract {
             file_path: "test.rs".to_string(),
         };
         
-        let violations = rule.apply(&contract);
+        let violations = rule.check(&RuleInput::Soroban(&contract));
         assert!(!violations.is_empty());
         assert_eq!(violations[0].rule_name, "soroban-unused-state-variables");
     }
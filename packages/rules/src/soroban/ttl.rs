@@ -0,0 +1,187 @@
+//! TTL coverage
+//!
+//! Persistent entries and the contract instance are archived once their TTL
+//! runs out, after which every read of them fails until they are restored.
+//! This module matches the entries a contract writes against the `extend_ttl`
+//! (or pre-SDK 20 `bump`) calls that keep them alive.
+
+use super::{CallGraph, KeyedAccess, SorobanContract, SorobanFunction, StorageKey, StorageOperation, StorageTier};
+
+/// Writes, reads and TTL extensions of one archivable storage entry
+#[derive(Debug, Clone)]
+pub struct TtlEntry<'a> {
+    pub tier: StorageTier,
+    /// Name of the persistent key, or `None` for the contract instance, whose
+    /// keys all share a single TTL
+    pub key: Option<String>,
+    pub writes: Vec<KeyedAccess<'a>>,
+    pub reads: Vec<KeyedAccess<'a>>,
+    /// `extend_ttl` calls for the entry in functions reachable from the contract interface
+    pub extensions: Vec<KeyedAccess<'a>>,
+}
+
+impl TtlEntry<'_> {
+    /// Whether the entry is written but nothing ever extends its TTL
+    pub fn is_uncovered(&self) -> bool {
+        !self.writes.is_empty() && self.extensions.is_empty()
+    }
+}
+
+/// TTL coverage of every persistent key and of the contract instance
+#[derive(Debug, Clone)]
+pub struct TtlCoverage<'a> {
+    pub entries: Vec<TtlEntry<'a>>,
+}
+
+impl<'a> TtlCoverage<'a> {
+    pub fn analyze(contract: &'a SorobanContract) -> Self {
+        let graph = CallGraph::build(contract);
+        let reachable = graph.reachable();
+        let is_reachable = |function: &SorobanFunction| reachable.iter().any(|seen| std::ptr::eq(*seen, function));
+        let mut entries: Vec<TtlEntry<'a>> = Vec::new();
+
+        for keyed in contract.storage_accesses() {
+            let access = keyed.access;
+            let key = match access.tier {
+                StorageTier::Temporary => continue,
+                StorageTier::Instance => None,
+                StorageTier::Persistent => match keyed.key() {
+                    Some(key) => Some(key.name),
+                    None => continue,
+                },
+            };
+
+            // A helper extending a key it is passed covers the keys its callers pass
+            if access.operation == StorageOperation::ExtendTtl && access.tier == StorageTier::Persistent {
+                let names = forwarded_keys(&graph, keyed);
+                if !names.is_empty() {
+                    if is_reachable(keyed.function) {
+                        for name in names {
+                            entry_mut(&mut entries, access.tier, Some(name)).extensions.push(keyed);
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            let entry = entry_mut(&mut entries, access.tier, key);
+            if access.operation == StorageOperation::ExtendTtl {
+                if is_reachable(keyed.function) {
+                    entry.extensions.push(keyed);
+                }
+            } else {
+                if access.operation.is_write() && access.operation != StorageOperation::Remove {
+                    entry.writes.push(keyed);
+                }
+                if access.operation.is_read() {
+                    entry.reads.push(keyed);
+                }
+            }
+        }
+
+        Self { entries }
+    }
+
+    /// The entry of a persistent key
+    pub fn persistent(&self, key: &str) -> Option<&TtlEntry<'a>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.tier == StorageTier::Persistent && entry.key.as_deref() == Some(key))
+    }
+
+    /// The entry of the contract instance
+    pub fn instance(&self) -> Option<&TtlEntry<'a>> {
+        self.entries.iter().find(|entry| entry.tier == StorageTier::Instance)
+    }
+}
+
+/// The entry of `key` in `tier`, added if it is not there yet
+fn entry_mut<'e, 'a>(entries: &'e mut Vec<TtlEntry<'a>>, tier: StorageTier, key: Option<String>) -> &'e mut TtlEntry<'a> {
+    match entries.iter().position(|entry| entry.tier == tier && entry.key == key) {
+        Some(index) => &mut entries[index],
+        None => {
+            entries.push(TtlEntry {
+                tier,
+                key,
+                writes: Vec::new(),
+                reads: Vec::new(),
+                extensions: Vec::new(),
+            });
+            entries.last_mut().unwrap()
+        }
+    }
+}
+
+/// Names of the keys callers pass to a function whose storage access uses a
+/// key parameter, e.g. `DataKey::Balance` for `Self::bump(&env, &DataKey::Balance(id))`
+fn forwarded_keys(graph: &CallGraph<'_>, keyed: KeyedAccess<'_>) -> Vec<String> {
+    let Some(key) = keyed.access.key.as_deref() else {
+        return Vec::new();
+    };
+    let key = key.trim_start_matches('&').trim();
+    let Some(index) = keyed.function.params.iter().position(|param| param.name == key) else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    for (caller, call) in graph.callers(keyed.function) {
+        if let Some(arg) = call.args.get(index) {
+            let name = StorageKey::resolve_expression(caller, arg, call.span).name;
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soroban::{SorobanParser, TtlCoverage};
+
+    #[test]
+    fn test_ttl_coverage_follows_helpers() {
+        let source = r#"
+#[contract]
+pub struct Token;
+
+#[contractimpl]
+impl Token {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let key = DataKey::Balance(to);
+        env.storage().persistent().set(&key, &amount);
+        Self::bump_balance(&env, &key);
+        env.storage().instance().set(&DataKey::Supply, &amount);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn set_name(env: Env, name: String) {
+        env.storage().persistent().set(&DataKey::Name, &name);
+    }
+
+    fn bump_balance(env: &Env, key: &DataKey) {
+        env.storage().persistent().extend_ttl(key, 100, 1000);
+    }
+
+    fn unused(env: &Env) {
+        env.storage().instance().extend_ttl(100, 1000);
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "token.rs").unwrap();
+        let coverage = TtlCoverage::analyze(&contract);
+
+        let balance = coverage.persistent("DataKey::Balance").unwrap();
+        assert_eq!(balance.writes.len(), 1);
+        assert_eq!(balance.reads.len(), 1);
+        assert_eq!(balance.extensions.len(), 1);
+        assert!(!balance.is_uncovered());
+
+        assert!(coverage.persistent("DataKey::Name").unwrap().is_uncovered());
+        // The only instance extension is in a helper nothing calls
+        assert!(coverage.instance().unwrap().is_uncovered());
+    }
+}
@@ -0,0 +1,74 @@
+//! Source locations
+//!
+//! Provides the span type shared by the language frontends to point at an exact
+//! region of the analyzed source file.
+
+use serde::{Deserialize, Serialize};
+
+/// A region of source code.
+///
+/// Lines and columns are 1-based, columns count characters, and the end
+/// position is exclusive. Byte offsets index directly into the source text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl SourceSpan {
+    /// Build a span covering a single syn/proc-macro2 span
+    pub fn from_span(span: proc_macro2::Span) -> Self {
+        Self::between(span, span)
+    }
+
+    /// Build a span starting at the start of `start` and ending at the end of `end`
+    pub fn between(start: proc_macro2::Span, end: proc_macro2::Span) -> Self {
+        let (from, to) = (start.start(), end.end());
+        Self {
+            start_line: from.line,
+            start_column: from.column + 1,
+            end_line: to.line,
+            end_column: to.column + 1,
+            start_byte: start.byte_range().start,
+            end_byte: end.byte_range().end,
+        }
+    }
+
+    /// Build a span from byte offsets into `source`
+    pub fn from_byte_range(source: &str, start_byte: usize, end_byte: usize) -> Self {
+        let (start_line, start_column) = Self::line_column(source, start_byte);
+        let (end_line, end_column) = Self::line_column(source, end_byte);
+        Self {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            start_byte,
+            end_byte,
+        }
+    }
+
+    /// The source text covered by this span
+    pub fn snippet<'a>(&self, source: &'a str) -> &'a str {
+        source.get(self.start_byte..self.end_byte).unwrap_or("")
+    }
+
+    /// Whether `other` lies entirely within this span
+    pub fn contains(&self, other: &SourceSpan) -> bool {
+        self.start_byte <= other.start_byte && other.end_byte <= self.end_byte
+    }
+
+    /// 1-based line and character column of a byte offset
+    fn line_column(source: &str, byte: usize) -> (usize, usize) {
+        let before = source.get(..byte).unwrap_or(source);
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+}
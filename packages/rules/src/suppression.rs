@@ -0,0 +1,230 @@
+//! Inline suppression comments
+//!
+//! Lets contract authors silence known-acceptable findings from the source itself:
+//!
+//! - `// gasguard-ignore: <rule-id>` suppresses the rule on the next line of code
+//! - `// gasguard-ignore-file: <rule-id>` suppresses the rule for the whole file
+//!
+//! Several rule IDs may be listed separated by commas. Vyper sources use `#`
+//! comments instead of `//`. Suppressions that match no finding are reported as
+//! `unused-suppression` violations so stale directives don't accumulate.
+
+use crate::{RuleViolation, SourceSpan, ViolationSeverity};
+use serde::{Deserialize, Serialize};
+
+/// Rule name of the violation reported for a suppression that matched nothing
+pub const UNUSED_SUPPRESSION_RULE: &str = "unused-suppression";
+
+const IGNORE_DIRECTIVE: &str = "gasguard-ignore:";
+const IGNORE_FILE_DIRECTIVE: &str = "gasguard-ignore-file:";
+
+/// Where a suppression applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionScope {
+    /// Lines between the directive and the next line of code, inclusive
+    Lines { first: usize, last: usize },
+    /// The whole file
+    File,
+}
+
+/// A single rule ID named by a suppression comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    pub rule_id: String,
+    pub scope: SuppressionScope,
+    /// Location of the rule ID in the comment
+    pub span: SourceSpan,
+}
+
+impl Suppression {
+    fn matches(&self, violation: &RuleViolation) -> bool {
+        violation.rule_name == self.rule_id
+            && match self.scope {
+                SuppressionScope::Lines { first, last } => {
+                    (first..=last).contains(&violation.line_number)
+                }
+                SuppressionScope::File => true,
+            }
+    }
+}
+
+/// Violations split by whether a suppression comment silenced them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SuppressionReport {
+    /// Active findings, including any unused suppressions
+    pub violations: Vec<RuleViolation>,
+    /// Findings silenced by a suppression comment
+    pub suppressed: Vec<RuleViolation>,
+}
+
+impl SuppressionReport {
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed.len()
+    }
+}
+
+/// Suppression directives found in a source file
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    directives: Vec<Suppression>,
+}
+
+impl Suppressions {
+    /// Collect the directives in `source`, whose line comments start with `comment_prefix`
+    pub fn parse(source: &str, comment_prefix: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for line in source.split_inclusive('\n') {
+            lines.push((offset, line.trim_end_matches(['\n', '\r'])));
+            offset += line.len();
+        }
+
+        let is_code = |line: &str| {
+            let line = line.trim_start();
+            !line.is_empty() && !line.starts_with(comment_prefix) && !line.starts_with("#[")
+        };
+
+        let mut directives = Vec::new();
+        for (index, (line_start, line)) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            let Some(comment) = trimmed.strip_prefix(comment_prefix) else {
+                continue;
+            };
+            let comment = comment.trim_start();
+            let (ids, scope) = if let Some(ids) = comment.strip_prefix(IGNORE_FILE_DIRECTIVE) {
+                (ids, SuppressionScope::File)
+            } else if let Some(ids) = comment.strip_prefix(IGNORE_DIRECTIVE) {
+                let line_number = index + 1;
+                let last = lines[index + 1..]
+                    .iter()
+                    .position(|(_, next)| is_code(next))
+                    .map_or(line_number, |skipped| line_number + skipped + 1);
+                (ids, SuppressionScope::Lines { first: line_number + 1, last })
+            } else {
+                continue;
+            };
+
+            let mut id_start = line_start + (line.len() - ids.len());
+            for id in ids.split(',') {
+                let trimmed_id = id.trim();
+                if !trimmed_id.is_empty() {
+                    let start = id_start + (id.len() - id.trim_start().len());
+                    directives.push(Suppression {
+                        rule_id: trimmed_id.to_string(),
+                        scope,
+                        span: SourceSpan::from_byte_range(source, start, start + trimmed_id.len()),
+                    });
+                }
+                id_start += id.len() + 1;
+            }
+        }
+
+        Self { directives }
+    }
+
+    pub fn directives(&self) -> &[Suppression] {
+        &self.directives
+    }
+
+    /// Split `violations` into active and suppressed findings, flagging every
+    /// directive that silenced nothing
+    pub fn apply(&self, violations: Vec<RuleViolation>) -> SuppressionReport {
+        let mut used = vec![false; self.directives.len()];
+        let mut report = SuppressionReport::default();
+
+        for violation in violations {
+            let mut suppressed = false;
+            for (index, directive) in self.directives.iter().enumerate() {
+                if directive.matches(&violation) {
+                    used[index] = true;
+                    suppressed = true;
+                }
+            }
+
+            if suppressed {
+                report.suppressed.push(violation);
+            } else {
+                report.violations.push(violation);
+            }
+        }
+
+        for (directive, _) in self.directives.iter().zip(used).filter(|(_, used)| !used) {
+            report.violations.push(RuleViolation {
+                rule_name: UNUSED_SUPPRESSION_RULE.to_string(),
+                description: format!("Suppression for '{}' does not match any finding", directive.rule_id),
+                severity: ViolationSeverity::Warning,
+                line_number: directive.span.start_line,
+                column_number: directive.span.start_column,
+                span: directive.span,
+                variable_name: directive.rule_id.clone(),
+                suggestion: "Remove the stale gasguard-ignore comment or fix the rule ID".to_string(),
+                fix: None,
+            });
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(rule_name: &str, line_number: usize) -> RuleViolation {
+        RuleViolation {
+            rule_name: rule_name.to_string(),
+            description: String::new(),
+            severity: ViolationSeverity::High,
+            line_number,
+            column_number: 1,
+            span: SourceSpan::default(),
+            variable_name: String::new(),
+            suggestion: String::new(),
+            fix: None,
+        }
+    }
+
+    #[test]
+    fn test_next_line_suppression_skips_comments_and_attributes() {
+        let source = "// gasguard-ignore: soroban-unbounded-loop, soroban-expensive-strings\n/// Docs\n#[inline]\npub fn run() {}\nfn other() {}\n";
+        let suppressions = Suppressions::parse(source, "//");
+
+        let ids: Vec<_> = suppressions.directives().iter().map(|d| d.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["soroban-unbounded-loop", "soroban-expensive-strings"]);
+        assert_eq!(suppressions.directives()[0].scope, SuppressionScope::Lines { first: 2, last: 4 });
+        assert_eq!(suppressions.directives()[1].span.snippet(source), "soroban-expensive-strings");
+
+        let report = suppressions.apply(vec![
+            violation("soroban-unbounded-loop", 4),
+            violation("soroban-unbounded-loop", 5),
+            violation("soroban-expensive-strings", 4),
+        ]);
+        assert_eq!(report.suppressed_count(), 2);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].line_number, 5);
+    }
+
+    #[test]
+    fn test_file_suppression_and_unused_directives() {
+        let source = "# gasguard-ignore-file: vyper-redundant-external\n# gasguard-ignore: typo-rule\ndef f():\n    pass\n";
+        let report = Suppressions::parse(source, "#").apply(vec![
+            violation("vyper-redundant-external", 3),
+            violation("vyper-redundant-external", 10),
+        ]);
+
+        assert_eq!(report.suppressed_count(), 2);
+        assert_eq!(report.violations.len(), 1);
+        let unused = &report.violations[0];
+        assert_eq!(unused.rule_name, UNUSED_SUPPRESSION_RULE);
+        assert_eq!(unused.variable_name, "typo-rule");
+        assert_eq!((unused.line_number, unused.column_number), (2, 20));
+    }
+
+    #[test]
+    fn test_directives_must_be_comments() {
+        let source = "let note = \"gasguard-ignore-file: soroban-unbounded-loop\";\n";
+        let report = Suppressions::parse(source, "//").apply(vec![violation("soroban-unbounded-loop", 1)]);
+        assert!(report.suppressed.is_empty());
+        assert_eq!(report.violations.len(), 1);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::rule_engine::{Rule, RuleCategory, RuleInput, RuleMetadata, RuleViolation, ViolationSeverity};
+use crate::soroban::{Arithmetic, SorobanContract, StatementKind};
+use crate::{Language, SourceSpan};
+use quote::ToTokens;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{FnArg, ImplItem, Item, Pat};
+
+/// Fixed-width integer types whose arithmetic can overflow; `usize` counters are
+/// bounded by memory long before they wrap and are left out
+const INTEGER_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "Uint64", "Uint128", "Uint256", "Uint512",
+    "Int64", "Int128", "Int256", "Int512",
+];
+
+/// Name fragments of values holding funds or counts, checked when neither operand
+/// has a known type
+const VALUE_NAMES: &[&str] = &[
+    "balance", "supply", "amount", "total", "count", "votes", "shares", "reserve", "deposit", "stake", "reward", "fee",
+    "price", "allowance", "nonce",
+];
+
+/// Rule for detecting `+`, `-` and `*` on token amounts and counters that can
+/// overflow without being caught
+///
+/// Soroban contracts built without `overflow-checks` wrap silently; CosmWasm's
+/// `Uint128` operators panic with no error the caller can handle. Operand types
+/// are taken from parameters, typed locals and struct fields where declared.
+pub struct UncheckedArithmeticRule;
+
+impl Rule for UncheckedArithmeticRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "unchecked-arithmetic",
+            name: "Unchecked Arithmetic",
+            description: "Detects +, - and * on integer balances, supplies and counters that can overflow instead of using checked_* or saturating_* methods",
+            default_severity: ViolationSeverity::High,
+            category: RuleCategory::Security,
+            languages: &[Language::Rust, Language::Soroban],
+            tags: &["arithmetic", "overflow"],
+        };
+        &METADATA
+    }
+
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let functions = match input {
+            RuleInput::Soroban(contract) => Self::soroban_functions(contract),
+            RuleInput::Rust(items) => Self::rust_functions(items),
+            _ => return Vec::new(),
+        };
+
+        let mut violations = Vec::new();
+        for function in &functions {
+            for operation in &function.arithmetic {
+                let Some(reason) = function.scope.overflow_reason(operation) else { continue };
+                let word = operation.checked_method().trim_start_matches("checked_");
+                violations.push(RuleViolation {
+                    rule_name: self.id().to_string(),
+                    description: format!(
+                        "Unchecked '{}' in '{}' on {}: `{} {} {}` can overflow",
+                        operation.operator, function.name, reason, operation.left, operation.operator, operation.right
+                    ),
+                    suggestion: format!(
+                        "Use {}(..) and handle the overflow case, or saturating_{}(..) where clamping is intended",
+                        operation.checked_method(),
+                        word
+                    ),
+                    line_number: operation.span.start_line,
+                    column_number: operation.span.start_column,
+                    span: operation.span,
+                    variable_name: operation.left.clone(),
+                    severity: self.severity(),
+                    fix: None,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// A function's arithmetic and the declared types it can be checked against
+struct ArithmeticFunction {
+    name: String,
+    scope: TypeScope,
+    arithmetic: Vec<Arithmetic>,
+}
+
+/// Declared types of the names visible in a function
+#[derive(Default)]
+struct TypeScope {
+    /// Parameters and typed locals
+    locals: HashMap<String, String>,
+    /// Struct fields, by field name
+    fields: HashMap<String, String>,
+}
+
+impl UncheckedArithmeticRule {
+    /// Functions of a Soroban contract, typed from its `SorobanParam`s and `SorobanField`s
+    fn soroban_functions(contract: &SorobanContract) -> Vec<ArithmeticFunction> {
+        let fields: HashMap<String, String> = contract
+            .contract_types
+            .iter()
+            .flat_map(|definition| &definition.fields)
+            .map(|field| (field.name.clone(), field.type_name.clone()))
+            .collect();
+
+        contract
+            .all_functions()
+            .filter(|function| !function.body.arithmetic.is_empty())
+            .map(|function| {
+                let mut locals: HashMap<String, String> = function
+                    .params
+                    .iter()
+                    .map(|param| (param.name.clone(), param.type_name.clone()))
+                    .collect();
+                for statement in &function.body.statements {
+                    if let StatementKind::Let { pattern, type_name: Some(type_name), .. } = &statement.kind {
+                        locals.insert(pattern.trim_start_matches("mut ").to_string(), type_name.clone());
+                    }
+                }
+                ArithmeticFunction {
+                    name: function.name.clone(),
+                    scope: TypeScope { locals, fields: fields.clone() },
+                    arithmetic: function.body.arithmetic.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Functions and methods of a Rust file such as a CosmWasm contract, outside test modules
+    fn rust_functions(items: &[Item]) -> Vec<ArithmeticFunction> {
+        let mut collector = RustCollector::default();
+        for item in items {
+            collector.visit_item(item);
+        }
+
+        let fields = collector.fields;
+        collector
+            .functions
+            .into_iter()
+            .map(|mut function| {
+                function.scope.fields = fields.clone();
+                function
+            })
+            .collect()
+    }
+}
+
+impl TypeScope {
+    /// Why `operation` may overflow, or `None` if its operands are known not to
+    fn overflow_reason(&self, operation: &Arithmetic) -> Option<String> {
+        let operands = [&operation.left, &operation.right];
+        if operands.iter().all(|operand| is_literal(operand)) {
+            return None;
+        }
+
+        let types: Vec<String> = operands.iter().filter_map(|operand| self.operand_type(operand)).collect();
+        if let Some(integer) = types.iter().find(|type_name| is_integer(type_name)) {
+            return Some(format!("{} values", integer));
+        }
+        if !types.is_empty() {
+            return None;
+        }
+
+        let valued = operands.iter().find(|operand| {
+            let operand = operand.to_lowercase();
+            VALUE_NAMES.iter().any(|name| operand.contains(name))
+        })?;
+        Some(format!("'{}'", valued))
+    }
+
+    /// Declared type of an operand, when it can be told
+    fn operand_type(&self, operand: &str) -> Option<String> {
+        let operand = operand
+            .trim()
+            .trim_start_matches(['&', '*'])
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .trim_end_matches('?')
+            .trim_end_matches(".clone()")
+            .trim();
+        if let Some((_, cast)) = operand.rsplit_once(" as ") {
+            return Some(cast.trim().to_string());
+        }
+        if is_literal(operand) {
+            let suffix = operand.trim_start_matches(|c: char| c.is_ascii_digit() || c == '_');
+            return (!suffix.is_empty()).then(|| suffix.to_string());
+        }
+        if !operand.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+            return None;
+        }
+
+        match operand.rsplit_once('.') {
+            Some((_, field)) => self.fields.get(field).cloned(),
+            None => self.locals.get(operand).cloned(),
+        }
+    }
+}
+
+/// Whether an operand is a numeric literal such as `1` or `100u64`
+fn is_literal(operand: &str) -> bool {
+    operand.trim().starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Whether a type is a fixed-width integer, ignoring references and paths
+fn is_integer(type_name: &str) -> bool {
+    let base = type_name.trim().trim_start_matches('&').trim();
+    let base = base.rsplit("::").next().unwrap_or(base);
+    INTEGER_TYPES.contains(&base)
+}
+
+/// Collects functions, their typed locals and arithmetic, and struct field types
+/// from a plain Rust file
+#[derive(Default)]
+struct RustCollector {
+    functions: Vec<ArithmeticFunction>,
+    fields: HashMap<String, String>,
+    /// Function being collected
+    current: Option<ArithmeticFunction>,
+}
+
+impl RustCollector {
+    /// Tokens of a node as text, e.g. `deps.storage` rather than `deps . storage`
+    fn text(node: &impl ToTokens) -> String {
+        node.to_token_stream()
+            .to_string()
+            .replace(" . ", ".")
+            .replace(" :: ", "::")
+            .replace(" (", "(")
+            .replace("( ", "(")
+            .replace(" )", ")")
+            .replace("& ", "&")
+    }
+
+    fn collect_fn(&mut self, name: String, sig: &syn::Signature, block: &syn::Block) {
+        let mut locals = HashMap::new();
+        for input in &sig.inputs {
+            if let FnArg::Typed(typed) = input {
+                locals.insert(Self::text(&typed.pat).trim_start_matches("mut ").to_string(), Self::text(&typed.ty));
+            }
+        }
+
+        let outer = self.current.replace(ArithmeticFunction {
+            name,
+            scope: TypeScope { locals, fields: HashMap::new() },
+            arithmetic: Vec::new(),
+        });
+        self.visit_block(block);
+        let function = std::mem::replace(&mut self.current, outer).expect("function being collected");
+        if !function.arithmetic.is_empty() {
+            self.functions.push(function);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for RustCollector {
+    fn visit_item(&mut self, item: &'ast Item) {
+        match item {
+            Item::Fn(function) => self.collect_fn(function.sig.ident.to_string(), &function.sig, &function.block),
+            Item::Impl(implementation) => {
+                for item in &implementation.items {
+                    if let ImplItem::Fn(method) = item {
+                        self.collect_fn(method.sig.ident.to_string(), &method.sig, &method.block);
+                    }
+                }
+            }
+            Item::Struct(definition) => {
+                for field in &definition.fields {
+                    if let Some(ident) = &field.ident {
+                        self.fields.insert(ident.to_string(), Self::text(&field.ty));
+                    }
+                }
+            }
+            Item::Mod(module) => {
+                let test_only = module.attrs.iter().any(|attr| {
+                    attr.path().is_ident("cfg") && Self::text(&attr.meta).contains("test")
+                });
+                if !test_only {
+                    visit::visit_item_mod(self, module);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let (Pat::Type(typed), Some(function)) = (&local.pat, self.current.as_mut()) {
+            let name = Self::text(&typed.pat).trim_start_matches("mut ").to_string();
+            function.scope.locals.insert(name, Self::text(&typed.ty));
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        use syn::BinOp::*;
+        if matches!(binary.op, Add(_) | Sub(_) | Mul(_) | AddAssign(_) | SubAssign(_) | MulAssign(_)) {
+            if let Some(function) = self.current.as_mut() {
+                function.arithmetic.push(Arithmetic {
+                    operator: Self::text(&binary.op),
+                    left: Self::text(&binary.left),
+                    right: Self::text(&binary.right),
+                    span: SourceSpan::from_span(binary.span()),
+                });
+            }
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_item_fn(&mut self, _function: &'ast syn::ItemFn) {
+        // Nested functions are collected on their own by `visit_item`
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soroban::SorobanParser;
+
+    #[test]
+    fn test_soroban_arithmetic_uses_declared_types() {
+        let source = r#"
+#[contracttype]
+pub struct Proposal {
+    pub votes: u64,
+    pub title: String,
+}
+
+#[contract]
+pub struct Governance;
+
+#[contractimpl]
+impl Governance {
+    pub fn vote(env: Env, proposal: Proposal, weight: u64, offset: usize) {
+        let mut current_votes: u64 = env.storage().instance().get(&1u32).unwrap_or(0);
+        current_votes += 1;
+        let next = proposal.votes + weight;
+        let index = offset + 1;
+        let label = proposal.title.len() + 2;
+        let total_supply = Self::supply(&env) * 2;
+    }
+}
+"#;
+        let contract = SorobanParser::parse_contract(source, "governance.rs").unwrap();
+        let violations = UncheckedArithmeticRule.check(&RuleInput::Soroban(&contract));
+
+        let found: Vec<_> = violations.iter().map(|v| (v.variable_name.as_str(), v.line_number)).collect();
+        assert_eq!(found, vec![("current_votes", 15), ("proposal.votes", 16), ("Self::supply(&env)", 19)]);
+        assert!(violations[0].description.contains("on u64 values"));
+        assert!(violations[0].suggestion.contains("checked_add"));
+    }
+
+    #[test]
+    fn test_cosmwasm_uint128_arithmetic() {
+        let file = syn::parse_file(
+            r#"
+pub fn execute_treasury_action(deps: DepsMut, amount: Uint128) -> StdResult<Response> {
+    let current: Uint128 = TREASURY_BALANCE.load(deps.storage).unwrap_or_default();
+    TREASURY_BALANCE.save(deps.storage, &(current + amount))?;
+    let withdrawn = current.checked_sub(amount)?;
+    Ok(Response::new())
+}
+
+#[cfg(test)]
+mod tests {
+    fn helper(amount: u64) -> u64 { amount * 2 }
+}
+"#,
+        )
+        .unwrap();
+        let violations = UncheckedArithmeticRule.check(&RuleInput::Rust(&file.items));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line_number, 4);
+        assert!(violations[0].description.contains("`current + amount`"));
+        assert!(violations[0].description.contains("Uint128 values"));
+    }
+}
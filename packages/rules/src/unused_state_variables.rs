@@ -1,22 +1,34 @@
 use crate::rule_engine::{
-    extract_struct_fields, find_variable_usage, Rule, RuleViolation, ViolationSeverity,
+    extract_struct_fields, find_variable_usage, Rule, RuleCategory, RuleInput, RuleMetadata, RuleViolation,
+    ViolationSeverity,
 };
+use crate::{Applicability, Fix, Language, SourceSpan, TextEdit};
+use proc_macro2::{TokenStream, TokenTree};
 use quote::ToTokens;
 use std::collections::HashSet;
+use syn::spanned::Spanned;
 use syn::{Item, ItemImpl, ItemStruct, Meta};
 
 pub struct UnusedStateVariablesRule;
 
 impl Rule for UnusedStateVariablesRule {
-    fn name(&self) -> &str {
-        "unused-state-variables"
-    }
-
-    fn description(&self) -> &str {
-        "Identifies state variables in Soroban contracts that are never read or written to, helping developers minimize storage footprint and ledger rent."
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "unused-state-variables",
+            name: "Unused State Variables",
+            description: "Identifies state variables in Soroban contracts that are never read or written to, helping developers minimize storage footprint and ledger rent.",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Rust],
+            tags: &["storage"],
+        };
+        &METADATA
     }
 
-    fn check(&self, ast: &[Item]) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(ast) = input.rust() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
 
         // Find all contract structs and their implementations
@@ -35,20 +47,33 @@ impl Rule for UnusedStateVariablesRule {
                 // Check each state variable for usage
                 for var_name in &state_variables {
                     if !self.is_variable_used(var_name, &used_variables) {
+                        let span = self.field_span(struct_item, var_name);
+                        // Initializers such as `Self { field: .. }` would have to go too
+                        let applicability = if self.count_ident(ast, var_name) > 1 {
+                            Applicability::MaybeIncorrect
+                        } else {
+                            Applicability::MachineApplicable
+                        };
                         violations.push(RuleViolation {
-                            rule_name: self.name().to_string(),
+                            rule_name: self.id().to_string(),
                             description: format!(
                                 "State variable '{}' is declared but never used in contract '{}'. This wastes storage space and increases ledger rent costs.",
                                 var_name, struct_name
                             ),
-                            severity: ViolationSeverity::Warning,
-                            line_number: 0, 
-                            column_number: 0,
+                            severity: self.severity(),
+                            line_number: span.start_line,
+                            column_number: span.start_column,
+                            span,
                             variable_name: var_name.clone(),
                             suggestion: format!(
                                 "Consider removing the unused state variable '{}' or implement functionality that uses it. If it's reserved for future use, add a comment explaining its purpose.",
                                 var_name
                             ),
+                            fix: Some(Fix::new(
+                                format!("Remove unused state variable '{}'", var_name),
+                                applicability,
+                                vec![TextEdit::delete(self.field_declaration_span(struct_item, var_name))],
+                            )),
                         });
                     }
                 }
@@ -96,6 +121,47 @@ impl UnusedStateVariablesRule {
         contract_impls
     }
 
+    fn field_span(&self, struct_item: &ItemStruct, var_name: &str) -> SourceSpan {
+        struct_item
+            .fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == var_name))
+            .map(|field| SourceSpan::between(field.ident.span(), field.ty.span()))
+            .unwrap_or_default()
+    }
+
+    /// Span of a field declaration including its attributes and trailing comma
+    fn field_declaration_span(&self, struct_item: &ItemStruct, var_name: &str) -> SourceSpan {
+        let syn::Fields::Named(named) = &struct_item.fields else {
+            return SourceSpan::default();
+        };
+        named
+            .named
+            .pairs()
+            .find(|pair| pair.value().ident.as_ref().is_some_and(|ident| ident == var_name))
+            .map(|pair| match pair.punct() {
+                Some(comma) => SourceSpan::between(pair.value().span(), comma.span),
+                None => SourceSpan::from_span(pair.value().span()),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Number of times `name` appears as an identifier anywhere in the file
+    fn count_ident(&self, ast: &[Item], name: &str) -> usize {
+        fn count(tokens: TokenStream, name: &str) -> usize {
+            tokens
+                .into_iter()
+                .map(|token| match token {
+                    TokenTree::Ident(ident) => usize::from(ident == name),
+                    TokenTree::Group(group) => count(group.stream(), name),
+                    _ => 0,
+                })
+                .sum()
+        }
+
+        ast.iter().map(|item| count(item.to_token_stream(), name)).sum()
+    }
+
     fn is_soroban_contract(&self, struct_item: &ItemStruct) -> bool {
         // Check for Soroban contract attributes
         for attr in &struct_item.attrs {
@@ -183,11 +249,14 @@ mod tests {
 
         let rule = UnusedStateVariablesRule;
         let file: syn::File = syn::parse2(code).unwrap();
-        let violations = rule.check(&file.items);
+        let violations = rule.check(&RuleInput::Rust(&file.items));
 
         // Should find one unused variable
         assert_eq!(violations.len(), 1);
         assert_eq!(violations[0].variable_name, "unused_var");
+        // The constructor still initializes the field, so removal needs review
+        let fix = violations[0].fix.as_ref().unwrap();
+        assert_eq!(fix.applicability, Applicability::MaybeIncorrect);
     }
 
     #[test]
@@ -220,7 +289,7 @@ mod tests {
 
         let rule = UnusedStateVariablesRule;
         let file: syn::File = syn::parse2(code).unwrap();
-        let violations = rule.check(&file.items);
+        let violations = rule.check(&RuleInput::Rust(&file.items));
 
         // Should find no violations
         assert_eq!(violations.len(), 0);
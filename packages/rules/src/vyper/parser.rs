@@ -1,3 +1,10 @@
+//! Vyper parser
+//!
+//! Vyper is indentation based, so the source is first split into logical lines
+//! (joining bracketed and backslash continuations, blanking out comments) and
+//! blocks are then rebuilt from their indentation. Expressions are kept as text.
+
+use crate::SourceSpan;
 use regex::Regex;
 use std::collections::HashSet;
 
@@ -6,8 +13,178 @@ use std::collections::HashSet;
 pub struct VyperFunction {
     pub name: String,
     pub decorators: Vec<String>,
+    /// Location of each decorator, in the same order as `decorators`
+    pub decorator_spans: Vec<SourceSpan>,
+    pub args: Vec<VyperParam>,
+    /// Return type as written, e.g. `uint256` or `(uint256, bool)`
+    pub return_type: Option<String>,
+    pub body: Vec<VyperStatement>,
     pub line_number: usize,
     pub column_number: usize,
+    /// Location of the `def` line
+    pub span: SourceSpan,
+}
+
+impl VyperFunction {
+    /// Location of the first decorator with the given name
+    pub fn decorator_span(&self, decorator: &str) -> Option<SourceSpan> {
+        self.decorators
+            .iter()
+            .position(|d| d == decorator)
+            .and_then(|index| self.decorator_spans.get(index).copied())
+    }
+
+    /// Every statement of the body, nested ones included, in source order
+    pub fn statements(&self) -> Vec<&VyperStatement> {
+        let mut statements = Vec::new();
+        for statement in &self.body {
+            statement.walk(&mut |s| statements.push(s));
+        }
+        statements
+    }
+}
+
+/// A function argument, event field or struct member
+#[derive(Debug, Clone)]
+pub struct VyperParam {
+    pub name: String,
+    pub type_name: String,
+    /// Default value of an optional argument
+    pub default: Option<String>,
+    /// Whether an event field is `indexed(...)`
+    pub indexed: bool,
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VyperVariableKind {
+    Storage,
+    Constant,
+    Immutable,
+    Transient,
+}
+
+/// A module-level variable declaration such as `owner: public(address)`
+#[derive(Debug, Clone)]
+pub struct VyperVariable {
+    pub name: String,
+    /// Declared type with the `public(...)`, `constant(...)`, `immutable(...)`
+    /// and `transient(...)` wrappers removed
+    pub type_name: String,
+    pub kind: VyperVariableKind,
+    pub public: bool,
+    /// Initializer of a constant
+    pub value: Option<String>,
+    pub line_number: usize,
+    pub span: SourceSpan,
+}
+
+impl VyperVariable {
+    /// Whether the variable lives in contract storage
+    pub fn is_storage(&self) -> bool {
+        self.kind == VyperVariableKind::Storage
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VyperEvent {
+    pub name: String,
+    pub fields: Vec<VyperParam>,
+    pub line_number: usize,
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone)]
+pub struct VyperStruct {
+    pub name: String,
+    pub fields: Vec<VyperParam>,
+    pub line_number: usize,
+    pub span: SourceSpan,
+}
+
+/// A function declared by an interface
+#[derive(Debug, Clone)]
+pub struct VyperInterfaceFunction {
+    pub name: String,
+    pub args: Vec<VyperParam>,
+    pub return_type: Option<String>,
+    /// `view`, `pure`, `nonpayable` or `payable`
+    pub mutability: Option<String>,
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone)]
+pub struct VyperInterface {
+    pub name: String,
+    pub functions: Vec<VyperInterfaceFunction>,
+    pub line_number: usize,
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VyperStatementKind {
+    /// `name: type = value`
+    Declaration {
+        name: String,
+        type_name: String,
+        value: Option<String>,
+    },
+    /// `target = value`, or an augmented assignment such as `target += value`
+    Assignment {
+        target: String,
+        operator: String,
+        value: String,
+    },
+    If { condition: String },
+    Elif { condition: String },
+    Else,
+    For {
+        variable: String,
+        type_name: Option<String>,
+        iterable: String,
+    },
+    Return { value: Option<String> },
+    /// `log Event(...)`
+    Log { event: String },
+    Assert { condition: String },
+    Raise { reason: Option<String> },
+    Pass,
+    Break,
+    Continue,
+    Expression { expression: String },
+}
+
+/// A statement of a function body
+#[derive(Debug, Clone)]
+pub struct VyperStatement {
+    pub kind: VyperStatementKind,
+    /// Source text of the statement, or of the header line for blocks
+    pub text: String,
+    pub line_number: usize,
+    pub span: SourceSpan,
+    /// Statements nested under `if`, `elif`, `else` and `for`
+    pub body: Vec<VyperStatement>,
+}
+
+impl VyperStatement {
+    /// Visit this statement and every statement nested in it
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a VyperStatement)) {
+        visit(self);
+        for statement in &self.body {
+            statement.walk(visit);
+        }
+    }
+
+    /// Storage variable written by an assignment to `self.<name>` or
+    /// `self.<name>[...]`
+    pub fn storage_write(&self) -> Option<&str> {
+        let VyperStatementKind::Assignment { target, .. } = &self.kind else {
+            return None;
+        };
+        let name = target.strip_prefix("self.")?;
+        let end = name.find(|c: char| !is_ident_char(c)).unwrap_or(name.len());
+        Some(&name[..end])
+    }
 }
 
 /// Represents a function call within the contract
@@ -23,71 +200,26 @@ pub struct VyperFunctionCall {
 pub struct VyperContract {
     pub functions: Vec<VyperFunction>,
     pub function_calls: Vec<VyperFunctionCall>,
+    /// Module-level variables: storage, constants, immutables and transients
+    pub variables: Vec<VyperVariable>,
+    pub events: Vec<VyperEvent>,
+    pub structs: Vec<VyperStruct>,
+    pub interfaces: Vec<VyperInterface>,
+    /// `import` and `from ... import` lines
+    pub imports: Vec<String>,
 }
 
 impl VyperContract {
-    /// Parse Vyper source code and extract function definitions with decorators
+    /// Parse Vyper source code into its declarations and function bodies
     pub fn parse(source: &str) -> Result<Self, String> {
-        let mut functions = Vec::new();
-        let mut function_calls = Vec::new();
-        let mut current_decorators: Vec<String> = Vec::new();
-        let mut decorator_start_line: Option<usize> = None;
-
-        // Regex patterns for Vyper parsing
-        let decorator_pattern = Regex::new(r"^@(\w+)").map_err(|e| e.to_string())?;
-        let function_pattern = Regex::new(r"^def\s+(\w+)\s*\(").map_err(|e| e.to_string())?;
-        let self_call_pattern = Regex::new(r"self\.(\w+)\s*\(").map_err(|e| e.to_string())?;
-
-        for (line_idx, line) in source.lines().enumerate() {
-            let line_number = line_idx + 1;
-            let trimmed = line.trim();
-
-            // Check for decorator
-            if let Some(captures) = decorator_pattern.captures(trimmed) {
-                if let Some(decorator_name) = captures.get(1) {
-                    if current_decorators.is_empty() {
-                        decorator_start_line = Some(line_number);
-                    }
-                    current_decorators.push(decorator_name.as_str().to_string());
-                }
-            }
-            // Check for function definition
-            else if let Some(captures) = function_pattern.captures(trimmed) {
-                if let Some(func_name) = captures.get(1) {
-                    let func_line = decorator_start_line.unwrap_or(line_number);
-                    functions.push(VyperFunction {
-                        name: func_name.as_str().to_string(),
-                        decorators: current_decorators.clone(),
-                        line_number: func_line,
-                        column_number: 1,
-                    });
-                    current_decorators.clear();
-                    decorator_start_line = None;
-                }
-            }
-            // Check for non-decorator, non-function lines (reset decorators if we hit something else)
-            else if !trimmed.is_empty() && !trimmed.starts_with('#') {
-                // If we encounter a non-empty, non-comment line that's not a decorator or function,
-                // and we have pending decorators, they might be orphaned (edge case)
-                // For now, we keep collecting decorators until we hit a function
-            }
-
-            // Track self.function() calls for internal usage analysis
-            for captures in self_call_pattern.captures_iter(line) {
-                if let Some(func_name) = captures.get(1) {
-                    function_calls.push(VyperFunctionCall {
-                        function_name: func_name.as_str().to_string(),
-                        is_self_call: true,
-                        line_number,
-                    });
-                }
-            }
-        }
-
-        Ok(VyperContract {
-            functions,
-            function_calls,
-        })
+        let parser = Parser {
+            source,
+            lines: logical_lines(source),
+            line_starts: std::iter::once(0)
+                .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+                .collect(),
+        };
+        parser.module()
     }
 
     /// Get all functions that are only called internally (via self.)
@@ -108,6 +240,623 @@ impl VyperContract {
     pub fn is_internal_naming_convention(func_name: &str) -> bool {
         func_name.starts_with('_') && !func_name.starts_with("__")
     }
+
+    pub fn function(&self, name: &str) -> Option<&VyperFunction> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&VyperVariable> {
+        self.variables.iter().find(|v| v.name == name)
+    }
+
+    /// Variables kept in contract storage
+    pub fn storage_variables(&self) -> impl Iterator<Item = &VyperVariable> {
+        self.variables.iter().filter(|v| v.is_storage())
+    }
+}
+
+/// Module-level statements that look like variable declarations but are not
+const MODULE_DIRECTIVES: &[&str] = &["implements", "initializes", "uses", "exports"];
+
+/// Augmented assignment operators, longest first
+const AUGMENTED_OPERATORS: &[&str] = &["//=", "**=", "<<=", ">>=", "+=", "-=", "*=", "/=", "%=", "|=", "&=", "^="];
+
+/// One statement or block header, possibly spanning several physical lines
+struct Line {
+    /// Column of the first character, in bytes
+    indent: usize,
+    /// Byte offset of the first character
+    start: usize,
+    /// Source text with comments blanked out, so offsets match the source
+    text: String,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    lines: Vec<Line>,
+    /// Byte offset at which each physical line starts
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn module(&self) -> Result<VyperContract, String> {
+        let mut contract = VyperContract {
+            functions: Vec::new(),
+            function_calls: Vec::new(),
+            variables: Vec::new(),
+            events: Vec::new(),
+            structs: Vec::new(),
+            interfaces: Vec::new(),
+            imports: Vec::new(),
+        };
+        let mut decorators: Vec<String> = Vec::new();
+        let mut decorator_spans: Vec<SourceSpan> = Vec::new();
+
+        let mut index = 0;
+        while index < self.lines.len() {
+            let line = &self.lines[index];
+            let block_end = self.block_end(index, self.lines.len());
+            let text = line.text.as_str();
+            let span = self.span(line.start, line.start + text.len());
+
+            if let Some(rest) = text.strip_prefix('@') {
+                let name_len = rest.find(|c: char| !is_ident_char(c)).unwrap_or(rest.len());
+                decorators.push(rest[..name_len].to_string());
+                decorator_spans.push(self.span(line.start, line.start + 1 + name_len));
+            } else if text.starts_with("def ") {
+                let header = self.function_header(line)?;
+                let mut body = self.statements(index + 1, block_end);
+                if let Some((rest, offset)) = header.rest {
+                    body.insert(0, self.statement(rest, offset, index + 1, index + 1));
+                }
+                contract.functions.push(VyperFunction {
+                    name: header.name,
+                    line_number: decorator_spans.first().map_or(span.start_line, |s| s.start_line),
+                    decorators: std::mem::take(&mut decorators),
+                    decorator_spans: std::mem::take(&mut decorator_spans),
+                    args: header.args,
+                    return_type: header.return_type,
+                    body,
+                    column_number: 1,
+                    span,
+                });
+            } else if let Some(name) = block_name(text, "event") {
+                contract.events.push(VyperEvent {
+                    name,
+                    fields: self.fields(index + 1, block_end),
+                    line_number: span.start_line,
+                    span,
+                });
+            } else if let Some(name) = block_name(text, "struct") {
+                contract.structs.push(VyperStruct {
+                    name,
+                    fields: self.fields(index + 1, block_end),
+                    line_number: span.start_line,
+                    span,
+                });
+            } else if let Some(name) = block_name(text, "interface") {
+                let mut functions = Vec::new();
+                for member in &self.lines[index + 1..block_end] {
+                    if !member.text.starts_with("def ") {
+                        continue;
+                    }
+                    let header = self.function_header(member)?;
+                    functions.push(VyperInterfaceFunction {
+                        name: header.name,
+                        args: header.args,
+                        return_type: header.return_type,
+                        mutability: header.rest.map(|(rest, _)| rest.to_string()),
+                        span: self.span(member.start, member.start + member.text.len()),
+                    });
+                }
+                contract.interfaces.push(VyperInterface {
+                    name,
+                    functions,
+                    line_number: span.start_line,
+                    span,
+                });
+            } else if text.starts_with("import ") || text.starts_with("from ") {
+                contract.imports.push(collapse_whitespace(text));
+            } else if let Some(variable) = self.variable(line) {
+                contract.variables.push(variable);
+            }
+
+            index = block_end;
+        }
+
+        // Track self.function() calls for internal usage analysis
+        let self_call_pattern = Regex::new(r"self\.(\w+)\s*\(").map_err(|e| e.to_string())?;
+        for line in &self.lines {
+            for captures in self_call_pattern.captures_iter(&line.text) {
+                let Some(func_name) = captures.get(1) else { continue };
+                contract.function_calls.push(VyperFunctionCall {
+                    function_name: func_name.as_str().to_string(),
+                    is_self_call: true,
+                    line_number: self.line_number(line.start + func_name.start()),
+                });
+            }
+        }
+
+        Ok(contract)
+    }
+
+    /// Index just past the lines indented under `index`, stopping at `end`
+    fn block_end(&self, index: usize, end: usize) -> usize {
+        let indent = self.lines[index].indent;
+        let mut block_end = index + 1;
+        while block_end < end && self.lines[block_end].indent > indent {
+            block_end += 1;
+        }
+        block_end
+    }
+
+    fn line_number(&self, byte: usize) -> usize {
+        self.line_starts.partition_point(|&line_start| line_start <= byte)
+    }
+
+    fn span(&self, start: usize, end: usize) -> SourceSpan {
+        let position = |byte: usize| {
+            let line = self.line_number(byte);
+            let column = self.source[self.line_starts[line - 1]..byte].chars().count() + 1;
+            (line, column)
+        };
+        let ((start_line, start_column), (end_line, end_column)) = (position(start), position(end));
+        SourceSpan {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            start_byte: start,
+            end_byte: end,
+        }
+    }
+
+    /// `name: type` members of an event or struct body
+    fn fields(&self, start: usize, end: usize) -> Vec<VyperParam> {
+        self.lines[start..end]
+            .iter()
+            .filter(|line| line.text != "pass" && !is_docstring(&line.text))
+            .map(|line| self.param(&line.text, line.start))
+            .collect()
+    }
+
+    /// `name: type = default`, where `text` starts at byte `start` of the source
+    fn param(&self, text: &str, start: usize) -> VyperParam {
+        let (name, mut type_name) = match find_top_level(text, |i| text.as_bytes()[i] == b':') {
+            Some(colon) => (text[..colon].trim(), text[colon + 1..].trim()),
+            None => (text.trim(), ""),
+        };
+        let mut default = None;
+        if let Some(equals) = find_top_level(type_name, |i| type_name.as_bytes()[i] == b'=') {
+            default = Some(type_name[equals + 1..].trim().to_string());
+            type_name = type_name[..equals].trim();
+        }
+        let (indexed, type_name) = match strip_call(type_name) {
+            Some(("indexed", inner)) => (true, inner),
+            _ => (false, type_name),
+        };
+
+        VyperParam {
+            name: name.to_string(),
+            type_name: normalize_type(type_name),
+            default,
+            indexed,
+            span: self.span(start, start + text.len()),
+        }
+    }
+
+    /// Parse a `def name(args) -> type:` line
+    fn function_header<'l>(&self, line: &'l Line) -> Result<FunctionHeader<'l>, String> {
+        let text = line.text.as_str();
+        let error = |message: &str| format!("Line {}: {}", self.line_number(line.start), message);
+
+        let open = text.find('(').ok_or_else(|| error("expected '(' after function name"))?;
+        let close = matching_bracket(text, open).ok_or_else(|| error("unclosed argument list"))?;
+        let args = split_top_level(&text[open + 1..close], b',')
+            .into_iter()
+            .map(|(offset, arg)| self.param(arg, line.start + open + 1 + offset))
+            .collect();
+
+        let after = &text[close + 1..];
+        let colon = find_top_level(after, |i| after.as_bytes()[i] == b':')
+            .ok_or_else(|| error("expected ':' after function signature"))?;
+        let return_type = after[..colon]
+            .trim()
+            .strip_prefix("->")
+            .map(|ty| normalize_type(ty.trim()));
+
+        let rest_start = close + 1 + colon + 1;
+        let rest = text[rest_start..].trim_start();
+        let rest = (!rest.is_empty()).then(|| (rest, line.start + text.len() - rest.len()));
+
+        Ok(FunctionHeader {
+            name: text[4..open].trim().to_string(),
+            args,
+            return_type,
+            rest,
+        })
+    }
+
+    /// A module-level `name: type [= value]` declaration
+    fn variable(&self, line: &Line) -> Option<VyperVariable> {
+        let text = line.text.as_str();
+        let colon = find_top_level(text, |i| text.as_bytes()[i] == b':')?;
+        let name = text[..colon].trim();
+        if !is_identifier(name) || MODULE_DIRECTIVES.contains(&name) {
+            return None;
+        }
+
+        let declared = &text[colon + 1..];
+        let (mut type_name, value) = match find_top_level(declared, |i| declared.as_bytes()[i] == b'=') {
+            Some(equals) => (declared[..equals].trim(), Some(declared[equals + 1..].trim().to_string())),
+            None => (declared.trim(), None),
+        };
+
+        let mut kind = VyperVariableKind::Storage;
+        let mut public = false;
+        while let Some((wrapper, inner)) = strip_call(type_name) {
+            match wrapper {
+                "public" => public = true,
+                "constant" => kind = VyperVariableKind::Constant,
+                "immutable" => kind = VyperVariableKind::Immutable,
+                "transient" => kind = VyperVariableKind::Transient,
+                _ => break,
+            }
+            type_name = inner;
+        }
+
+        let span = self.span(line.start, line.start + text.len());
+        Some(VyperVariable {
+            name: name.to_string(),
+            type_name: normalize_type(type_name),
+            kind,
+            public,
+            value,
+            line_number: span.start_line,
+            span,
+        })
+    }
+
+    /// Statements of the lines `start..end`, all indented at the same level
+    fn statements(&self, start: usize, end: usize) -> Vec<VyperStatement> {
+        let mut statements = Vec::new();
+        let mut index = start;
+        while index < end {
+            let block_end = self.block_end(index, end);
+            let line = &self.lines[index];
+            if !is_docstring(&line.text) {
+                statements.push(self.statement(&line.text, line.start, index + 1, block_end));
+            }
+            index = block_end;
+        }
+        statements
+    }
+
+    /// Parse one statement whose text starts at byte `start` of the source;
+    /// block statements take the lines `body_start..body_end` as their body
+    fn statement(&self, text: &str, start: usize, body_start: usize, body_end: usize) -> VyperStatement {
+        let keyword_len = text.find(|c: char| !is_ident_char(c)).unwrap_or(text.len());
+        let (keyword, rest) = (&text[..keyword_len], text[keyword_len..].trim());
+        let optional = |rest: &str| (!rest.is_empty()).then(|| rest.to_string());
+
+        // Header of a block: the text up to its colon, plus any statement after the colon
+        let mut inline = None;
+        let mut header = |search_from: usize| -> String {
+            let colon = find_top_level(&text[search_from..], |i| text.as_bytes()[search_from + i] == b':')
+                .map_or(text.len(), |colon| search_from + colon);
+            let after = text.get(colon + 1..).unwrap_or("").trim_start();
+            if !after.is_empty() {
+                inline = Some((after, start + text.len() - after.len()));
+            }
+            text[search_from..colon].trim().to_string()
+        };
+
+        let kind = match keyword {
+            "pass" if rest.is_empty() => VyperStatementKind::Pass,
+            "break" if rest.is_empty() => VyperStatementKind::Break,
+            "continue" if rest.is_empty() => VyperStatementKind::Continue,
+            "return" => VyperStatementKind::Return { value: optional(rest) },
+            "raise" => VyperStatementKind::Raise { reason: optional(rest) },
+            "assert" => VyperStatementKind::Assert {
+                condition: rest.to_string(),
+            },
+            "log" => VyperStatementKind::Log {
+                event: rest[..rest.find('(').unwrap_or(rest.len())].trim().to_string(),
+            },
+            "if" => VyperStatementKind::If {
+                condition: header(keyword_len),
+            },
+            "elif" => VyperStatementKind::Elif {
+                condition: header(keyword_len),
+            },
+            "else" if rest.starts_with(':') => {
+                header(keyword_len);
+                VyperStatementKind::Else
+            }
+            "for" => {
+                let in_at = find_top_level(text, |i| text[i..].starts_with(" in ")).unwrap_or(text.len());
+                let target = text[keyword_len..in_at].trim();
+                let iterable = header((in_at + " in ".len()).min(text.len()));
+                let (variable, type_name) = match target.split_once(':') {
+                    Some((variable, type_name)) => (variable.trim(), Some(normalize_type(type_name.trim()))),
+                    None => (target, None),
+                };
+                VyperStatementKind::For {
+                    variable: variable.to_string(),
+                    type_name,
+                    iterable,
+                }
+            }
+            _ => simple_statement(text),
+        };
+
+        let mut body = Vec::new();
+        if matches!(
+            kind,
+            VyperStatementKind::If { .. }
+                | VyperStatementKind::Elif { .. }
+                | VyperStatementKind::Else
+                | VyperStatementKind::For { .. }
+        ) {
+            if let Some((inline_text, inline_start)) = inline {
+                body.push(self.statement(inline_text, inline_start, body_start, body_start));
+            }
+            body.extend(self.statements(body_start, body_end));
+        }
+
+        let span = self.span(start, start + text.len());
+        VyperStatement {
+            kind,
+            text: collapse_whitespace(text),
+            line_number: span.start_line,
+            span,
+            body,
+        }
+    }
+}
+
+struct FunctionHeader<'l> {
+    name: String,
+    args: Vec<VyperParam>,
+    return_type: Option<String>,
+    /// Text after the signature's colon and its byte offset in the source
+    rest: Option<(&'l str, usize)>,
+}
+
+/// An assignment, declaration or bare expression
+fn simple_statement(text: &str) -> VyperStatementKind {
+    let bytes = text.as_bytes();
+    let equals = find_top_level(text, |i| {
+        if bytes[i] != b'=' || i == 0 || bytes.get(i + 1) == Some(&b'=') {
+            return false;
+        }
+        match bytes[i - 1] {
+            b'=' | b'!' => false,
+            // `<=` and `>=` compare, `<<=` and `>>=` assign
+            c @ (b'<' | b'>') => i >= 2 && bytes[i - 2] == c,
+            _ => true,
+        }
+    });
+    let colon = find_top_level(text, |i| bytes[i] == b':');
+
+    if let Some(colon) = colon.filter(|colon| equals.is_none_or(|equals| *colon < equals)) {
+        let name = text[..colon].trim();
+        if is_identifier(name) {
+            let declared = &text[colon + 1..equals.unwrap_or(text.len())];
+            return VyperStatementKind::Declaration {
+                name: name.to_string(),
+                type_name: normalize_type(declared.trim()),
+                value: equals.map(|equals| text[equals + 1..].trim().to_string()),
+            };
+        }
+    }
+
+    if let Some(equals) = equals {
+        let operator = AUGMENTED_OPERATORS
+            .iter()
+            .find(|op| text[..=equals].ends_with(**op))
+            .copied()
+            .unwrap_or("=");
+        let operator_start = equals + 1 - operator.len();
+        return VyperStatementKind::Assignment {
+            target: text[..operator_start].trim().to_string(),
+            operator: operator.to_string(),
+            value: text[equals + 1..].trim().to_string(),
+        };
+    }
+
+    VyperStatementKind::Expression {
+        expression: collapse_whitespace(text),
+    }
+}
+
+/// Split `source` into logical lines, dropping blank lines and comments
+fn logical_lines(source: &str) -> Vec<Line> {
+    let bytes = source.as_bytes();
+    let mut cleaned = bytes.to_vec();
+    let mut lines = Vec::new();
+    let mut depth = 0usize;
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+
+    let mut push = |start: usize, end: usize, cleaned: &[u8]| {
+        let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+        lines.push(Line {
+            indent: start - line_start,
+            start,
+            text: String::from_utf8_lossy(&cleaned[start..end]).into_owned(),
+        });
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    cleaned[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            b'"' | b'\'' => {
+                start.get_or_insert(i);
+                i = string_end(bytes, i);
+                end = i;
+                continue;
+            }
+            // Backslash continuation joins the next line
+            b'\\' if bytes[i + 1..].starts_with(b"\n") || bytes[i + 1..].starts_with(b"\r\n") => {
+                i += if bytes[i + 1] == b'\n' { 2 } else { 3 };
+                continue;
+            }
+            b'\n' if depth == 0 => {
+                if let Some(line_start) = start.take() {
+                    push(line_start, end, &cleaned);
+                }
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if !bytes[i].is_ascii_whitespace() {
+            start.get_or_insert(i);
+            end = i + 1;
+        }
+        i += 1;
+    }
+    if let Some(line_start) = start {
+        push(line_start, end, &cleaned);
+    }
+
+    lines
+}
+
+/// End of the string literal whose opening quote is at `start`. Single-quoted
+/// strings stop at the end of the line even when unterminated.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let triple = bytes[start..].starts_with(&[quote; 3]);
+    let mut i = start + if triple { 3 } else { 1 };
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if !triple => return i,
+            b if b == quote && (!triple || bytes[i..].starts_with(&[quote; 3])) => {
+                return i + if triple { 3 } else { 1 };
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// First byte index outside strings and brackets for which `matches` holds
+fn find_top_level(text: &str, mut matches: impl FnMut(usize) -> bool) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' => {
+                i = string_end(bytes, i);
+                continue;
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && matches(i) => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Non-empty pieces of `text` between top-level separators, trimmed, with their offsets
+fn split_top_level(text: &str, separator: u8) -> Vec<(usize, &str)> {
+    let mut separators = Vec::new();
+    find_top_level(text, |i| {
+        if text.as_bytes()[i] == separator {
+            separators.push(i);
+        }
+        false
+    });
+
+    let mut pieces = Vec::new();
+    let mut piece_start = 0;
+    for piece_end in separators.into_iter().chain(std::iter::once(text.len())) {
+        let piece = &text[piece_start..piece_end];
+        let trimmed = piece.trim();
+        if !trimmed.is_empty() {
+            pieces.push((piece_start + piece.len() - piece.trim_start().len(), trimmed));
+        }
+        piece_start = piece_end + 1;
+    }
+    pieces
+}
+
+/// Index of the bracket closing the one at `open`
+fn matching_bracket(text: &str, open: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' => {
+                i = string_end(bytes, i);
+                continue;
+            }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split `wrapper(inner)` into its parts
+fn strip_call(text: &str) -> Option<(&str, &str)> {
+    let open = text.find('(')?;
+    let wrapper = text[..open].trim();
+    (is_identifier(wrapper) && matching_bracket(text, open) == Some(text.len() - 1))
+        .then(|| (wrapper, text[open + 1..text.len() - 1].trim()))
+}
+
+/// Name declared by a `keyword Name:` block header
+fn block_name(text: &str, keyword: &str) -> Option<String> {
+    let name = text.strip_prefix(keyword)?.strip_suffix(':')?;
+    (name.starts_with(char::is_whitespace) && is_identifier(name.trim())).then(|| name.trim().to_string())
+}
+
+/// Whether a line is a bare string literal, such as a docstring
+fn is_docstring(text: &str) -> bool {
+    text.starts_with(['"', '\'']) && string_end(text.as_bytes(), 0) == text.len()
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() && !text.starts_with(|c: char| c.is_ascii_digit()) && text.chars().all(is_ident_char)
+}
+
+/// Type name on one line, without padding inside brackets or trailing commas
+fn normalize_type(text: &str) -> String {
+    let mut normalized = collapse_whitespace(text);
+    for (from, to) in [("[ ", "["), ("( ", "("), (" ]", "]"), (" )", ")"), (",]", "]"), (",)", ")")] {
+        normalized = normalized.replace(from, to);
+    }
+    normalized
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 #[cfg(test)]
@@ -186,4 +935,127 @@ def _helper():
         ));
         assert!(!VyperContract::is_internal_naming_convention("__init__")); // Dunder methods excluded
     }
+
+    const TOKEN: &str = r#"# @version ^0.3.9
+"""
+@title Token
+"""
+from vyper.interfaces import ERC20
+
+implements: ERC20
+
+interface Oracle:
+    def price(asset: address) -> uint256: view
+    def update(): nonpayable
+
+event Transfer:
+    sender: indexed(address)
+    receiver: indexed(address)
+    value: uint256
+
+struct Checkpoint:
+    block: uint256
+    votes: uint256
+
+DECIMALS: constant(uint8) = 18
+owner: public(immutable(address))
+totalSupply: public(uint256)
+balanceOf: public(HashMap[
+    address,  # holder
+    uint256,
+])
+
+@external
+@nonreentrant("lock")
+def transfer(
+    _to: address,
+    _value: uint256 = 0,
+) -> bool:
+    """Move tokens"""
+    assert _to != empty(address), "zero address"
+    sender_balance: uint256 = self.balanceOf[msg.sender]
+    if _value > sender_balance:
+        raise "insufficient"
+    elif _value == 0:
+        return False
+    else:
+        self.balanceOf[msg.sender] = sender_balance - _value
+    for i: uint256 in range(10):
+        if i >= _value: break
+        self.totalSupply -= 1
+    self.balanceOf[_to] += _value
+    log Transfer(msg.sender, _to, _value)
+    return True
+"#;
+
+    #[test]
+    fn test_parse_declarations() {
+        let contract = VyperContract::parse(TOKEN).unwrap();
+
+        assert_eq!(contract.imports, vec!["from vyper.interfaces import ERC20"]);
+        let names: Vec<_> = contract.variables.iter().map(|v| (v.name.as_str(), v.type_name.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("DECIMALS", "uint8"),
+                ("owner", "address"),
+                ("totalSupply", "uint256"),
+                ("balanceOf", "HashMap[address, uint256]"),
+            ]
+        );
+        let decimals = contract.variable("DECIMALS").unwrap();
+        assert_eq!((decimals.kind, decimals.value.as_deref()), (VyperVariableKind::Constant, Some("18")));
+        let owner = contract.variable("owner").unwrap();
+        assert_eq!((owner.kind, owner.public), (VyperVariableKind::Immutable, true));
+        assert_eq!(contract.storage_variables().count(), 2);
+        assert_eq!(contract.variable("balanceOf").unwrap().span.end_line, 28);
+
+        let oracle = &contract.interfaces[0];
+        assert_eq!(oracle.name, "Oracle");
+        assert_eq!(oracle.functions[0].args[0].type_name, "address");
+        assert_eq!(oracle.functions[0].return_type.as_deref(), Some("uint256"));
+        assert_eq!(oracle.functions[1].mutability.as_deref(), Some("nonpayable"));
+
+        let transfer = &contract.events[0];
+        assert_eq!(transfer.fields.iter().filter(|f| f.indexed).count(), 2);
+        assert_eq!(transfer.fields[2].type_name, "uint256");
+        assert_eq!(contract.structs[0].fields[1].name, "votes");
+    }
+
+    #[test]
+    fn test_parse_function_signature_and_body() {
+        let contract = VyperContract::parse(TOKEN).unwrap();
+        let transfer = contract.function("transfer").unwrap();
+
+        assert_eq!(transfer.decorators, vec!["external", "nonreentrant"]);
+        assert_eq!(transfer.line_number, 30);
+        let args: Vec<_> = transfer.args.iter().map(|a| (a.name.as_str(), a.default.as_deref())).collect();
+        assert_eq!(args, vec![("_to", None), ("_value", Some("0"))]);
+        assert_eq!(transfer.args[1].span.snippet(TOKEN), "_value: uint256 = 0");
+        assert_eq!(transfer.return_type.as_deref(), Some("bool"));
+
+        let kinds: Vec<_> = transfer.body.iter().map(|s| std::mem::discriminant(&s.kind)).collect();
+        assert_eq!(kinds.len(), 9);
+        assert!(matches!(&transfer.body[1].kind, VyperStatementKind::Declaration { name, type_name, .. }
+            if name == "sender_balance" && type_name == "uint256"));
+        assert!(matches!(&transfer.body[3].kind, VyperStatementKind::Elif { condition } if condition == "_value == 0"));
+        assert!(matches!(&transfer.body[5].kind, VyperStatementKind::For { variable, type_name: Some(ty), iterable }
+            if variable == "i" && ty == "uint256" && iterable == "range(10)"));
+        assert!(matches!(&transfer.body[7].kind, VyperStatementKind::Log { event } if event == "Transfer"));
+
+        let loop_body = &transfer.body[5].body;
+        assert!(matches!(loop_body[0].body[0].kind, VyperStatementKind::Break));
+        assert!(matches!(&loop_body[1].kind, VyperStatementKind::Assignment { operator, .. } if operator == "-="));
+
+        let writes: Vec<_> = transfer.statements().into_iter().filter_map(|s| s.storage_write()).collect();
+        assert_eq!(writes, vec!["balanceOf", "totalSupply", "balanceOf"]);
+        assert_eq!(transfer.body[6].span.snippet(TOKEN), "self.balanceOf[_to] += _value");
+        assert_eq!(transfer.body[6].line_number, 48);
+    }
+
+    #[test]
+    fn test_malformed_function_is_an_error() {
+        let error = VyperContract::parse("\n@external\ndef broken:\n    pass\n").unwrap_err();
+        assert!(error.starts_with("Line 3:"), "{}", error);
+    }
 }
@@ -1,5 +1,6 @@
-use crate::rule_engine::{RuleViolation, ViolationSeverity};
+use crate::rule_engine::{Rule, RuleCategory, RuleInput, RuleMetadata, RuleViolation, ViolationSeverity};
 use crate::vyper::parser::{VyperContract, VyperFunction};
+use crate::{Applicability, Fix, Language, RuleRegistry, SuppressionReport, TextEdit};
 use std::collections::HashSet;
 
 /// Rule for detecting redundant @external decorators on internal Vyper functions
@@ -9,23 +10,24 @@ use std::collections::HashSet;
 /// 2. Functions that are only called internally via self.function()
 pub struct RedundantExternalDecoratorRule;
 
-/// Vyper-specific rule trait for analyzing Vyper contracts
-pub trait VyperRule {
-    fn name(&self) -> &str;
-    fn description(&self) -> &str;
-    fn check(&self, contract: &VyperContract) -> Vec<RuleViolation>;
-}
-
-impl VyperRule for RedundantExternalDecoratorRule {
-    fn name(&self) -> &str {
-        "vyper-redundant-external"
-    }
-
-    fn description(&self) -> &str {
-        "Detects internal functions that are accidentally marked as @external, which leads to higher gas consumption and potential security gaps."
+impl Rule for RedundantExternalDecoratorRule {
+    fn metadata(&self) -> &RuleMetadata {
+        static METADATA: RuleMetadata = RuleMetadata {
+            id: "vyper-redundant-external",
+            name: "Redundant External Decorator",
+            description: "Detects internal functions that are accidentally marked as @external, which leads to higher gas consumption and potential security gaps.",
+            default_severity: ViolationSeverity::Warning,
+            category: RuleCategory::Gas,
+            languages: &[Language::Vyper],
+            tags: &["visibility"],
+        };
+        &METADATA
     }
 
-    fn check(&self, contract: &VyperContract) -> Vec<RuleViolation> {
+    fn check(&self, input: &RuleInput<'_>) -> Vec<RuleViolation> {
+        let Some(contract) = input.vyper() else {
+            return Vec::new();
+        };
         let mut violations = Vec::new();
 
         // Get all functions that are called internally
@@ -64,45 +66,62 @@ impl VyperRule for RedundantExternalDecoratorRule {
 }
 
 impl RedundantExternalDecoratorRule {
+    /// Replace the function's `@external` decorator with `@internal`
+    fn internal_decorator_fix(&self, func: &VyperFunction, applicability: Applicability) -> Fix {
+        let edits = func
+            .decorator_span("external")
+            .map(|span| TextEdit::replace(span, "@internal"))
+            .into_iter()
+            .collect();
+        Fix::new(format!("Change @external to @internal on '{}'", func.name), applicability, edits)
+    }
+
     /// Create a violation for functions with internal naming convention but @external decorator
     fn create_naming_violation(&self, func: &VyperFunction) -> RuleViolation {
+        let span = func.decorator_span("external").unwrap_or(func.span);
         RuleViolation {
-            rule_name: self.name().to_string(),
+            rule_name: self.id().to_string(),
             description: format!(
                 "Function '{}' is marked @external but uses internal naming convention (_prefix). \
                 This may expose internal logic unnecessarily and increase gas costs.",
                 func.name
             ),
-            severity: ViolationSeverity::Warning,
-            line_number: func.line_number,
-            column_number: func.column_number,
+            severity: self.severity(),
+            line_number: span.start_line,
+            column_number: span.start_column,
+            span,
             variable_name: func.name.clone(),
             suggestion: format!(
                 "Consider changing @external to @internal for function '{}'. \
                 Internal functions save gas by not generating external interface code and improve security by not exposing internal logic.",
                 func.name
             ),
+            fix: Some(self.internal_decorator_fix(func, Applicability::MaybeIncorrect)),
         }
     }
 
     /// Create a violation for functions only called internally but marked @external
     fn create_internal_usage_violation(&self, func: &VyperFunction) -> RuleViolation {
+        let span = func.decorator_span("external").unwrap_or(func.span);
         RuleViolation {
-            rule_name: self.name().to_string(),
+            rule_name: self.id().to_string(),
             description: format!(
                 "Function '{}' is marked @external but appears to only be called internally (via self.{}()). \
                 This wastes gas and may expose internal logic unnecessarily.",
                 func.name, func.name
             ),
-            severity: ViolationSeverity::Warning,
-            line_number: func.line_number,
-            column_number: func.column_number,
+            severity: self.severity(),
+            line_number: span.start_line,
+            column_number: span.start_column,
+            span,
             variable_name: func.name.clone(),
             suggestion: format!(
                 "Consider changing @external to @internal for function '{}' if it's not meant to be called externally. \
                 Internal functions are more gas-efficient and don't expose the function in the contract's ABI.",
                 func.name
             ),
+            // Vyper rejects `self.` calls to external functions, so this is safe
+            fix: Some(self.internal_decorator_fix(func, Applicability::MachineApplicable)),
         }
     }
 
@@ -155,33 +174,43 @@ impl RedundantExternalDecoratorRule {
 
 /// Vyper rule engine for running Vyper-specific rules
 pub struct VyperRuleEngine {
-    rules: Vec<Box<dyn VyperRule>>,
+    registry: RuleRegistry,
 }
 
 impl VyperRuleEngine {
     pub fn new() -> Self {
-        Self { rules: Vec::new() }
+        Self { registry: RuleRegistry::new() }
     }
 
     pub fn with_default_rules() -> Self {
         let mut engine = Self::new();
-        engine.add_rule(Box::new(RedundantExternalDecoratorRule));
+        for rule in Self::default_rules() {
+            engine.add_rule(rule);
+        }
         engine
     }
 
-    pub fn add_rule(&mut self, rule: Box<dyn VyperRule>) {
-        self.rules.push(rule);
+    /// The built-in Vyper rules
+    pub fn default_rules() -> Vec<Box<dyn Rule>> {
+        vec![Box::new(RedundantExternalDecoratorRule)]
     }
 
-    pub fn analyze(&self, source: &str) -> Result<Vec<RuleViolation>, String> {
-        let contract = VyperContract::parse(source)?;
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+        self.registry.register(rule);
+    }
 
-        let mut violations = Vec::new();
-        for rule in &self.rules {
-            violations.extend(rule.check(&contract));
-        }
+    pub fn get_rules(&self) -> Vec<&dyn Rule> {
+        self.registry.rules().collect()
+    }
+
+    pub fn analyze(&self, source: &str) -> Result<Vec<RuleViolation>, String> {
+        self.analyze_with_suppressions(source).map(|report| report.violations)
+    }
 
-        Ok(violations)
+    /// Analyze `source`, honouring `# gasguard-ignore` comments and keeping the
+    /// suppressed findings separately
+    pub fn analyze_with_suppressions(&self, source: &str) -> Result<SuppressionReport, String> {
+        self.registry.analyze(Language::Vyper, source, "")
     }
 }
 
@@ -218,6 +247,34 @@ def public_function() -> uint256:
             .contains("internal naming convention"));
     }
 
+    #[test]
+    fn test_violation_span_points_at_external_decorator() {
+        let source = "@view\n@external\ndef _peek() -> uint256:\n    return 1\n";
+        let engine = VyperRuleEngine::with_default_rules();
+        let violations = engine.analyze(source).unwrap();
+
+        assert_eq!(violations.len(), 1);
+        let span = violations[0].span;
+        assert_eq!((span.start_line, span.start_column), (2, 1));
+        assert_eq!(span.snippet(source), "@external");
+
+        let fix = violations[0].fix.as_ref().unwrap();
+        assert_eq!(fix.applicability, Applicability::MaybeIncorrect);
+        let fixed = crate::apply_edits(source, &fix.edits).unwrap();
+        assert_eq!(fixed, "@view\n@internal\ndef _peek() -> uint256:\n    return 1\n");
+    }
+
+    #[test]
+    fn test_inline_suppression() {
+        let source = "# gasguard-ignore: vyper-redundant-external\n@external\ndef _peek() -> uint256:\n    return 1\n";
+        let engine = VyperRuleEngine::with_default_rules();
+        let report = engine.analyze_with_suppressions(source).unwrap();
+
+        assert!(report.violations.is_empty());
+        assert_eq!(report.suppressed_count(), 1);
+        assert!(engine.analyze(source).unwrap().is_empty());
+    }
+
     #[test]
     fn test_no_violation_on_proper_internal() {
         let source = r#"
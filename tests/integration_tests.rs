@@ -1,5 +1,5 @@
 use gasguard_engine::{ContractScanner, ScanAnalyzer};
-use gasguard_rules::{SorobanParser, SorobanContract, SorobanAnalyzer, SorobanRuleEngine};
+use gasguard_rules::{SorobanParser, SorobanContract, SorobanRuleEngine};
 use std::path::Path;
 
 #[test]
@@ -215,7 +215,7 @@ impl TokenContract {
 }
 
 #[test]
-fn test_soroban_analyzer_unused_variables() {
+fn test_soroban_default_rules_unused_variables() {
     let contract_code = r#"
 use soroban_sdk::{contract, contractimpl, contracttype, Address};
 
@@ -242,11 +242,11 @@ impl TestContract {
 }
 "#;
 
-    let contract = SorobanParser::parse_contract(contract_code, "test.rs").unwrap();
-    let violations = SorobanAnalyzer::analyze_contract(&contract);
+    let engine = SorobanRuleEngine::with_default_rules();
+    let violations = engine.analyze(contract_code, "test.rs").unwrap();
     
     let unused_found = violations.iter().any(|v| 
-        v.rule_name == "unused-state-variable" && v.variable_name == "unused_counter"
+        v.rule_name == "soroban-unused-state-variables" && v.variable_name == "unused_counter"
     );
     assert!(unused_found);
 }